@@ -0,0 +1,452 @@
+use crate::AMDGPU::{DeviceHandle, AMDGPU_GPU_PAGE_SIZE};
+use crate::{query_error, Error};
+use crate::bindings::{self, amdgpu_bo_handle};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{BitOr, BitOrAssign, Deref, DerefMut};
+
+#[cfg(feature = "dynamic_loading")]
+use std::sync::Arc;
+#[cfg(feature = "dynamic_loading")]
+use crate::DynLibDrmAmdgpu;
+
+pub use bindings::{amdgpu_bo_info, amdgpu_bo_metadata};
+
+/// RAII wrapper for `amdgpu_bo_handle`, freed on drop.
+/// Allocate by [DeviceHandle::alloc_bo], or import by [DeviceHandle::import_bo].
+/// `BufferObject` borrows the [DeviceHandle], it cannot outlive the device.
+pub struct BufferObject<'dev> {
+    pub(crate) bo_handle: amdgpu_bo_handle,
+    pub(crate) size: u64,
+    #[cfg(feature = "dynamic_loading")]
+    pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
+    _dev: PhantomData<&'dev DeviceHandle>,
+}
+
+unsafe impl Send for BufferObject<'_> {}
+unsafe impl Sync for BufferObject<'_> {}
+
+impl DeviceHandle {
+    /// Allocate a buffer object.
+    /// `alignment` is the physical alignment in bytes (`0` for the default, page size).
+    pub fn alloc_bo(
+        &self,
+        size: u64,
+        alignment: u64,
+        domain: BoDomain,
+        flags: BoFlags,
+    ) -> Result<BufferObject<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_alloc;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_alloc;
+
+        if domain == BoDomain::Unknown {
+            return Err(Error::InvalidArgument("BoDomain::Unknown"));
+        }
+
+        let mut request = bindings::amdgpu_bo_alloc_request {
+            alloc_size: size,
+            phys_alignment: alignment,
+            preferred_heap: domain as u32,
+            flags: flags.0,
+        };
+
+        unsafe {
+            let mut bo_handle: MaybeUninit<amdgpu_bo_handle> = MaybeUninit::zeroed();
+
+            let r = func(self.amdgpu_dev, &mut request, bo_handle.as_mut_ptr());
+
+            query_error!(r, "amdgpu_bo_alloc");
+
+            /* the kernel driver allocates whole pages */
+            let size = size.next_multiple_of(AMDGPU_GPU_PAGE_SIZE);
+
+            Ok(BufferObject::new(self, bo_handle.assume_init(), size))
+        }
+    }
+
+    /// Import a buffer object from a dma-buf fd, a KMS handle or a GEM flink name.
+    pub fn import_bo(
+        &self,
+        handle_type: BoHandleType,
+        shared_handle: u32,
+    ) -> Result<BufferObject<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_import;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_import;
+
+        unsafe {
+            let mut result: MaybeUninit<bindings::amdgpu_bo_import_result> = MaybeUninit::zeroed();
+
+            let r = func(
+                self.amdgpu_dev,
+                handle_type as u32,
+                shared_handle,
+                result.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_bo_import");
+
+            let result = result.assume_init();
+
+            Ok(BufferObject::new(self, result.buf_handle, result.alloc_size))
+        }
+    }
+
+    /// Import a buffer object from a dma-buf fd
+    pub fn import_bo_from_dma_buf_fd(&self, fd: i32) -> Result<BufferObject<'_>, Error> {
+        self.import_bo(BoHandleType::DmaBufFd, fd as u32)
+    }
+}
+
+impl<'dev> BufferObject<'dev> {
+    fn new(_amdgpu_dev: &'dev DeviceHandle, bo_handle: amdgpu_bo_handle, size: u64) -> Self {
+        Self {
+            bo_handle,
+            size,
+            #[cfg(feature = "dynamic_loading")]
+            libdrm_amdgpu: _amdgpu_dev.libdrm_amdgpu.clone(),
+            _dev: PhantomData,
+        }
+    }
+
+    /// Allocation size in bytes, a multiple of [AMDGPU_GPU_PAGE_SIZE]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn query_info(&self) -> Result<amdgpu_bo_info, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_query_info;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_query_info;
+
+        unsafe {
+            let mut info: MaybeUninit<amdgpu_bo_info> = MaybeUninit::zeroed();
+
+            let r = func(self.bo_handle, info.as_mut_ptr());
+
+            query_error!(r, "amdgpu_bo_query_info");
+
+            Ok(info.assume_init())
+        }
+    }
+
+    pub fn domain(&self) -> Result<BoDomain, Error> {
+        let info = self.query_info()?;
+
+        Ok(BoDomain::from(info.preferred_heap))
+    }
+
+    pub fn flags(&self) -> Result<BoFlags, Error> {
+        let info = self.query_info()?;
+
+        Ok(BoFlags(info.alloc_flags))
+    }
+
+    pub fn metadata(&self) -> Result<amdgpu_bo_metadata, Error> {
+        let info = self.query_info()?;
+
+        Ok(info.metadata)
+    }
+
+    /// Set the tiling info and the UMD metadata shared with other processes
+    pub fn set_metadata(&self, metadata: &amdgpu_bo_metadata) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_set_metadata;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_set_metadata;
+
+        let mut metadata = *metadata;
+
+        let r = unsafe { func(self.bo_handle, &mut metadata) };
+
+        query_error!(r, "amdgpu_bo_set_metadata");
+
+        Ok(())
+    }
+
+    pub fn export(&self, handle_type: BoHandleType) -> Result<u32, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_export;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_export;
+
+        unsafe {
+            let mut shared_handle: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(self.bo_handle, handle_type as u32, shared_handle.as_mut_ptr());
+
+            query_error!(r, "amdgpu_bo_export");
+
+            Ok(shared_handle.assume_init())
+        }
+    }
+
+    /// Export as a dma-buf fd, the caller owns the returned fd.
+    pub fn export_dma_buf_fd(&self) -> Result<i32, Error> {
+        self.export(BoHandleType::DmaBufFd).map(|fd| fd as i32)
+    }
+
+    /// Get the KMS (GEM) handle, it is used for BO list and VA operations
+    pub fn kms_handle(&self) -> Result<u32, Error> {
+        self.export(BoHandleType::Kms)
+    }
+
+    /// Returns `true` if the buffer is still busy after `timeout_ns`
+    pub fn wait_for_idle(&self, timeout_ns: u64) -> Result<bool, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_wait_for_idle;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_wait_for_idle;
+
+        unsafe {
+            let mut busy: MaybeUninit<bool> = MaybeUninit::zeroed();
+
+            let r = func(self.bo_handle, timeout_ns, busy.as_mut_ptr());
+
+            query_error!(r, "amdgpu_bo_wait_for_idle");
+
+            Ok(busy.assume_init())
+        }
+    }
+
+    /// Map the buffer into the CPU address space.
+    /// The mapping is unmapped when [BoCpuMapping] is dropped.
+    pub fn cpu_map(&mut self) -> Result<BoCpuMapping<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_cpu_map;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_cpu_map;
+
+        unsafe {
+            let mut ptr: MaybeUninit<*mut ::core::ffi::c_void> = MaybeUninit::zeroed();
+
+            let r = func(self.bo_handle, ptr.as_mut_ptr());
+
+            query_error!(r, "amdgpu_bo_cpu_map");
+
+            let ptr = ptr.assume_init();
+
+            if ptr.is_null() {
+                return Err(Error::Query { func: "amdgpu_bo_cpu_map", query: None, errno: -libc::EFAULT });
+            }
+
+            Ok(BoCpuMapping { ptr: ptr as *mut u8, len: self.size as usize, bo: self })
+        }
+    }
+
+    unsafe fn cpu_unmap(&self) -> Result<(), Error> { unsafe {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_cpu_unmap;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_cpu_unmap;
+
+        let r = func(self.bo_handle);
+
+        query_error!(r, "amdgpu_bo_cpu_unmap");
+
+        Ok(())
+    }}
+
+    unsafe fn free(&self) -> Result<(), Error> { unsafe {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_free;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_free;
+
+        let r = func(self.bo_handle);
+
+        query_error!(r, "amdgpu_bo_free");
+
+        Ok(())
+    }}
+}
+
+impl Drop for BufferObject<'_> {
+    fn drop(&mut self) {
+        /* the BO handle is invalid after this, there is nothing to do on failure */
+        let _ = unsafe { self.free() };
+    }
+}
+
+/// CPU mapping of [BufferObject], borrowed from [BufferObject::cpu_map]
+pub struct BoCpuMapping<'a> {
+    ptr: *mut u8,
+    len: usize,
+    bo: &'a BufferObject<'a>,
+}
+
+impl Deref for BoCpuMapping<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl DerefMut for BoCpuMapping<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl Drop for BoCpuMapping<'_> {
+    fn drop(&mut self) {
+        let _ = unsafe { self.bo.cpu_unmap() };
+    }
+}
+
+use crate::bindings::{
+    AMDGPU_GEM_DOMAIN_CPU,
+    AMDGPU_GEM_DOMAIN_GTT,
+    AMDGPU_GEM_DOMAIN_VRAM,
+    AMDGPU_GEM_DOMAIN_GDS,
+    AMDGPU_GEM_DOMAIN_GWS,
+    AMDGPU_GEM_DOMAIN_OA,
+};
+
+const AMDGPU_GEM_DOMAIN_VRAM_GTT: u32 = AMDGPU_GEM_DOMAIN_VRAM | AMDGPU_GEM_DOMAIN_GTT;
+
+/// Preferred heap of [BufferObject]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BoDomain {
+    CPU = AMDGPU_GEM_DOMAIN_CPU,
+    GTT = AMDGPU_GEM_DOMAIN_GTT,
+    VRAM = AMDGPU_GEM_DOMAIN_VRAM,
+    GDS = AMDGPU_GEM_DOMAIN_GDS,
+    GWS = AMDGPU_GEM_DOMAIN_GWS,
+    OA = AMDGPU_GEM_DOMAIN_OA,
+    /// VRAM or GTT
+    VRAM_GTT = AMDGPU_GEM_DOMAIN_VRAM_GTT,
+    /// Returned by [BufferObject::domain] for an unknown heap, rejected by [DeviceHandle::alloc_bo]
+    Unknown = u32::MAX,
+}
+
+impl From<u32> for BoDomain {
+    fn from(val: u32) -> Self {
+        match val {
+            AMDGPU_GEM_DOMAIN_CPU => Self::CPU,
+            AMDGPU_GEM_DOMAIN_GTT => Self::GTT,
+            AMDGPU_GEM_DOMAIN_VRAM => Self::VRAM,
+            AMDGPU_GEM_DOMAIN_GDS => Self::GDS,
+            AMDGPU_GEM_DOMAIN_GWS => Self::GWS,
+            AMDGPU_GEM_DOMAIN_OA => Self::OA,
+            AMDGPU_GEM_DOMAIN_VRAM_GTT => Self::VRAM_GTT,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+use crate::bindings::{
+    AMDGPU_GEM_CREATE_CPU_ACCESS_REQUIRED,
+    AMDGPU_GEM_CREATE_NO_CPU_ACCESS,
+    AMDGPU_GEM_CREATE_CPU_GTT_USWC,
+    AMDGPU_GEM_CREATE_VRAM_CLEARED,
+    AMDGPU_GEM_CREATE_VRAM_CONTIGUOUS,
+    AMDGPU_GEM_CREATE_VM_ALWAYS_VALID,
+    AMDGPU_GEM_CREATE_EXPLICIT_SYNC,
+    AMDGPU_GEM_CREATE_VRAM_WIPE_ON_RELEASE,
+    AMDGPU_GEM_CREATE_ENCRYPTED,
+    AMDGPU_GEM_CREATE_DISCARDABLE,
+    AMDGPU_GEM_CREATE_COHERENT,
+    AMDGPU_GEM_CREATE_UNCACHED,
+};
+
+/// Allocation flags (`AMDGPU_GEM_CREATE_*`) of [BufferObject]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BoFlags(pub u64);
+
+impl BoFlags {
+    pub const NONE: Self = Self(0);
+    pub const CPU_ACCESS_REQUIRED: Self = Self(AMDGPU_GEM_CREATE_CPU_ACCESS_REQUIRED as u64);
+    pub const NO_CPU_ACCESS: Self = Self(AMDGPU_GEM_CREATE_NO_CPU_ACCESS as u64);
+    pub const CPU_GTT_USWC: Self = Self(AMDGPU_GEM_CREATE_CPU_GTT_USWC as u64);
+    pub const VRAM_CLEARED: Self = Self(AMDGPU_GEM_CREATE_VRAM_CLEARED as u64);
+    pub const VRAM_CONTIGUOUS: Self = Self(AMDGPU_GEM_CREATE_VRAM_CONTIGUOUS as u64);
+    pub const VM_ALWAYS_VALID: Self = Self(AMDGPU_GEM_CREATE_VM_ALWAYS_VALID as u64);
+    pub const EXPLICIT_SYNC: Self = Self(AMDGPU_GEM_CREATE_EXPLICIT_SYNC as u64);
+    pub const VRAM_WIPE_ON_RELEASE: Self = Self(AMDGPU_GEM_CREATE_VRAM_WIPE_ON_RELEASE as u64);
+    pub const ENCRYPTED: Self = Self(AMDGPU_GEM_CREATE_ENCRYPTED as u64);
+    pub const DISCARDABLE: Self = Self(AMDGPU_GEM_CREATE_DISCARDABLE as u64);
+    pub const COHERENT: Self = Self(AMDGPU_GEM_CREATE_COHERENT as u64);
+    pub const UNCACHED: Self = Self(AMDGPU_GEM_CREATE_UNCACHED as u64);
+
+    pub fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl BitOr for BoFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for BoFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+use crate::bindings::{
+    amdgpu_bo_handle_type_amdgpu_bo_handle_type_gem_flink_name,
+    amdgpu_bo_handle_type_amdgpu_bo_handle_type_kms,
+    amdgpu_bo_handle_type_amdgpu_bo_handle_type_dma_buf_fd,
+    amdgpu_bo_handle_type_amdgpu_bo_handle_type_kms_noimport,
+};
+
+/// Used for [BufferObject::export] and [DeviceHandle::import_bo]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BoHandleType {
+    GemFlinkName = amdgpu_bo_handle_type_amdgpu_bo_handle_type_gem_flink_name,
+    Kms = amdgpu_bo_handle_type_amdgpu_bo_handle_type_kms,
+    DmaBufFd = amdgpu_bo_handle_type_amdgpu_bo_handle_type_dma_buf_fd,
+    /// KMS handle, but not imported into the buffer table of libdrm
+    KmsNoImport = amdgpu_bo_handle_type_amdgpu_bo_handle_type_kms_noimport,
+}
+
+use std::fmt;
+impl fmt::Display for BoDomain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[test]
+fn test_bo_domain_flags() {
+    for domain in [
+        BoDomain::CPU,
+        BoDomain::GTT,
+        BoDomain::VRAM,
+        BoDomain::GDS,
+        BoDomain::GWS,
+        BoDomain::OA,
+        BoDomain::VRAM_GTT,
+    ] {
+        assert_eq!(BoDomain::from(domain as u32), domain);
+    }
+
+    assert_eq!(BoDomain::from(0), BoDomain::Unknown);
+    assert_eq!(BoDomain::from(AMDGPU_GEM_DOMAIN_CPU | AMDGPU_GEM_DOMAIN_GTT), BoDomain::Unknown);
+    assert_eq!(BoDomain::from(BoDomain::Unknown as u32), BoDomain::Unknown);
+
+    let mut flags = BoFlags::CPU_ACCESS_REQUIRED | BoFlags::VRAM_CLEARED;
+    flags |= BoFlags::EXPLICIT_SYNC;
+
+    assert!(flags.contains(BoFlags::VRAM_CLEARED | BoFlags::EXPLICIT_SYNC));
+    assert!(!flags.contains(BoFlags::NO_CPU_ACCESS));
+    assert!(flags.contains(BoFlags::NONE));
+    assert_eq!(
+        flags.0,
+        (AMDGPU_GEM_CREATE_CPU_ACCESS_REQUIRED
+            | AMDGPU_GEM_CREATE_VRAM_CLEARED
+            | AMDGPU_GEM_CREATE_EXPLICIT_SYNC) as u64,
+    );
+}
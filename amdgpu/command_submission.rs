@@ -0,0 +1,444 @@
+use crate::AMDGPU::{BufferObject, ContextHandle, HW_IP::HW_IP_TYPE};
+use crate::{query_error, Error};
+use crate::bindings::{
+    self,
+    amdgpu_context_handle,
+    amdgpu_cs_fence,
+    drm_amdgpu_bo_list_entry,
+    drm_amdgpu_cs_chunk,
+    drm_amdgpu_cs_chunk_dep,
+    drm_amdgpu_cs_chunk_ib,
+    drm_amdgpu_cs_chunk_sem,
+    drm_amdgpu_cs_chunk_syncobj,
+};
+use core::marker::PhantomData;
+use core::mem::{size_of_val, MaybeUninit};
+use core::ops::{BitOr, BitOrAssign};
+use std::fmt;
+
+#[cfg(feature = "dynamic_loading")]
+use std::sync::Arc;
+#[cfg(feature = "dynamic_loading")]
+use crate::DynLibDrmAmdgpu;
+
+/// Fence of the submitted command, returned by [CommandSubmission::submit].
+/// `Fence` borrows the [ContextHandle] used for the submission, it cannot outlive the context.
+#[derive(Clone)]
+pub struct Fence<'ctx> {
+    pub(crate) ctx_handle: amdgpu_context_handle,
+    pub ip_type: HW_IP_TYPE,
+    pub ip_instance: u32,
+    pub ring: u32,
+    /// Sequence number of the submission
+    pub seq_no: u64,
+    #[cfg(feature = "dynamic_loading")]
+    pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
+    pub(crate) _ctx: PhantomData<&'ctx ContextHandle<'ctx>>,
+}
+
+unsafe impl Send for Fence<'_> {}
+unsafe impl Sync for Fence<'_> {}
+
+impl fmt::Debug for Fence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Fence")
+            .field("ctx_handle", &self.ctx_handle)
+            .field("ip_type", &self.ip_type)
+            .field("ip_instance", &self.ip_instance)
+            .field("ring", &self.ring)
+            .field("seq_no", &self.seq_no)
+            .finish()
+    }
+}
+
+impl Fence<'_> {
+    pub fn as_raw(&self) -> amdgpu_cs_fence {
+        amdgpu_cs_fence {
+            context: self.ctx_handle,
+            ip_type: self.ip_type as u32,
+            ip_instance: self.ip_instance,
+            ring: self.ring,
+            fence: self.seq_no,
+        }
+    }
+
+    /// Returns `true` if the fence is signaled, without waiting
+    pub fn status(&self) -> Result<bool, Error> {
+        self.wait(0)
+    }
+
+    /// Wait for the fence up to `timeout_ns`, returns `false` on timeout
+    pub fn wait(&self, timeout_ns: u64) -> Result<bool, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_query_fence_status;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_query_fence_status;
+
+        unsafe {
+            let mut fence = self.as_raw();
+            let mut expired: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(&mut fence, timeout_ns, 0, expired.as_mut_ptr());
+
+            query_error!(r, "amdgpu_cs_query_fence_status");
+
+            Ok(expired.assume_init() != 0)
+        }
+    }
+
+    /// Wait for all (`wait_all`) or any of `fences` up to `timeout_ns`.
+    /// Returns the index of the first signaled fence, or `None` on timeout.
+    pub fn wait_fences(
+        fences: &[Fence<'_>],
+        wait_all: bool,
+        timeout_ns: u64,
+    ) -> Result<Option<u32>, Error> {
+        let Some(_fence) = fences.first() else {
+            return Err(Error::InvalidArgument("no fences to wait"));
+        };
+
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_wait_fences;
+        #[cfg(feature = "dynamic_loading")]
+        let func = _fence.libdrm_amdgpu.amdgpu_cs_wait_fences;
+
+        let mut raw: Vec<amdgpu_cs_fence> = fences.iter().map(|f| f.as_raw()).collect();
+
+        unsafe {
+            let mut status: MaybeUninit<u32> = MaybeUninit::zeroed();
+            let mut first: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(
+                raw.as_mut_ptr(),
+                raw.len() as u32,
+                wait_all,
+                timeout_ns,
+                status.as_mut_ptr(),
+                first.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_cs_wait_fences");
+
+            if status.assume_init() == 0 {
+                return Ok(None);
+            }
+
+            Ok(Some(first.assume_init()))
+        }
+    }
+}
+
+/// Builder for a command submission (`amdgpu_cs_submit_raw2`)
+///
+/// ```no_run
+/// # use libdrm_amdgpu_sys::AMDGPU::{*, HW_IP::HW_IP_TYPE};
+/// # fn submit(ctx: &ContextHandle, ib: &BufferObject, ib_va: u64, ib_size_dw: u32) {
+/// let fence = CommandSubmission::new(HW_IP_TYPE::GFX, 0)
+///     .ib(ib_va, ib_size_dw, IbFlags::NONE)
+///     .bo(ib, 0)
+///     .submit(ctx)
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CommandSubmission<'a> {
+    ip_type: HW_IP_TYPE,
+    ip_instance: u32,
+    ring: u32,
+    /// `(va_start, size_dw, flags)`
+    ibs: Vec<(u64, u32, IbFlags)>,
+    bos: Vec<(&'a BufferObject<'a>, u32)>,
+    dependencies: Vec<amdgpu_cs_fence>,
+    syncobj_in: Vec<drm_amdgpu_cs_chunk_sem>,
+    syncobj_out: Vec<drm_amdgpu_cs_chunk_sem>,
+    timeline_wait: Vec<drm_amdgpu_cs_chunk_syncobj>,
+    timeline_signal: Vec<drm_amdgpu_cs_chunk_syncobj>,
+}
+
+impl<'a> CommandSubmission<'a> {
+    pub fn new(ip_type: HW_IP_TYPE, ring: u32) -> Self {
+        Self {
+            ip_type,
+            ip_instance: 0,
+            ring,
+            ibs: Vec::new(),
+            bos: Vec::new(),
+            dependencies: Vec::new(),
+            syncobj_in: Vec::new(),
+            syncobj_out: Vec::new(),
+            timeline_wait: Vec::new(),
+            timeline_signal: Vec::new(),
+        }
+    }
+
+    pub fn ip_instance(mut self, ip_instance: u32) -> Self {
+        self.ip_instance = ip_instance;
+        self
+    }
+
+    /// Add an indirect buffer at GPU virtual address `va_start` with `size_dw` dwords
+    pub fn ib(mut self, va_start: u64, size_dw: u32, flags: IbFlags) -> Self {
+        self.ibs.push((va_start, size_dw, flags));
+        self
+    }
+
+    /// Add a buffer object used by the submission to the BO list
+    pub fn bo(mut self, bo: &'a BufferObject<'a>, priority: u32) -> Self {
+        self.bos.push((bo, priority));
+        self
+    }
+
+    /// Wait for `fence` before executing the submission
+    pub fn dependency(mut self, fence: &Fence<'_>) -> Self {
+        self.dependencies.push(fence.as_raw());
+        self
+    }
+
+    /// Wait for the binary syncobj `handle` ([SyncObj::handle](crate::AMDGPU::SyncObj::handle)) before executing the submission
+    pub fn syncobj_in(mut self, handle: u32) -> Self {
+        self.syncobj_in.push(drm_amdgpu_cs_chunk_sem { handle });
+        self
+    }
+
+    /// Signal the binary syncobj `handle` when the submission completes
+    pub fn syncobj_out(mut self, handle: u32) -> Self {
+        self.syncobj_out.push(drm_amdgpu_cs_chunk_sem { handle });
+        self
+    }
+
+    /// Wait for `point` of the timeline syncobj `handle` before executing the submission
+    pub fn timeline_wait(mut self, handle: u32, point: u64, flags: u32) -> Self {
+        self.timeline_wait.push(drm_amdgpu_cs_chunk_syncobj { handle, flags, point });
+        self
+    }
+
+    /// Signal `point` of the timeline syncobj `handle` when the submission completes
+    pub fn timeline_signal(mut self, handle: u32, point: u64) -> Self {
+        self.timeline_signal.push(drm_amdgpu_cs_chunk_syncobj { handle, flags: 0, point });
+        self
+    }
+
+    /// The IP type, instance and ring are resolved at submission
+    fn ib_chunks(&self) -> Result<Vec<drm_amdgpu_cs_chunk_ib>, Error> {
+        self.ibs.iter().map(|&(va_start, size_dw, flags)| Ok(drm_amdgpu_cs_chunk_ib {
+            _pad: 0,
+            flags: flags.0,
+            va_start,
+            ib_bytes: size_dw.checked_mul(4).ok_or(Error::InvalidArgument("IB size overflows u32"))?,
+            ip_type: self.ip_type as u32,
+            ip_instance: self.ip_instance,
+            ring: self.ring,
+        })).collect()
+    }
+
+    /// The returned chunks point to the data in `ibs`, `self` and `deps`
+    fn chunks(
+        &self,
+        ibs: &[drm_amdgpu_cs_chunk_ib],
+        deps: &[drm_amdgpu_cs_chunk_dep],
+    ) -> Vec<drm_amdgpu_cs_chunk> {
+        fn chunk<T>(chunk_id: u32, data: &[T]) -> drm_amdgpu_cs_chunk {
+            drm_amdgpu_cs_chunk {
+                chunk_id,
+                length_dw: (size_of_val(data) / 4) as u32,
+                chunk_data: data.as_ptr() as u64,
+            }
+        }
+
+        let mut chunks: Vec<_> = ibs
+            .iter()
+            .map(|ib| chunk(bindings::AMDGPU_CHUNK_ID_IB, core::slice::from_ref(ib)))
+            .collect();
+
+        if !deps.is_empty() {
+            chunks.push(chunk(bindings::AMDGPU_CHUNK_ID_DEPENDENCIES, deps));
+        }
+        if !self.syncobj_in.is_empty() {
+            chunks.push(chunk(bindings::AMDGPU_CHUNK_ID_SYNCOBJ_IN, &self.syncobj_in));
+        }
+        if !self.syncobj_out.is_empty() {
+            chunks.push(chunk(bindings::AMDGPU_CHUNK_ID_SYNCOBJ_OUT, &self.syncobj_out));
+        }
+        if !self.timeline_wait.is_empty() {
+            chunks.push(chunk(bindings::AMDGPU_CHUNK_ID_SYNCOBJ_TIMELINE_WAIT, &self.timeline_wait));
+        }
+        if !self.timeline_signal.is_empty() {
+            chunks.push(chunk(bindings::AMDGPU_CHUNK_ID_SYNCOBJ_TIMELINE_SIGNAL, &self.timeline_signal));
+        }
+
+        chunks
+    }
+
+    pub fn submit<'ctx>(&self, ctx: &'ctx ContextHandle<'_>) -> Result<Fence<'ctx>, Error> {
+        #[cfg(feature = "link_drm")]
+        let (func, fence_to_dep) = (bindings::amdgpu_cs_submit_raw2, bindings::amdgpu_cs_chunk_fence_to_dep);
+        #[cfg(feature = "dynamic_loading")]
+        let (func, fence_to_dep) = (
+            ctx.libdrm_amdgpu.amdgpu_cs_submit_raw2,
+            ctx.libdrm_amdgpu.amdgpu_cs_chunk_fence_to_dep,
+        );
+
+        if self.ibs.is_empty() {
+            return Err(Error::InvalidArgument("command submission without IB"));
+        }
+
+        let ibs = self.ib_chunks()?;
+
+        let deps: Vec<drm_amdgpu_cs_chunk_dep> = self.dependencies.iter().map(|fence| unsafe {
+            let mut raw = *fence;
+            let mut dep: MaybeUninit<drm_amdgpu_cs_chunk_dep> = MaybeUninit::zeroed();
+
+            fence_to_dep(&mut raw, dep.as_mut_ptr());
+
+            dep.assume_init()
+        }).collect();
+        let mut chunks = self.chunks(&ibs, &deps);
+
+        let bo_list = BoList::new(ctx, &self.bos)?;
+
+        unsafe {
+            let mut seq_no: MaybeUninit<u64> = MaybeUninit::zeroed();
+
+            let r = func(
+                ctx.amdgpu_dev,
+                ctx.ctx_handle,
+                bo_list.handle,
+                chunks.len() as i32,
+                chunks.as_mut_ptr(),
+                seq_no.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_cs_submit_raw2");
+
+            Ok(Fence {
+                ctx_handle: ctx.ctx_handle,
+                ip_type: self.ip_type,
+                ip_instance: self.ip_instance,
+                ring: self.ring,
+                seq_no: seq_no.assume_init(),
+                #[cfg(feature = "dynamic_loading")]
+                libdrm_amdgpu: ctx.libdrm_amdgpu.clone(),
+                _ctx: PhantomData,
+            })
+        }
+    }
+}
+
+/// BO list for a submission, destroyed on drop
+struct BoList<'a> {
+    ctx: &'a ContextHandle<'a>,
+    handle: u32,
+}
+
+impl<'a> BoList<'a> {
+    fn new(ctx: &'a ContextHandle<'a>, bos: &[(&BufferObject<'_>, u32)]) -> Result<Self, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_list_create_raw;
+        #[cfg(feature = "dynamic_loading")]
+        let func = ctx.libdrm_amdgpu.amdgpu_bo_list_create_raw;
+
+        /* 0 is "no BO list" */
+        if bos.is_empty() {
+            return Ok(Self { ctx, handle: 0 });
+        }
+
+        let mut entries = bos.iter().map(|(bo, bo_priority)| Ok(drm_amdgpu_bo_list_entry {
+            bo_handle: bo.kms_handle()?,
+            bo_priority: *bo_priority,
+        })).collect::<Result<Vec<_>, Error>>()?;
+
+        unsafe {
+            let mut handle: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(
+                ctx.amdgpu_dev,
+                entries.len() as u32,
+                entries.as_mut_ptr(),
+                handle.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_bo_list_create_raw");
+
+            Ok(Self { ctx, handle: handle.assume_init() })
+        }
+    }
+}
+
+impl Drop for BoList<'_> {
+    fn drop(&mut self) {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_list_destroy_raw;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.ctx.libdrm_amdgpu.amdgpu_bo_list_destroy_raw;
+
+        if self.handle != 0 {
+            unsafe { func(self.ctx.amdgpu_dev, self.handle); }
+        }
+    }
+}
+
+use crate::bindings::{
+    AMDGPU_IB_FLAG_CE,
+    AMDGPU_IB_FLAG_PREAMBLE,
+    AMDGPU_IB_FLAG_PREEMPT,
+    AMDGPU_IB_FLAG_TC_WB_NOT_INVALIDATE,
+    AMDGPU_IB_FLAG_RESET_GDS_MAX_WAVE_ID,
+    AMDGPU_IB_FLAGS_SECURE,
+    AMDGPU_IB_FLAG_EMIT_MEM_SYNC,
+};
+
+/// Flags (`AMDGPU_IB_FLAG_*`) for [CommandSubmission::ib]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IbFlags(pub u32);
+
+impl IbFlags {
+    pub const NONE: Self = Self(0);
+    pub const CE: Self = Self(AMDGPU_IB_FLAG_CE);
+    pub const PREAMBLE: Self = Self(AMDGPU_IB_FLAG_PREAMBLE);
+    pub const PREEMPT: Self = Self(AMDGPU_IB_FLAG_PREEMPT);
+    pub const TC_WB_NOT_INVALIDATE: Self = Self(AMDGPU_IB_FLAG_TC_WB_NOT_INVALIDATE);
+    pub const RESET_GDS_MAX_WAVE_ID: Self = Self(AMDGPU_IB_FLAG_RESET_GDS_MAX_WAVE_ID);
+    pub const SECURE: Self = Self(AMDGPU_IB_FLAGS_SECURE);
+    pub const EMIT_MEM_SYNC: Self = Self(AMDGPU_IB_FLAG_EMIT_MEM_SYNC);
+}
+
+impl BitOr for IbFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for IbFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[test]
+fn test_cs_chunks() {
+    let cs = CommandSubmission::new(HW_IP_TYPE::COMPUTE, 1)
+        .ib(0x1000, 16, IbFlags::NONE)
+        .ib(0x2000, 8, IbFlags::PREAMBLE)
+        .ip_instance(2)
+        .syncobj_out(3)
+        .timeline_signal(4, 10);
+    let ibs = cs.ib_chunks().unwrap();
+    let chunks = cs.chunks(&ibs, &[]);
+
+    assert_eq!(chunks.len(), 4);
+    assert_eq!(chunks[0].chunk_id, bindings::AMDGPU_CHUNK_ID_IB);
+    assert_eq!(chunks[0].length_dw, 8);
+    assert_eq!(ibs[0].ib_bytes, 64);
+    assert_eq!(ibs[0].ip_instance, 2);
+    assert_eq!(ibs[1].ring, 1);
+    assert_eq!(chunks[2].chunk_id, bindings::AMDGPU_CHUNK_ID_SYNCOBJ_OUT);
+    assert_eq!(chunks[2].length_dw, 1);
+    assert_eq!(chunks[3].chunk_id, bindings::AMDGPU_CHUNK_ID_SYNCOBJ_TIMELINE_SIGNAL);
+    assert_eq!(chunks[3].length_dw, 4);
+
+    let cs = CommandSubmission::new(HW_IP_TYPE::GFX, 0).ib(0x1000, u32::MAX, IbFlags::NONE);
+
+    assert!(matches!(cs.ib_chunks(), Err(Error::InvalidArgument(_))));
+}
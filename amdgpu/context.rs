@@ -1,6 +1,7 @@
-use crate::AMDGPU::DeviceHandle;
-use crate::query_error;
+use crate::AMDGPU::{DeviceHandle, DEVICE_HANDLE};
+use crate::{query_error, Error};
 use crate::bindings::{self, amdgpu_context_handle};
+use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 
 #[cfg(feature = "dynamic_loading")]
@@ -8,14 +9,17 @@ use std::sync::Arc;
 #[cfg(feature = "dynamic_loading")]
 use crate::DynLibDrmAmdgpu;
 
-pub struct ContextHandle {
+/// `ContextHandle` borrows the [DeviceHandle], it cannot outlive the device.
+pub struct ContextHandle<'dev> {
     pub(crate) ctx_handle: amdgpu_context_handle,
+    pub(crate) amdgpu_dev: DEVICE_HANDLE,
     #[cfg(feature = "dynamic_loading")]
     pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
+    _dev: PhantomData<&'dev DeviceHandle>,
 }
 
 impl DeviceHandle {
-    pub fn create_context(&self) -> Result<ContextHandle, i32> {
+    pub fn create_context(&self) -> Result<ContextHandle<'_>, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_cs_ctx_create;
         #[cfg(feature = "dynamic_loading")]
@@ -26,25 +30,45 @@ impl DeviceHandle {
 
             let r = func(self.amdgpu_dev, ctx_handle.as_mut_ptr());
 
-            let ctx_handle = ContextHandle::new(&self, ctx_handle.assume_init());
+            query_error!(r, "amdgpu_cs_ctx_create");
 
-            query_error!(r);
+            Ok(ContextHandle::new(self, ctx_handle.assume_init()))
+        }
+    }
+
+    pub fn create_context_with_priority(
+        &self,
+        priority: ContextPriority,
+    ) -> Result<ContextHandle<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_ctx_create2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_ctx_create2;
+
+        unsafe {
+            let mut ctx_handle: MaybeUninit<amdgpu_context_handle> = MaybeUninit::zeroed();
+
+            let r = func(self.amdgpu_dev, priority as i32 as u32, ctx_handle.as_mut_ptr());
+
+            query_error!(r, "amdgpu_cs_ctx_create2");
 
-            Ok(ctx_handle)
+            Ok(ContextHandle::new(self, ctx_handle.assume_init()))
         }
     }
 }
 
-impl ContextHandle {
-    pub fn new(_amdgpu_dev: &DeviceHandle, ctx_handle: amdgpu_context_handle) -> Self {
+impl<'dev> ContextHandle<'dev> {
+    pub fn new(amdgpu_dev: &'dev DeviceHandle, ctx_handle: amdgpu_context_handle) -> Self {
         Self {
             ctx_handle,
+            amdgpu_dev: amdgpu_dev.amdgpu_dev,
             #[cfg(feature = "dynamic_loading")]
-            libdrm_amdgpu: _amdgpu_dev.libdrm_amdgpu.clone(),
+            libdrm_amdgpu: amdgpu_dev.libdrm_amdgpu.clone(),
+            _dev: PhantomData,
         }
     }
 
-    unsafe fn free(&self) -> Result<(), i32> { unsafe {
+    unsafe fn free(&self) -> Result<(), Error> { unsafe {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_cs_ctx_free;
         #[cfg(feature = "dynamic_loading")]
@@ -52,7 +76,7 @@ impl ContextHandle {
 
         let r = func(self.ctx_handle);
 
-        query_error!(r);
+        query_error!(r, "amdgpu_cs_ctx_free");
 
         Ok(())
     }}
@@ -61,7 +85,7 @@ impl ContextHandle {
         &self,
         op: u32,
         pstate_flag: StablePstateFlag,
-    ) -> Result<StablePstateFlag, i32> {
+    ) -> Result<StablePstateFlag, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_cs_ctx_stable_pstate;
         #[cfg(feature = "dynamic_loading")]
@@ -79,7 +103,7 @@ impl ContextHandle {
 
             let out_flags = out_flags.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_cs_ctx_stable_pstate");
 
             Ok(out_flags.into())
         }
@@ -88,16 +112,52 @@ impl ContextHandle {
     pub fn set_stable_pstate(
         &self,
         pstate_flag: StablePstateFlag,
-    ) -> Result<StablePstateFlag, i32> {
+    ) -> Result<StablePstateFlag, Error> {
         self.stable_pstate(bindings::AMDGPU_CTX_OP_SET_STABLE_PSTATE, pstate_flag)
     }
 
-    pub fn get_stable_pstate(&self) -> Result<StablePstateFlag, i32> {
+    pub fn get_stable_pstate(&self) -> Result<StablePstateFlag, Error> {
         self.stable_pstate(bindings::AMDGPU_CTX_OP_GET_STABLE_PSTATE, StablePstateFlag::NONE)
     }
+
+    /// Override the priority of the context.
+    /// `master_fd` is the fd of the DRM master (e.g. `/dev/dri/card0` opened by a compositor)
+    pub fn override_priority(
+        &self,
+        master_fd: i32,
+        priority: ContextPriority,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_ctx_override_priority;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_ctx_override_priority;
+
+        let r = unsafe { func(self.amdgpu_dev, self.ctx_handle, master_fd, priority as i32 as u32) };
+
+        query_error!(r, "amdgpu_cs_ctx_override_priority");
+
+        Ok(())
+    }
+
+    pub fn query_reset_state(&self) -> Result<ResetState, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_query_reset_state2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_query_reset_state2;
+
+        unsafe {
+            let mut flags: MaybeUninit<u64> = MaybeUninit::zeroed();
+
+            let r = func(self.ctx_handle, flags.as_mut_ptr());
+
+            query_error!(r, "amdgpu_cs_query_reset_state2");
+
+            Ok(ResetState::from(flags.assume_init()))
+        }
+    }
 }
 
-impl Drop for ContextHandle {
+impl Drop for ContextHandle<'_> {
     fn drop(&mut self) {
         unsafe { self.free().unwrap(); }
     }
@@ -134,3 +194,84 @@ impl From<u32> for StablePstateFlag {
         }
     }
 }
+
+use crate::bindings::{
+    AMDGPU_CTX_PRIORITY_VERY_LOW,
+    AMDGPU_CTX_PRIORITY_LOW,
+    AMDGPU_CTX_PRIORITY_NORMAL,
+    AMDGPU_CTX_PRIORITY_HIGH,
+    AMDGPU_CTX_PRIORITY_VERY_HIGH,
+};
+
+/// Scheduler priority of the context.
+/// `HIGH` and `VERY_HIGH` require `CAP_SYS_NICE` or DRM master.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ContextPriority {
+    VERY_LOW = AMDGPU_CTX_PRIORITY_VERY_LOW,
+    LOW = AMDGPU_CTX_PRIORITY_LOW,
+    NORMAL = AMDGPU_CTX_PRIORITY_NORMAL as i32,
+    HIGH = AMDGPU_CTX_PRIORITY_HIGH as i32,
+    VERY_HIGH = AMDGPU_CTX_PRIORITY_VERY_HIGH as i32,
+}
+
+use crate::bindings::{
+    AMDGPU_CTX_QUERY2_FLAGS_RESET,
+    AMDGPU_CTX_QUERY2_FLAGS_VRAMLOST,
+    AMDGPU_CTX_QUERY2_FLAGS_GUILTY,
+    AMDGPU_CTX_QUERY2_FLAGS_RAS_CE,
+    AMDGPU_CTX_QUERY2_FLAGS_RAS_UE,
+    AMDGPU_CTX_QUERY2_FLAGS_RESET_IN_PROGRESS,
+};
+
+/// Reset state of the context, decoded from `AMDGPU_CTX_QUERY2_FLAGS_*`
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct ResetState {
+    /// The GPU was reset after the context was created
+    pub reset: bool,
+    /// VRAM contents were lost by the reset
+    pub vram_lost: bool,
+    /// The context caused the reset
+    pub guilty: bool,
+    /// RAS correctable error occurred
+    pub ras_ce: bool,
+    /// RAS uncorrectable error occurred
+    pub ras_ue: bool,
+    pub reset_in_progress: bool,
+}
+
+impl ResetState {
+    /// The context is unusable and must be recreated
+    pub fn is_lost(&self) -> bool {
+        self.reset || self.vram_lost || self.guilty
+    }
+}
+
+impl From<u64> for ResetState {
+    fn from(flags: u64) -> Self {
+        let has = |flag: u32| (flags & flag as u64) != 0;
+
+        Self {
+            reset: has(AMDGPU_CTX_QUERY2_FLAGS_RESET),
+            vram_lost: has(AMDGPU_CTX_QUERY2_FLAGS_VRAMLOST),
+            guilty: has(AMDGPU_CTX_QUERY2_FLAGS_GUILTY),
+            ras_ce: has(AMDGPU_CTX_QUERY2_FLAGS_RAS_CE),
+            ras_ue: has(AMDGPU_CTX_QUERY2_FLAGS_RAS_UE),
+            reset_in_progress: has(AMDGPU_CTX_QUERY2_FLAGS_RESET_IN_PROGRESS),
+        }
+    }
+}
+
+#[test]
+fn test_reset_state() {
+    let state = ResetState::from(0x7);
+
+    assert!(state.reset && state.vram_lost && state.guilty);
+    assert!(!state.reset_in_progress);
+    assert!(state.is_lost());
+
+    let state = ResetState::from(0x20);
+
+    assert!(state.reset_in_progress);
+    assert!(!state.is_lost());
+}
@@ -0,0 +1,213 @@
+use crate::AMDGPU::{self, DeviceHandle, PowerCap};
+use crate::{Error, LibDrmAmdgpu, PCI, SysfsRoot};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Enumerates the devices bound to the amdgpu driver.
+/// Only sysfs is read, so a suspended dGPU is not woken up.
+pub struct DeviceEnumerator {
+    root: SysfsRoot,
+    pci_devs: std::vec::IntoIter<PCI::BUS_INFO>,
+}
+
+impl DeviceEnumerator {
+    /// [SysfsRoot::global] is used as the root
+    pub fn new() -> Self {
+        Self::new_with_root(SysfsRoot::global())
+    }
+
+    pub fn new_with_root(root: SysfsRoot) -> Self {
+        let mut pci_devs = AMDGPU::get_all_amdgpu_pci_bus_with_root(&root);
+
+        pci_devs.sort_by_key(|pci| (pci.domain, pci.bus, pci.dev, pci.func));
+
+        Self { root, pci_devs: pci_devs.into_iter() }
+    }
+}
+
+impl Default for DeviceEnumerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for DeviceEnumerator {
+    type Item = DeviceDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pci = self.pci_devs.next()?;
+
+        Some(DeviceDescriptor::from_pci_with_root(pci, &self.root))
+    }
+}
+
+/// Device information collected from sysfs
+pub struct DeviceDescriptor {
+    pub pci: PCI::BUS_INFO,
+    pub render_path: Option<PathBuf>,
+    pub card_path: Option<PathBuf>,
+    pub sysfs_path: PathBuf,
+    pub hwmon_path: Option<PathBuf>,
+    pub device_id: Option<u32>,
+    pub revision_id: Option<u32>,
+    /// Marketing name from [crate::bindings::AMDGPU_IDS], or [AMDGPU::DEFAULT_DEVICE_NAME]
+    pub marketing_name: String,
+    /// Runtime PM state when enumerated (`power/runtime_status` is `active`)
+    pub is_active: bool,
+    /// The second die of Aldebaran (MI200), it does not have power cap
+    pub is_secondary_die: bool,
+    /// `current_compute_partition` (e.g. `SPX`, `CPX`), MI300+
+    pub compute_partition: Option<String>,
+    /// `current_memory_partition` (e.g. `NPS1`), MI300+
+    pub memory_partition: Option<String>,
+    root: SysfsRoot,
+    handle: OnceLock<DeviceHandle>,
+}
+
+impl DeviceDescriptor {
+    pub fn from_pci(pci: PCI::BUS_INFO) -> Self {
+        Self::from_pci_with_root(pci, &SysfsRoot::global())
+    }
+
+    pub fn from_pci_with_root(pci: PCI::BUS_INFO, root: &SysfsRoot) -> Self {
+        let sysfs_path = pci.get_sysfs_path_with_root(root);
+        let hwmon_path = pci.get_hwmon_path_with_root(root).ok();
+        let device_id = pci.get_device_id_with_root(root);
+        let revision_id = pci.get_revision_id_with_root(root);
+        let marketing_name = device_id
+            .zip(revision_id)
+            .and_then(|(did, rid)| AMDGPU::find_device_name(did, rid))
+            .unwrap_or(AMDGPU::DEFAULT_DEVICE_NAME.to_string());
+        let is_secondary_die = hwmon_path
+            .as_ref()
+            .and_then(|path| PowerCap::from_hwmon_path(path).ok())
+            .is_some_and(|cap| cap.check_if_secondary_die());
+        let read_partition = |name: &str| {
+            std::fs::read_to_string(sysfs_path.join(name))
+                .ok()
+                .map(|s| s.trim_end().to_string())
+        };
+
+        Self {
+            pci,
+            render_path: pci.get_drm_render_path_with_root(root).ok(),
+            card_path: pci.get_drm_card_path_with_root(root).ok(),
+            hwmon_path: hwmon_path.clone(),
+            device_id,
+            revision_id,
+            marketing_name,
+            is_active: pci.check_if_device_is_active_with_root(root),
+            is_secondary_die,
+            compute_partition: read_partition("current_compute_partition"),
+            memory_partition: read_partition("current_memory_partition"),
+            sysfs_path,
+            root: root.clone(),
+            handle: OnceLock::new(),
+        }
+    }
+
+    /// Returns the [DeviceHandle] if it is already opened
+    pub fn get_device_handle(&self) -> Option<&DeviceHandle> {
+        self.handle.get()
+    }
+
+    /// Opens the render node (or the card node) on first use.
+    /// Opening the DRM node resumes a suspended dGPU,
+    /// use [DeviceDescriptor::device_handle_if_active] to avoid it.
+    pub fn device_handle(&self, libdrm_amdgpu: &LibDrmAmdgpu) -> Result<&DeviceHandle, Error> {
+        if let Some(handle) = self.handle.get() {
+            return Ok(handle);
+        }
+
+        let handle = self.open(libdrm_amdgpu)?;
+
+        /* another thread may have opened it first, then `handle` is dropped */
+        let _ = self.handle.set(handle);
+
+        Ok(self.handle.get().unwrap())
+    }
+
+    /// Returns `Ok(None)` if the device is runtime-suspended and not opened yet
+    pub fn device_handle_if_active(
+        &self,
+        libdrm_amdgpu: &LibDrmAmdgpu,
+    ) -> Result<Option<&DeviceHandle>, Error> {
+        if self.handle.get().is_none() && !self.check_if_device_is_active() {
+            return Ok(None);
+        }
+
+        self.device_handle(libdrm_amdgpu).map(Some)
+    }
+
+    /// Reads the current runtime PM state, it does not wake up the device
+    pub fn check_if_device_is_active(&self) -> bool {
+        self.pci.check_if_device_is_active_with_root(&self.root)
+    }
+
+    fn open(&self, libdrm_amdgpu: &LibDrmAmdgpu) -> Result<DeviceHandle, Error> {
+        use std::fs::File;
+        use std::os::fd::AsRawFd;
+
+        let path = self.render_path.as_ref().or(self.card_path.as_ref())
+            .ok_or(Error::Unsupported { path: self.sysfs_path.join("drm"), source: None })?;
+        let fd = File::open(path).map_err(|e| Error::io(path, e))?;
+        /* `fd` is closed on drop if the initialization fails */
+        let (mut handle, _major, _minor) = libdrm_amdgpu.init_device_handle(fd.as_raw_fd())?;
+
+        /* closed when `handle` is dropped */
+        handle.owned_fd = Some(fd.into());
+
+        Ok(handle)
+    }
+}
+
+impl std::fmt::Debug for DeviceDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("DeviceDescriptor")
+            .field("pci", &self.pci)
+            .field("render_path", &self.render_path)
+            .field("card_path", &self.card_path)
+            .field("sysfs_path", &self.sysfs_path)
+            .field("hwmon_path", &self.hwmon_path)
+            .field("device_id", &self.device_id)
+            .field("revision_id", &self.revision_id)
+            .field("marketing_name", &self.marketing_name)
+            .field("is_active", &self.is_active)
+            .field("is_secondary_die", &self.is_secondary_die)
+            .field("compute_partition", &self.compute_partition)
+            .field("memory_partition", &self.memory_partition)
+            .field("opened", &self.handle.get().is_some())
+            .finish()
+    }
+}
+
+#[test]
+fn test_device_enumerator() {
+    let root = crate::test_util::TempDir::new("device_enumerator");
+    let sysfs_root = SysfsRoot::new(root.path());
+    let pci_bus: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let dev_path = sysfs_root.pci_device_path(&pci_bus);
+
+    std::fs::create_dir_all(sysfs_root.amdgpu_driver_path().join(pci_bus.to_string())).unwrap();
+    std::fs::create_dir_all(dev_path.join("drm/card1")).unwrap();
+    std::fs::create_dir_all(dev_path.join("drm/renderD128")).unwrap();
+    std::fs::create_dir_all(dev_path.join("power")).unwrap();
+    std::fs::write(dev_path.join("device"), "0x73bf\n").unwrap();
+    std::fs::write(dev_path.join("revision"), "0xc1\n").unwrap();
+    std::fs::write(dev_path.join("power/runtime_status"), "suspended\n").unwrap();
+
+    let devs: Vec<_> = DeviceEnumerator::new_with_root(sysfs_root.clone()).collect();
+
+    assert_eq!(devs.len(), 1);
+
+    let dev = &devs[0];
+
+    assert_eq!(dev.pci, pci_bus);
+    assert_eq!(dev.render_path, Some(sysfs_root.dri.join("renderD128")));
+    assert_eq!(dev.card_path, Some(sysfs_root.dri.join("card1")));
+    assert_eq!(dev.device_id, Some(0x73BF));
+    assert_eq!(dev.revision_id, Some(0xC1));
+    assert!(!dev.is_active && !dev.is_secondary_die);
+    assert_eq!(dev.compute_partition, None);
+    assert!(dev.get_device_handle().is_none());
+}
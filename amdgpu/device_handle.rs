@@ -41,15 +41,19 @@ pub struct DeviceHandle {
     pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
     pub(crate) amdgpu_dev: DEVICE_HANDLE,
     pub(crate) fd: i32,
+    /// Set if the DRM node was opened by this crate ([crate::AMDGPU::DeviceDescriptor::device_handle]),
+    /// closed after `amdgpu_device_deinitialize`
+    pub(crate) owned_fd: Option<OwnedFd>,
 }
 
 unsafe impl Send for DeviceHandle {}
 unsafe impl Sync for DeviceHandle {}
 
+use std::os::fd::OwnedFd;
 use std::path::PathBuf;
 
 impl LibDrmAmdgpu {
-    pub fn init_device_handle(&self, fd: i32) -> Result<(DeviceHandle, u32, u32), i32> {
+    pub fn init_device_handle(&self, fd: i32) -> Result<(DeviceHandle, u32, u32), Error> {
         #[cfg(feature = "link_drm")]
         let init = bindings::amdgpu_device_initialize;
         #[cfg(feature = "dynamic_loading")]
@@ -67,6 +71,9 @@ impl LibDrmAmdgpu {
                 amdgpu_dev.as_mut_ptr(),
             );
 
+            /* check before constructing `DeviceHandle`, its `Drop` deinitializes the handle */
+            query_error!(r, "amdgpu_device_initialize");
+
             let [major, minor] = [major.assume_init(), minor.assume_init()];
             let device_handle = DeviceHandle {
                 #[cfg(feature = "dynamic_loading")]
@@ -75,10 +82,9 @@ impl LibDrmAmdgpu {
                 libdrm_amdgpu: self.libdrm_amdgpu.clone(),
                 amdgpu_dev: amdgpu_dev.assume_init(),
                 fd,
+                owned_fd: None,
             };
 
-            query_error!(r);
-
             Ok((device_handle, major, minor))
         }
     }
@@ -91,7 +97,7 @@ impl DeviceHandle {
     /// for GUI context.  
     /// ref: <https://gitlab.freedesktop.org/mesa/mesa/-/issues/2424>
     #[cfg(feature = "link_drm")]
-    pub fn init(fd: i32) -> Result<(Self, u32, u32), i32> {
+    pub fn init(fd: i32) -> Result<(Self, u32, u32), Error> {
         unsafe {
             let mut amdgpu_dev: MaybeUninit<amdgpu_device_handle> = MaybeUninit::zeroed();
             let mut major: MaybeUninit<u32> = MaybeUninit::zeroed();
@@ -104,19 +110,21 @@ impl DeviceHandle {
                 amdgpu_dev.as_mut_ptr(),
             );
 
+            /* check before constructing `DeviceHandle`, its `Drop` deinitializes the handle */
+            query_error!(r, "amdgpu_device_initialize");
+
             let [major, minor] = [major.assume_init(), minor.assume_init()];
             let device_handle = Self {
                 amdgpu_dev: amdgpu_dev.assume_init(),
                 fd,
+                owned_fd: None,
             };
 
-            query_error!(r);
-
             Ok((device_handle, major, minor))
         }
     }
 
-    fn deinit(&self) -> Result<i32, i32> {
+    fn deinit(&self) -> Result<i32, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_device_deinitialize;
         #[cfg(feature = "dynamic_loading")]
@@ -124,7 +132,7 @@ impl DeviceHandle {
 
         let r = unsafe { func(self.amdgpu_dev) };
 
-        query_error!(r);
+        query_error!(r, "amdgpu_device_deinitialize");
 
         Ok(r)
     }
@@ -134,8 +142,8 @@ impl DeviceHandle {
     }
 
     /// Returns the result of reading the register at the specified offset.
-    /// If the offset is not allowed, returns `Err(i32)`.
-    pub fn read_mm_registers(&self, offset: u32) -> Result<u32, i32> {
+    /// If the offset is not allowed, returns `Err(Error::Query)`.
+    pub fn read_mm_registers(&self, offset: u32) -> Result<u32, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_read_mm_registers;
         #[cfg(feature = "dynamic_loading")]
@@ -155,13 +163,13 @@ impl DeviceHandle {
 
             let out = out.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_read_mm_registers", offset);
 
             Ok(out)
         }
     }
 
-    pub fn query_gpu_info(&self) -> Result<amdgpu_gpu_info, i32> {
+    pub fn query_gpu_info(&self) -> Result<amdgpu_gpu_info, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_gpu_info;
         #[cfg(feature = "dynamic_loading")]
@@ -174,13 +182,13 @@ impl DeviceHandle {
 
             let gpu_info = gpu_info.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_gpu_info");
 
             Ok(gpu_info)
         }
     }
 
-    pub fn query_gds_info(&self) -> Result<amdgpu_gds_resource_info, i32> {
+    pub fn query_gds_info(&self) -> Result<amdgpu_gds_resource_info, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_gds_info;
         #[cfg(feature = "dynamic_loading")]
@@ -193,13 +201,13 @@ impl DeviceHandle {
 
             let gds_info = gds_info.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_gds_info");
 
             Ok(gds_info)
         }
     }
 
-    pub fn query_sw_info(&self, info: amdgpu_sw_info) -> Result<u32, i32> {
+    pub fn query_sw_info(&self, info: amdgpu_sw_info) -> Result<u32, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_sw_info;
         #[cfg(feature = "dynamic_loading")]
@@ -216,13 +224,13 @@ impl DeviceHandle {
 
             let val = val.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_sw_info", info);
 
             Ok(val)
         }
     }
 
-    pub(crate) fn query<T>(&self, info_id: ::core::ffi::c_uint) -> Result<T, i32> {
+    pub(crate) fn query<T>(&self, info_id: ::core::ffi::c_uint) -> Result<T, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_info;
         #[cfg(feature = "dynamic_loading")]
@@ -240,72 +248,72 @@ impl DeviceHandle {
 
             let dev = dev.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_info", info_id);
 
             Ok(dev)
         }
     }
 
-    pub fn device_info(&self) -> Result<drm_amdgpu_info_device, i32> {
+    pub fn device_info(&self) -> Result<drm_amdgpu_info_device, Error> {
         Self::query(self, AMDGPU_INFO_DEV_INFO)
     }
 
     /// Note: `usable_heap_size` equal `real_size - pin_size - reserved_size`, is not fixed.
-    pub fn vram_gtt_info(&self) -> Result<drm_amdgpu_info_vram_gtt, i32> {
+    pub fn vram_gtt_info(&self) -> Result<drm_amdgpu_info_vram_gtt, Error> {
         Self::query(self, AMDGPU_INFO_VRAM_GTT)
     }
 
-    pub fn memory_info(&self) -> Result<drm_amdgpu_memory_info, i32> {
+    pub fn memory_info(&self) -> Result<drm_amdgpu_memory_info, Error> {
         Self::query(self, AMDGPU_INFO_MEMORY)
     }
 
-    pub fn vram_usage_info(&self) -> Result<u64, i32> {
+    pub fn vram_usage_info(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_VRAM_USAGE)
     }
 
-    pub fn vis_vram_usage_info(&self) -> Result<u64, i32> {
+    pub fn vis_vram_usage_info(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_VIS_VRAM_USAGE)
     }
 
-    pub fn gtt_usage_info(&self) -> Result<u64, i32> {
+    pub fn gtt_usage_info(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_GTT_USAGE)
     }
 
-    pub fn gds_info(&self) -> Result<drm_amdgpu_info_gds, i32> {
+    pub fn gds_info(&self) -> Result<drm_amdgpu_info_gds, Error> {
         Self::query(self, AMDGPU_INFO_GDS_CONFIG)
     }
 
     /// AMDGPU driver returns invalid [drm_amdgpu_info_vce_clock_table].
     /// ref: <https://gitlab.freedesktop.org/drm/amd/-/issues/2391>
-    pub fn vce_clock_info(&self) -> Result<drm_amdgpu_info_vce_clock_table, i32> {
+    pub fn vce_clock_info(&self) -> Result<drm_amdgpu_info_vce_clock_table, Error> {
         Self::query(self, AMDGPU_INFO_VCE_CLOCK_TABLE)
     }
 
     /// Number of VRAM page faults on CPU access
-    pub fn num_vram_cpu_page_faults(&self) -> Result<u64, i32> {
+    pub fn num_vram_cpu_page_faults(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_NUM_VRAM_CPU_PAGE_FAULTS)
     }
 
     /// Number of bytes moved for TTM migration
-    pub fn num_bytes_moved(&self) -> Result<u64, i32> {
+    pub fn num_bytes_moved(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_NUM_BYTES_MOVED)
     }
 
     /// Number of TTM buffer evictions
-    pub fn num_evictions(&self) -> Result<u64, i32> {
+    pub fn num_evictions(&self) -> Result<u64, Error> {
         Self::query(self, AMDGPU_INFO_NUM_EVICTIONS)
     }
 
-    pub fn vram_lost_counter(&self) -> Result<u32, i32> {
+    pub fn vram_lost_counter(&self) -> Result<u32, Error> {
         Self::query(self, AMDGPU_INFO_VRAM_LOST_COUNTER)
     }
 
     /// Get [PCI::BUS_INFO]
-    pub fn get_pci_bus_info(&self) -> Result<PCI::BUS_INFO, i32> {
+    pub fn get_pci_bus_info(&self) -> Result<PCI::BUS_INFO, Error> {
         self.drm_get_device2()
     }
 
-    fn drm_get_device2(&self) -> Result<PCI::BUS_INFO, i32> {
+    fn drm_get_device2(&self) -> Result<PCI::BUS_INFO, Error> {
         let pci = unsafe {
             let mut dev_info = self.__drmGetDevice2(self.fd, 0)?;
             let pci = core::ptr::read((*dev_info).businfo.pci);
@@ -322,7 +330,7 @@ impl DeviceHandle {
         })
     }
 
-    unsafe fn __drmGetDevice2(&self, fd: ::core::ffi::c_int, flags: u32) -> Result<drmDevicePtr, i32> { unsafe {
+    unsafe fn __drmGetDevice2(&self, fd: ::core::ffi::c_int, flags: u32) -> Result<drmDevicePtr, Error> { unsafe {
         #[cfg(feature = "link_drm")]
         let func = bindings::drmGetDevice2;
         #[cfg(feature = "dynamic_loading")]
@@ -335,10 +343,10 @@ impl DeviceHandle {
         let drm_dev_info = drm_dev_info.assume_init();
 
         if drm_dev_info.is_null() {
-            return Err(r);
+            return Err(Error::Query { func: "drmGetDevice2", query: None, errno: r });
         }
 
-        query_error!(r);
+        query_error!(r, "drmGetDevice2");
 
         Ok(drm_dev_info)
     }}
@@ -355,12 +363,9 @@ impl DeviceHandle {
     fn get_min_max_clock_from_dpm<P: Into<PathBuf>>(
         &self,
         sysfs_path: P,
+        type_: AMDGPU::DpmClockType,
     ) -> Option<[u32; 2]> {
-        let parse_line = |s: &str| -> Option<u32> {
-            s.split(' ').nth(1)?.trim_end_matches("Mhz").parse::<u32>().ok()
-        };
-
-        get_min_max_from_dpm(sysfs_path.into(), parse_line)
+        AMDGPU::DpmClockTable::from_sysfs(type_, sysfs_path).ok()?.min_max_clock()
     }
 
     /// Get the min/max gpu core clock (MHz) from sysfs (`pp_dpm_mclk`)
@@ -368,7 +373,7 @@ impl DeviceHandle {
         &self,
         path: P
     ) -> Option<[u32; 2]> {
-        self.get_min_max_clock_from_dpm(path.into().join("pp_dpm_mclk"))
+        self.get_min_max_clock_from_dpm(path, AMDGPU::DpmClockType::MCLK)
     }
 
     /// Get the min/max gpu core clock (MHz) from sysfs (`pp_dpm_sclk`)
@@ -376,7 +381,7 @@ impl DeviceHandle {
         &self,
         path: P
     ) -> Option<[u32; 2]> {
-        self.get_min_max_clock_from_dpm(path.into().join("pp_dpm_sclk"))
+        self.get_min_max_clock_from_dpm(path, AMDGPU::DpmClockType::SCLK)
     }
 
     /// Get the min/max gpu core clock (MHz) from sysfs (`pp_dpm_mclk`)
@@ -384,7 +389,7 @@ impl DeviceHandle {
         &self,
         path: P
     ) -> Option<(u32, u32)> {
-        let tmp = self.get_min_max_clock_from_dpm(path.into().join("pp_dpm_mclk"))?;
+        let tmp = self.get_min_max_memory_clock_from_dpm(path)?;
 
         Some((tmp[0], tmp[1]))
     }
@@ -400,7 +405,7 @@ impl DeviceHandle {
         &self,
         path: P
     ) -> Option<(u32, u32)> {
-        let tmp = self.get_min_max_clock_from_dpm(path.into().join("pp_dpm_sclk"))?;
+        let tmp = self.get_min_max_gpu_clock_from_dpm(path)?;
 
         Some((tmp[0], tmp[1]))
     }
@@ -412,39 +417,39 @@ impl DeviceHandle {
     }
 
     /// 
-    pub fn get_sysfs_path(&self) -> Result<PathBuf, i32> {
+    pub fn get_sysfs_path(&self) -> Result<PathBuf, Error> {
         let path = self.get_pci_bus_info()?.get_sysfs_path();
 
         Ok(path)
     }
 
     /// 
-    pub fn get_hwmon_path(&self) -> Option<PathBuf> {
-        self.get_pci_bus_info().ok()?.get_hwmon_path()
+    pub fn get_hwmon_path(&self) -> Result<PathBuf, Error> {
+        self.get_pci_bus_info()?.get_hwmon_path()
     }
 
     /// ref: drivers/gpu/drm/amd/pm/swsmu/smu13/aldebaran_ppt.c
     /// ref: <https://github.com/RadeonOpenCompute/rocm_smi_lib/blob/master/python_smi_tools/rocm_smi.py>
     pub fn check_if_secondary_die(&self) -> bool {
-        let Some(power_cap) = self.get_power_cap() else { return false };
+        let Ok(power_cap) = self.get_power_cap() else { return false };
 
         power_cap.check_if_secondary_die()
     }
 
-    pub fn get_min_max_link_info_from_dpm(&self) -> Option<[PCI::LINK; 2]> {
-        let pci_bus = self.get_pci_bus_info().ok()?;
+    pub fn get_min_max_link_info_from_dpm(&self) -> Result<[PCI::LINK; 2], Error> {
+        let pci_bus = self.get_pci_bus_info()?;
 
         pci_bus.get_min_max_link_info_from_dpm()
     }
 
-    pub fn get_max_gpu_link(&self) -> Option<PCI::LINK> {
-        let pci_bus = self.get_pci_bus_info().ok()?;
+    pub fn get_max_gpu_link(&self) -> Result<PCI::LINK, Error> {
+        let pci_bus = self.get_pci_bus_info()?;
 
         pci_bus.get_max_gpu_link()
     }
 
-    pub fn get_max_system_link(&self) -> Option<PCI::LINK> {
-        let pci_bus = self.get_pci_bus_info().ok()?;
+    pub fn get_max_system_link(&self) -> Result<PCI::LINK, Error> {
+        let pci_bus = self.get_pci_bus_info()?;
 
         pci_bus.get_max_system_link()
     }
@@ -453,6 +458,7 @@ impl DeviceHandle {
 impl Drop for DeviceHandle {
     fn drop(&mut self) {
         self.deinit().unwrap();
+        /* `owned_fd` is closed after this */
     }
 }
 
@@ -466,6 +472,7 @@ impl drm_amdgpu_memory_info {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 #[repr(u32)]
 pub enum amdgpu_sw_info {
     address32_hi = 0,
@@ -0,0 +1,253 @@
+use crate::AMDGPU::DeviceHandle;
+use crate::{Error, PCI};
+use crate::error::{read_sysfs, write_sysfs};
+use std::path::PathBuf;
+
+impl DeviceHandle {
+    /// Read all supported `pp_dpm_*` files.
+    /// Files that do not exist, are empty or fail to parse are skipped.
+    pub fn dpm_tables(&self) -> Result<Vec<DpmClockTable>, Error> {
+        let sysfs_path = self.get_sysfs_path()?;
+        let mut tables = Vec::with_capacity(DpmClockType::ALL.len());
+
+        for type_ in DpmClockType::ALL {
+            match DpmClockTable::from_sysfs(type_, &sysfs_path) {
+                Ok(table) => tables.push(table),
+                Err(e) if e.is_unsupported() => continue,
+                Err(Error::Parse { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(tables)
+    }
+}
+
+/// DPM clock domains exposed as `pp_dpm_*`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DpmClockType {
+    SCLK,
+    MCLK,
+    FCLK,
+    SOCCLK,
+    DCEFCLK,
+    VCLK,
+    DCLK,
+    VCLK1,
+    DCLK1,
+    PCIE,
+}
+
+impl DpmClockType {
+    pub const ALL: [Self; 10] = [
+        Self::SCLK,
+        Self::MCLK,
+        Self::FCLK,
+        Self::SOCCLK,
+        Self::DCEFCLK,
+        Self::VCLK,
+        Self::DCLK,
+        Self::VCLK1,
+        Self::DCLK1,
+        Self::PCIE,
+    ];
+
+    pub const fn file_name(&self) -> &str {
+        match self {
+            Self::SCLK => "pp_dpm_sclk",
+            Self::MCLK => "pp_dpm_mclk",
+            Self::FCLK => "pp_dpm_fclk",
+            Self::SOCCLK => "pp_dpm_socclk",
+            Self::DCEFCLK => "pp_dpm_dcefclk",
+            Self::VCLK => "pp_dpm_vclk",
+            Self::DCLK => "pp_dpm_dclk",
+            Self::VCLK1 => "pp_dpm_vclk1",
+            Self::DCLK1 => "pp_dpm_dclk1",
+            Self::PCIE => "pp_dpm_pcie",
+        }
+    }
+
+    /// Number of DPM levels (`N: xxxMhz` lines), the deep sleep level (`S:`) is excluded
+    pub fn get_level_count<P: Into<PathBuf>>(&self, sysfs_path: P) -> Result<usize, Error> {
+        let s = read_sysfs(sysfs_path.into().join(self.file_name()))?;
+
+        Ok(s.lines().filter(|line| Self::parse_level_index(line).is_some()).count())
+    }
+
+    /// Restrict the DPM levels to `levels` (e.g. `&[2]` to pin the clock to level 2), requires root.
+    /// [crate::AMDGPU::DpmForcedLevel::Manual] must be set before this.
+    pub fn force_levels<P: Into<PathBuf>>(&self, sysfs_path: P, levels: &[u32]) -> Result<(), Error> {
+        let sysfs_path = sysfs_path.into();
+        let count = self.get_level_count(&sysfs_path)? as u32;
+
+        if levels.is_empty() {
+            return Err(Error::InvalidArgument("no DPM level"));
+        }
+
+        if let Some(level) = levels.iter().find(|level| **level >= count) {
+            return Err(Error::OutOfRange {
+                value: *level as i64,
+                min: 0,
+                max: count as i64 - 1,
+            });
+        }
+
+        let arg = levels.iter().map(|level| level.to_string()).collect::<Vec<_>>().join(" ");
+
+        write_sysfs(sysfs_path.join(self.file_name()), &arg)
+    }
+
+    fn parse_level_index(line: &str) -> Option<u32> {
+        let (index, _) = line.split_once(':')?;
+
+        index.trim().parse().ok()
+    }
+}
+
+/// A line of `pp_dpm_*`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DpmLevel {
+    /// `None` for the deep sleep level (`S:`)
+    pub index: Option<u32>,
+    /// MHz, `pp_dpm_pcie` has LCLK on SMU11+
+    pub clock: Option<u32>,
+    /// `pp_dpm_pcie` only
+    pub link: Option<PCI::LINK>,
+    /// Marked with `*`
+    pub is_current: bool,
+}
+
+impl DpmLevel {
+    /// `1: 1800Mhz *`, `S: 19Mhz`, `0: 2.5GT/s, x8 619Mhz *`
+    pub fn parse(line: &str, type_: DpmClockType) -> Option<Self> {
+        let line = line.trim();
+        let (index, values) = line.split_once(':')?;
+        let index = match index.trim() {
+            "S" => None,
+            i => Some(i.parse().ok()?),
+        };
+        let clock = values
+            .split_whitespace()
+            .find_map(|v| v.strip_suffix("Mhz").or(v.strip_suffix("MHz"))?.parse().ok());
+        let link = if type_ == DpmClockType::PCIE {
+            /* `LINK::parse_dpm_line` expects a space after the width */
+            Some(PCI::LINK::parse_dpm_line(&format!("{line} "))?)
+        } else {
+            None
+        };
+
+        if clock.is_none() && link.is_none() {
+            return None;
+        }
+
+        Some(Self { index, clock, link, is_current: line.ends_with('*') })
+    }
+}
+
+/// Parsed `pp_dpm_*`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DpmClockTable {
+    pub type_: DpmClockType,
+    pub levels: Vec<DpmLevel>,
+    /// `S:` level (Navi2x+, APUs)
+    pub deep_sleep: Option<DpmLevel>,
+}
+
+impl DpmClockTable {
+    pub fn from_sysfs<P: Into<PathBuf>>(type_: DpmClockType, sysfs_path: P) -> Result<Self, Error> {
+        let path = sysfs_path.into().join(type_.file_name());
+        let s = read_sysfs(&path)?;
+
+        Self::parse(type_, &s).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    /// Returns `None` if there is no level
+    pub fn parse(type_: DpmClockType, s: &str) -> Option<Self> {
+        let mut levels = Vec::new();
+        let mut deep_sleep = None;
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let level = DpmLevel::parse(line, type_)?;
+
+            if level.index.is_some() {
+                levels.push(level);
+            } else {
+                deep_sleep = Some(level);
+            }
+        }
+
+        if levels.is_empty() {
+            return None;
+        }
+
+        Some(Self { type_, levels, deep_sleep })
+    }
+
+    /// The level marked with `*`, including the deep sleep level
+    pub fn current(&self) -> Option<&DpmLevel> {
+        self.levels.iter().chain(self.deep_sleep.as_ref()).find(|level| level.is_current)
+    }
+
+    /// `[min, max]` clock (MHz) of the levels, the deep sleep level is excluded
+    pub fn min_max_clock(&self) -> Option<[u32; 2]> {
+        let clocks = self.levels.iter().filter_map(|level| level.clock);
+
+        Some([clocks.clone().min()?, clocks.max()?])
+    }
+}
+
+use std::fmt;
+impl fmt::Display for DpmClockType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[test]
+fn test_dpm_clock_table() {
+    let table = DpmClockTable::parse(
+        DpmClockType::SCLK,
+        "S: 19Mhz *\n0: 500Mhz\n1: 1800Mhz\n2: 2600Mhz\n",
+    ).unwrap();
+
+    assert_eq!(table.levels.len(), 3);
+    assert_eq!(table.current(), table.deep_sleep.as_ref());
+    assert_eq!(table.min_max_clock(), Some([500, 2600]));
+    assert_eq!(DpmClockTable::parse(DpmClockType::SCLK, "\n"), None);
+
+    let table = DpmClockTable::parse(
+        DpmClockType::PCIE,
+        "0: 2.5GT/s, x1 310Mhz \n1: 16.0GT/s, x16 619Mhz *\n",
+    ).unwrap();
+    let cur = table.current().unwrap();
+
+    assert_eq!(cur.index, Some(1));
+    assert_eq!(cur.clock, Some(619));
+    assert_eq!(cur.link, Some(PCI::LINK { r#gen: 4, width: 16 }));
+
+    /* SMU7 */
+    let table = DpmClockTable::parse(DpmClockType::PCIE, "0: 2.5GT/s, x8 \n1: 8.0GT/s, x16 *\n").unwrap();
+
+    assert_eq!(table.current().unwrap().clock, None);
+    assert_eq!(table.levels[0].link, Some(PCI::LINK { r#gen: 1, width: 8 }));
+}
+
+#[test]
+fn test_force_dpm_levels() {
+    let tmp = crate::test_util::TempDir::new("dpm_clock");
+    let dir = tmp.path();
+    let file = dir.join(DpmClockType::SCLK.file_name());
+
+    std::fs::write(&file, "S: 19Mhz\n0: 500Mhz\n1: 1800Mhz *\n2: 2600Mhz\n").unwrap();
+
+    assert_eq!(DpmClockType::SCLK.get_level_count(dir).unwrap(), 3);
+    assert!(matches!(
+        DpmClockType::SCLK.force_levels(dir, &[1, 3]),
+        Err(Error::OutOfRange { value: 3, min: 0, max: 2 }),
+    ));
+    assert!(DpmClockType::MCLK.force_levels(dir, &[0]).unwrap_err().is_unsupported());
+
+    /* a regular file accepts the write, the kernel driver would parse it */
+    DpmClockType::SCLK.force_levels(dir, &[1, 2]).unwrap();
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "1 2");
+}
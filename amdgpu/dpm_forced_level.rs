@@ -1,5 +1,6 @@
-use std::io;
 use std::path::PathBuf;
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
 
 const SYSFS_NAME: &str = "power_dpm_force_performance_level";
 
@@ -18,9 +19,9 @@ pub enum DpmForcedLevel {
 }
 
 impl DpmForcedLevel {
-    pub fn get_from_sysfs<P: Into<PathBuf>>(sysfs_path: P) -> io::Result<Self> {
-        let sysfs_path = sysfs_path.into();
-        let s = std::fs::read_to_string(sysfs_path.join(SYSFS_NAME))?;
+    pub fn get_from_sysfs<P: Into<PathBuf>>(sysfs_path: P) -> Result<Self, Error> {
+        let path = sysfs_path.into().join(SYSFS_NAME);
+        let s = read_sysfs(&path)?;
 
         let level = match s.get(0..4).unwrap_or("") {
             "auto" => Self::Auto,
@@ -36,16 +37,21 @@ impl DpmForcedLevel {
                     "min_sclk\n" => Self::ProfileMinSclk,
                     "min_mclk\n" => Self::ProfileMinMclk,
                     "exit\n" => Self::ProfileExit,
-                    _ => return Err(io::Error::other("Unknown Level")),
+                    _ => return Err(Error::parse(&path, s.trim_end())),
                 }
             },
             "perf" => Self::PerfDeterminism,
-            _ => return Err(io::Error::other("Unknown Level")),
+            _ => return Err(Error::parse(&path, s.trim_end())),
         };
 
         Ok(level)
     }
 
+    /// Requires root
+    pub fn set<P: Into<PathBuf>>(&self, sysfs_path: P) -> Result<(), Error> {
+        write_sysfs(sysfs_path.into().join(SYSFS_NAME), self.to_arg())
+    }
+
     pub const fn to_arg(&self) -> &str {
         match self {
             Self::Auto => "auto",
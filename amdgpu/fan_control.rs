@@ -0,0 +1,389 @@
+/*
+    ref: drivers/gpu/drm/amd/pm/amdgpu_pm.c
+    ref: Documentation/gpu/amdgpu/thermal.rst
+*/
+
+use crate::AMDGPU::DeviceHandle;
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
+use std::path::PathBuf;
+use super::parse_hwmon;
+use super::od_clk_voltage::check_range;
+
+impl DeviceHandle {
+    pub fn get_fan_control(&self) -> Option<FanControl> {
+        let hwmon_path = self.get_hwmon_path().ok()?;
+
+        FanControl::from_hwmon_path(hwmon_path)
+    }
+
+    /// SMU13+ (RDNA3)
+    pub fn get_od_fan_ctrl(&self) -> Option<OdFanCtrl> {
+        let sysfs_path = self.get_sysfs_path().ok()?;
+
+        OdFanCtrl::from_sysfs_path(sysfs_path)
+    }
+}
+
+/// `pwm1_enable`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FanMode {
+    /// No fan speed control (full speed)
+    None = 0,
+    Manual = 1,
+    Auto = 2,
+}
+
+impl TryFrom<u32> for FanMode {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Manual),
+            2 => Ok(Self::Auto),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Fan control via hwmon.
+/// Some SMU13+ dGPUs do not support `pwm1`/`fan1_target`, use [OdFanCtrl] instead.
+#[derive(Clone, Debug)]
+pub struct FanControl {
+    pub hwmon_path: PathBuf,
+    pub mode: Option<FanMode>,
+    /// `fan1_input` (RPM)
+    pub rpm: Option<u32>,
+    /// `pwm1` (0-255)
+    pub pwm: Option<u8>,
+    /// `fan1_target` (RPM)
+    pub target_rpm: Option<u32>,
+    /// `fan1_min` (RPM)
+    pub min_rpm: Option<u32>,
+    /// `fan1_max` (RPM)
+    pub max_rpm: Option<u32>,
+}
+
+impl FanControl {
+    /// Returns `None` if the device has neither `fan1_input` nor `pwm1`
+    pub fn from_hwmon_path<P: Into<PathBuf>>(path: P) -> Option<Self> {
+        let mut fan = Self {
+            hwmon_path: path.into(),
+            mode: None,
+            rpm: None,
+            pwm: None,
+            target_rpm: None,
+            min_rpm: None,
+            max_rpm: None,
+        };
+
+        fan.update();
+
+        if fan.rpm.is_none() && fan.pwm.is_none() {
+            return None;
+        }
+
+        Some(fan)
+    }
+
+    pub fn update(&mut self) {
+        let path = &self.hwmon_path;
+
+        self.mode = parse_hwmon::<u32, _>(path.join("pwm1_enable")).ok()
+            .and_then(|v| FanMode::try_from(v).ok());
+        self.rpm = parse_hwmon(path.join("fan1_input")).ok();
+        self.pwm = parse_hwmon(path.join("pwm1")).ok();
+        self.target_rpm = parse_hwmon(path.join("fan1_target")).ok();
+        self.min_rpm = parse_hwmon(path.join("fan1_min")).ok();
+        self.max_rpm = parse_hwmon(path.join("fan1_max")).ok();
+    }
+
+    /// Requires root
+    pub fn set_mode(&mut self, mode: FanMode) -> Result<(), Error> {
+        write_sysfs(self.hwmon_path.join("pwm1_enable"), &(mode as u32).to_string())?;
+        self.mode = Some(mode);
+
+        Ok(())
+    }
+
+    /// Switch to [FanMode::Manual] and set `pwm1`, requires root
+    pub fn set_pwm(&mut self, pwm: u8) -> Result<(), Error> {
+        if self.mode != Some(FanMode::Manual) {
+            self.set_mode(FanMode::Manual)?;
+        }
+
+        write_sysfs(self.hwmon_path.join("pwm1"), &pwm.to_string())?;
+        self.pwm = Some(pwm);
+
+        Ok(())
+    }
+
+    /// Switch to manual RPM control (`fan1_enable`) and set `fan1_target`, requires root.
+    /// `rpm` must be in `fan1_min..=fan1_max`.
+    pub fn set_target_rpm(&mut self, rpm: u32) -> Result<(), Error> {
+        if let (Some(min), Some(max)) = (self.min_rpm, self.max_rpm)
+        && !(min..=max).contains(&rpm) {
+            return Err(Error::OutOfRange { value: rpm as i64, min: min as i64, max: max as i64 });
+        }
+
+        write_sysfs(self.hwmon_path.join("fan1_enable"), "1")?;
+        write_sysfs(self.hwmon_path.join("fan1_target"), &rpm.to_string())?;
+        self.target_rpm = Some(rpm);
+
+        Ok(())
+    }
+
+    /// Revert to the automatic fan control, requires root
+    pub fn set_auto(&mut self) -> Result<(), Error> {
+        if self.target_rpm.is_some() {
+            write_sysfs(self.hwmon_path.join("fan1_enable"), "0")?;
+        }
+
+        self.set_mode(FanMode::Auto)
+    }
+}
+
+/// `gpu_od/fan_ctrl/fan_curve` point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FanCurvePoint {
+    /// Hotspot temperature (C)
+    pub temp: i32,
+    /// Fan speed (%)
+    pub speed: i32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FanCurve {
+    pub points: Vec<FanCurvePoint>,
+    /// `FAN_CURVE(hotspot temp)`, `[min, max]`
+    pub temp_range: Option<[i32; 2]>,
+    /// `FAN_CURVE(fan speed)`, `[min, max]`
+    pub speed_range: Option<[i32; 2]>,
+}
+
+impl FanCurve {
+    /// ```text
+    /// OD_FAN_CURVE:
+    /// 0: 25C 20%
+    /// OD_RANGE:
+    /// FAN_CURVE(hotspot temp): 25C 100C
+    /// FAN_CURVE(fan speed): 20% 100%
+    /// ```
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut curve = Self::default();
+        let parse = |v: &str, unit: char| v.trim().strip_suffix(unit)?.parse::<i32>().ok();
+
+        for line in s.lines().skip_while(|line| !line.starts_with("OD_FAN_CURVE:")).skip(1) {
+            let Some((name, values)) = line.split_once(':') else { continue };
+            let mut values = values.split_whitespace();
+            let [Some(a), Some(b)] = [values.next(), values.next()] else { continue };
+
+            match name.trim() {
+                "FAN_CURVE(hotspot temp)" => curve.temp_range = Some([parse(a, 'C')?, parse(b, 'C')?]),
+                "FAN_CURVE(fan speed)" => curve.speed_range = Some([parse(a, '%')?, parse(b, '%')?]),
+                index if index.parse::<u32>().is_ok() => curve.points.push(FanCurvePoint {
+                    temp: parse(a, 'C')?,
+                    speed: parse(b, '%')?,
+                }),
+                _ => {},
+            }
+        }
+
+        Some(curve)
+    }
+}
+
+/// Single value files in `gpu_od/fan_ctrl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdFanCtrlFile {
+    /// RPM
+    AcousticLimitRpmThreshold,
+    /// RPM
+    AcousticTargetRpmThreshold,
+    /// C
+    FanTargetTemperature,
+    /// %
+    FanMinimumPwm,
+    /// 0 or 1
+    FanZeroRpmEnable,
+}
+
+impl OdFanCtrlFile {
+    pub const fn file_name(&self) -> &str {
+        match self {
+            Self::AcousticLimitRpmThreshold => "acoustic_limit_rpm_threshold",
+            Self::AcousticTargetRpmThreshold => "acoustic_target_rpm_threshold",
+            Self::FanTargetTemperature => "fan_target_temperature",
+            Self::FanMinimumPwm => "fan_minimum_pwm",
+            Self::FanZeroRpmEnable => "fan_zero_rpm_enable",
+        }
+    }
+}
+
+/// Value and `OD_RANGE` of [OdFanCtrlFile]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OdFanValue {
+    pub value: i32,
+    pub range: Option<[i32; 2]>,
+}
+
+impl OdFanValue {
+    /// ```text
+    /// FAN_TARGET_TEMPERATURE:
+    /// 95
+    /// OD_RANGE:
+    /// TARGET_TEMPERATURE: 25 110
+    /// ```
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+        let _header = lines.next()?;
+        let value = lines.next()?.parse().ok()?;
+        let range = lines
+            .skip_while(|line| *line != "OD_RANGE:")
+            .nth(1)
+            .and_then(|line| {
+                let mut values = line.split_once(':')?.1.split_whitespace();
+
+                Some([values.next()?.parse().ok()?, values.next()?.parse().ok()?])
+            });
+
+        Some(Self { value, range })
+    }
+}
+
+/// `gpu_od/fan_ctrl` (SMU13+).
+/// Writes are committed immediately (`c`).
+#[derive(Clone, Debug)]
+pub struct OdFanCtrl {
+    pub path: PathBuf,
+}
+
+impl OdFanCtrl {
+    pub fn from_sysfs_path<P: Into<PathBuf>>(sysfs_path: P) -> Option<Self> {
+        let path = sysfs_path.into().join("gpu_od/fan_ctrl");
+
+        path.is_dir().then_some(Self { path })
+    }
+
+    pub fn fan_curve(&self) -> Result<FanCurve, Error> {
+        let path = self.path.join("fan_curve");
+        let s = read_sysfs(&path)?;
+
+        FanCurve::parse(&s).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    /// Set a point of `fan_curve`, requires root.
+    /// The fan curve takes effect when [OdFanCtrlFile::FanZeroRpmEnable] is disabled
+    /// on some boards.
+    pub fn set_fan_curve_point(&self, index: u32, point: FanCurvePoint) -> Result<(), Error> {
+        Self::check_fan_curve_point(&self.fan_curve()?, index, point)?;
+
+        self.commit("fan_curve", &format!("{index} {} {}", point.temp, point.speed))
+    }
+
+    /// Set all points of `fan_curve` and commit them at once, requires root.
+    /// All points are checked before writing.
+    pub fn set_fan_curve(&self, points: &[FanCurvePoint]) -> Result<(), Error> {
+        let curve = self.fan_curve()?;
+
+        for (i, point) in points.iter().enumerate() {
+            Self::check_fan_curve_point(&curve, i as u32, *point)?;
+        }
+
+        let path = self.path.join("fan_curve");
+
+        for (i, point) in points.iter().enumerate() {
+            write_sysfs(&path, &format!("{i} {} {}", point.temp, point.speed))?;
+        }
+
+        write_sysfs(&path, "c")
+    }
+
+    fn check_fan_curve_point(curve: &FanCurve, index: u32, point: FanCurvePoint) -> Result<(), Error> {
+        if index as usize >= curve.points.len() {
+            return Err(Error::OutOfRange {
+                value: index as i64,
+                min: 0,
+                max: curve.points.len() as i64 - 1,
+            });
+        }
+
+        check_range(point.temp, curve.temp_range)?;
+        check_range(point.speed, curve.speed_range)
+    }
+
+    pub fn get(&self, file: OdFanCtrlFile) -> Result<OdFanValue, Error> {
+        let path = self.path.join(file.file_name());
+        let s = read_sysfs(&path)?;
+
+        OdFanValue::parse(&s).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    /// Requires root, `value` is checked against `OD_RANGE`
+    pub fn set(&self, file: OdFanCtrlFile, value: i32) -> Result<(), Error> {
+        check_range(value, self.get(file)?.range)?;
+
+        self.commit(file.file_name(), &value.to_string())
+    }
+
+    /// Restore the default value (`r`), requires root
+    pub fn reset(&self, file: OdFanCtrlFile) -> Result<(), Error> {
+        self.commit(file.file_name(), "r")
+    }
+
+    /// Restore the default fan curve (`r`), requires root
+    pub fn reset_fan_curve(&self) -> Result<(), Error> {
+        self.commit("fan_curve", "r")
+    }
+
+    fn commit(&self, file_name: &str, arg: &str) -> Result<(), Error> {
+        let path = self.path.join(file_name);
+
+        write_sysfs(&path, arg)?;
+        write_sysfs(&path, "c")
+    }
+}
+
+#[test]
+fn test_od_fan_ctrl_parse() {
+    const FAN_CURVE: &str = "\
+OD_FAN_CURVE:
+0: 0C 0%
+1: 45C 30%
+2: 60C 50%
+3: 75C 70%
+4: 90C 100%
+OD_RANGE:
+FAN_CURVE(hotspot temp): 25C 100C
+FAN_CURVE(fan speed): 20% 100%
+";
+    let curve = FanCurve::parse(FAN_CURVE).unwrap();
+
+    assert_eq!(curve.points.len(), 5);
+    assert_eq!(curve.points[1], FanCurvePoint { temp: 45, speed: 30 });
+    assert_eq!(curve.temp_range, Some([25, 100]));
+    assert_eq!(curve.speed_range, Some([20, 100]));
+    assert!(check_range(101, curve.temp_range).is_err());
+    assert!(OdFanCtrl::check_fan_curve_point(&curve, 4, FanCurvePoint { temp: 90, speed: 100 }).is_ok());
+    assert!(matches!(
+        OdFanCtrl::check_fan_curve_point(&curve, 5, FanCurvePoint { temp: 90, speed: 100 }),
+        Err(Error::OutOfRange { value: 5, min: 0, max: 4 }),
+    ));
+    assert!(OdFanCtrl::check_fan_curve_point(&curve, 0, FanCurvePoint { temp: 0, speed: 0 }).is_err());
+
+    const ACOUSTIC_LIMIT: &str = "\
+OD_ACOUSTIC_LIMIT:
+2450
+OD_RANGE:
+ACOUSTIC_LIMIT: 500 3100
+";
+    assert_eq!(
+        OdFanValue::parse(ACOUSTIC_LIMIT),
+        Some(OdFanValue { value: 2450, range: Some([500, 3100]) }),
+    );
+
+    const ZERO_RPM: &str = "FAN_ZERO_RPM_ENABLE:\n1\nOD_RANGE:\nZERO_RPM_ENABLE: 0 1\n";
+    assert_eq!(OdFanValue::parse(ZERO_RPM).unwrap().range, Some([0, 1]));
+}
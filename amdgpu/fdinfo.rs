@@ -0,0 +1,319 @@
+/*
+    ref: https://docs.kernel.org/gpu/drm-usage-stats.html
+    ref: drivers/gpu/drm/amd/amdgpu/amdgpu_fdinfo.c
+*/
+
+use crate::PCI;
+use crate::Error;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const KIB: u64 = 1024;
+
+/// Usage of one DRM client (file description), parsed from `/proc/<pid>/fdinfo/<fd>`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FdInfoUsage {
+    /// `drm-client-id`, shared by duplicated fds
+    pub client_id: u64,
+    /// `drm-memory-vram` (or `drm-resident-vram`) in bytes
+    pub vram: u64,
+    /// `drm-memory-gtt` (or `drm-resident-gtt`) in bytes
+    pub gtt: u64,
+    /// `drm-engine-gfx` in ns
+    pub gfx: u64,
+    /// `drm-engine-compute` in ns
+    pub compute: u64,
+    /// `drm-engine-dec` in ns
+    pub dec: u64,
+    /// `drm-engine-enc` in ns
+    pub enc: u64,
+}
+
+impl FdInfoUsage {
+    /// Returns `None` if `s` is not a fdinfo of amdgpu for `pci`
+    pub fn parse(s: &str, pci: &PCI::BUS_INFO) -> Option<Self> {
+        let mut usage = Self::default();
+        let mut pdev_matched = false;
+        let mut resident_vram = None;
+        let mut resident_gtt = None;
+        let mut vram = None;
+        let mut gtt = None;
+
+        for line in s.lines() {
+            let Some((key, val)) = line.split_once(':') else { continue };
+            let val = val.trim();
+
+            match key {
+                "drm-driver" if val != "amdgpu" => return None,
+                "drm-pdev" => pdev_matched = val.parse::<PCI::BUS_INFO>().ok()? == *pci,
+                "drm-client-id" => usage.client_id = val.parse().ok()?,
+                "drm-memory-vram" => vram = parse_kib(val),
+                "drm-memory-gtt" => gtt = parse_kib(val),
+                "drm-resident-vram" => resident_vram = parse_kib(val),
+                "drm-resident-gtt" => resident_gtt = parse_kib(val),
+                "drm-engine-gfx" => usage.gfx = parse_ns(val)?,
+                "drm-engine-compute" => usage.compute = parse_ns(val)?,
+                "drm-engine-dec" => usage.dec = parse_ns(val)?,
+                "drm-engine-enc" => usage.enc = parse_ns(val)?,
+                _ => {},
+            }
+        }
+
+        if !pdev_matched {
+            return None;
+        }
+
+        /* `drm-memory-*` is deprecated and removed in Linux Kernel 6.12+ */
+        usage.vram = vram.or(resident_vram).unwrap_or(0);
+        usage.gtt = gtt.or(resident_gtt).unwrap_or(0);
+
+        Some(usage)
+    }
+}
+
+impl std::ops::AddAssign for FdInfoUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.vram += other.vram;
+        self.gtt += other.gtt;
+        self.gfx += other.gfx;
+        self.compute += other.compute;
+        self.dec += other.dec;
+        self.enc += other.enc;
+    }
+}
+
+fn parse_kib(s: &str) -> Option<u64> {
+    let (val, unit) = s.split_once(' ').unwrap_or((s, ""));
+    let val: u64 = val.parse().ok()?;
+
+    match unit {
+        "" => Some(val),
+        "KiB" => Some(val * KIB),
+        "MiB" => Some(val * KIB * KIB),
+        "GiB" => Some(val * KIB * KIB * KIB),
+        _ => None,
+    }
+}
+
+fn parse_ns(s: &str) -> Option<u64> {
+    s.strip_suffix(" ns")?.parse().ok()
+}
+
+/// Usage summed over all DRM clients of the process for the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessUsage {
+    pub pid: i32,
+    /// `/proc/<pid>/comm`
+    pub name: String,
+    pub usage: FdInfoUsage,
+}
+
+/// Engine utilisation between two [FdInfoSampler::update] in percent.
+/// `compute` may exceed 100% when multiple queues are busy.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EngineUtilisation {
+    pub gfx: f32,
+    pub compute: f32,
+    pub dec: f32,
+    pub enc: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessUsageDelta {
+    pub pid: i32,
+    pub name: String,
+    /// VRAM usage in bytes
+    pub vram: u64,
+    /// GTT usage in bytes
+    pub gtt: u64,
+    pub engine: EngineUtilisation,
+}
+
+/// Walks `/proc/*/fdinfo` to find DRM clients of the device
+#[derive(Debug, Clone)]
+pub struct FdInfoScanner {
+    pub pci: PCI::BUS_INFO,
+    proc_root: PathBuf,
+    nodes: Vec<PathBuf>,
+}
+
+impl FdInfoScanner {
+    pub fn new(pci: PCI::BUS_INFO) -> Result<Self, Error> {
+        Self::new_with_proc_root(pci, "/proc")
+    }
+
+    pub fn new_with_proc_root<P: Into<PathBuf>>(
+        pci: PCI::BUS_INFO,
+        proc_root: P,
+    ) -> Result<Self, Error> {
+        let render = pci.get_drm_render_path();
+        let card = pci.get_drm_card_path();
+
+        let nodes: Vec<PathBuf> = [&render, &card]
+            .into_iter()
+            .filter_map(|path| path.as_ref().ok().cloned())
+            .collect();
+
+        if nodes.is_empty() {
+            return Err(render.unwrap_err());
+        }
+
+        Ok(Self { pci, proc_root: proc_root.into(), nodes })
+    }
+
+    /// Processes that cannot be read (e.g. permission denied) are skipped.
+    pub fn scan(&self) -> Vec<ProcessUsage> {
+        let Ok(dir) = std::fs::read_dir(&self.proc_root) else { return Vec::new() };
+
+        dir
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let pid: i32 = entry.file_name().to_str()?.parse().ok()?;
+
+                self.scan_pid(pid, &entry.path())
+            })
+            .collect()
+    }
+
+    fn scan_pid(&self, pid: i32, proc_pid: &Path) -> Option<ProcessUsage> {
+        let mut clients: HashMap<u64, FdInfoUsage> = HashMap::new();
+
+        for fd in std::fs::read_dir(proc_pid.join("fd")).ok()? {
+            let Ok(fd) = fd else { continue };
+            let Ok(link) = std::fs::read_link(fd.path()) else { continue };
+
+            if !self.nodes.contains(&link) {
+                continue;
+            }
+
+            let Ok(s) = std::fs::read_to_string(proc_pid.join("fdinfo").join(fd.file_name()))
+                else { continue };
+            let Some(usage) = FdInfoUsage::parse(&s, &self.pci) else { continue };
+
+            clients.insert(usage.client_id, usage);
+        }
+
+        if clients.is_empty() {
+            return None;
+        }
+
+        let name = std::fs::read_to_string(proc_pid.join("comm"))
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default();
+        let mut usage = FdInfoUsage::default();
+
+        for client in clients.into_values() {
+            usage += client;
+        }
+
+        Some(ProcessUsage { pid, name, usage })
+    }
+}
+
+/// Tracks engine time of each process between updates
+#[derive(Debug, Clone)]
+pub struct FdInfoSampler {
+    pub scanner: FdInfoScanner,
+    prev: HashMap<i32, FdInfoUsage>,
+    last_update: Option<Instant>,
+}
+
+impl FdInfoSampler {
+    pub fn new(scanner: FdInfoScanner) -> Self {
+        Self { scanner, prev: HashMap::new(), last_update: None }
+    }
+
+    /// Engine utilisation is 0% for the first update and for new processes
+    pub fn update(&mut self) -> Vec<ProcessUsageDelta> {
+        let now = Instant::now();
+        let elapsed_ns = self.last_update.map(|t| now.duration_since(t).as_nanos() as u64);
+        let procs = self.scanner.scan();
+
+        self.last_update = Some(now);
+
+        Self::calc_deltas(&mut self.prev, procs, elapsed_ns)
+    }
+
+    fn calc_deltas(
+        prev: &mut HashMap<i32, FdInfoUsage>,
+        procs: Vec<ProcessUsage>,
+        elapsed_ns: Option<u64>,
+    ) -> Vec<ProcessUsageDelta> {
+        let mut deltas = Vec::with_capacity(procs.len());
+        let mut current = HashMap::with_capacity(procs.len());
+
+        for proc in procs {
+            let engine = match (prev.get(&proc.pid), elapsed_ns) {
+                (Some(pre), Some(elapsed)) if elapsed != 0 => {
+                    let cur = &proc.usage;
+                    let percent = |cur: u64, pre: u64| {
+                        cur.saturating_sub(pre) as f32 * 100.0 / elapsed as f32
+                    };
+
+                    EngineUtilisation {
+                        gfx: percent(cur.gfx, pre.gfx),
+                        compute: percent(cur.compute, pre.compute),
+                        dec: percent(cur.dec, pre.dec),
+                        enc: percent(cur.enc, pre.enc),
+                    }
+                },
+                _ => EngineUtilisation::default(),
+            };
+
+            deltas.push(ProcessUsageDelta {
+                pid: proc.pid,
+                name: proc.name,
+                vram: proc.usage.vram,
+                gtt: proc.usage.gtt,
+                engine,
+            });
+            current.insert(proc.pid, proc.usage);
+        }
+
+        *prev = current;
+
+        deltas
+    }
+}
+
+#[test]
+fn test_fdinfo_parse() {
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let s = "\
+pos:	0
+flags:	02100002
+drm-driver:	amdgpu
+drm-client-id:	42
+drm-pdev:	0000:0b:00.0
+pasid:	32781
+drm-memory-vram:	18372 KiB
+drm-memory-gtt:	2048 KiB
+drm-memory-cpu:	0 KiB
+drm-engine-gfx:	2000000 ns
+drm-engine-compute:	0 ns
+drm-engine-dec:	500000 ns
+";
+    let usage = FdInfoUsage::parse(s, &pci).unwrap();
+
+    assert_eq!(usage.client_id, 42);
+    assert_eq!(usage.vram, 18372 * KIB);
+    assert_eq!(usage.gtt, 2048 * KIB);
+    assert_eq!(usage.gfx, 2_000_000);
+    assert_eq!(usage.enc, 0);
+
+    let other: PCI::BUS_INFO = "0000:03:00.0".parse().unwrap();
+    assert_eq!(FdInfoUsage::parse(s, &other), None);
+
+    let mut prev = HashMap::from([(100, FdInfoUsage { gfx: 1_000_000, ..usage })]);
+    let procs = vec![
+        ProcessUsage { pid: 100, name: "foo".to_string(), usage },
+        ProcessUsage { pid: 200, name: "bar".to_string(), usage },
+    ];
+    let deltas = FdInfoSampler::calc_deltas(&mut prev, procs, Some(4_000_000));
+
+    assert_eq!(deltas[0].engine.gfx, 25.0);
+    assert_eq!(deltas[0].engine.dec, 0.0);
+    assert_eq!(deltas[1].engine, EngineUtilisation::default());
+    assert_eq!(prev.len(), 2);
+}
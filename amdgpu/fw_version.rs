@@ -4,6 +4,7 @@ use crate::*;
 use core::mem::MaybeUninit;
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FwVer {
     pub fw_type: FW_TYPE,
     pub ip_instance: u32,
@@ -19,7 +20,7 @@ impl DeviceHandle {
         fw_type: FW_TYPE,
         ip_instance: ::core::ffi::c_uint,
         index: ::core::ffi::c_uint,
-    ) -> Result<FwVer, i32> {
+    ) -> Result<FwVer, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_firmware_version;
         #[cfg(feature = "dynamic_loading")]
@@ -46,7 +47,7 @@ impl DeviceHandle {
                 feature: feature.assume_init(),
             };
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_firmware_version", fw_type);
 
             Ok(fw_ver)
         }
@@ -79,6 +80,7 @@ pub use crate::bindings::{
 
 /// Used for [DeviceHandle::query_firmware_version]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum FW_TYPE {
     VCE = AMDGPU_INFO_FW_VCE,
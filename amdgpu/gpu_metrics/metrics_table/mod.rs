@@ -5,7 +5,7 @@ mod v1_4_5;
 mod v2_v3;
 
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::Read;
 use std::path::PathBuf;
 pub use crate::bindings::{
     metrics_table_header,
@@ -29,6 +29,7 @@ pub use crate::bindings::{
     MAX_GFX_CLKS,
 };
 use crate::AMDGPU::ThrottleStatus;
+use crate::Error;
 
 impl metrics_table_header {
     pub(crate) fn from_bytes(buf: &[u8]) -> Self {
@@ -51,11 +52,13 @@ impl metrics_table_header {
         Self::from_bytes(&buf)
     }
 
-    pub fn from_sysfs_path<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
-        let mut f = File::open(path.into())?;
+    pub fn from_sysfs_path<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
+        let path = path.into();
         let mut buf = [0u8; 4];
 
-        f.read_exact(&mut buf)?;
+        File::open(&path)
+            .and_then(|mut f| f.read_exact(&mut buf))
+            .map_err(|e| Error::io(&path, e))?;
 
         Ok(Self::from_buf(buf))
     }
@@ -115,6 +118,9 @@ pub trait MetricsInfo {
     fn get_average_ipu_writes(&self) -> Option<u16>;
 
     fn get_system_clock_counter(&self) -> Option<u64>;
+    /// Accumulated energy (15.259uJ (2^-16 J) units), only [gpu_metrics_v1_0] - [gpu_metrics_v1_5] has it.
+    /// The SMU may leave it as 0.
+    fn get_energy_accumulator(&self) -> Option<u64> { None }
     /// Watts
     fn get_average_socket_power(&self) -> Option<u32>;
     /// Watts
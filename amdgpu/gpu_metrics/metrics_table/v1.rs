@@ -206,6 +206,10 @@ macro_rules! v1_impl {
 impl MetricsInfo for gpu_metrics_v1_0 {
     v1_impl!();
 
+    fn get_energy_accumulator(&self) -> Option<u64> {
+        Some(self.energy_accumulator as u64)
+    }
+
     fn get_gfx_activity_acc(&self) -> Option<u32> { None }
     fn get_mem_activity_acc(&self) -> Option<u32> { None }
     fn get_temperature_hbm(&self) -> Option<[u16; NUM_HBM_INSTANCES as usize]> { None }
@@ -218,6 +222,10 @@ impl MetricsInfo for gpu_metrics_v1_0 {
 impl MetricsInfo for gpu_metrics_v1_1 {
     v1_impl!();
 
+    fn get_energy_accumulator(&self) -> Option<u64> {
+        Some(self.energy_accumulator)
+    }
+
     fn get_gfx_activity_acc(&self) -> Option<u32> {
         Some(self.gfx_activity_acc)
     }
@@ -239,6 +247,10 @@ impl MetricsInfo for gpu_metrics_v1_1 {
 impl MetricsInfo for gpu_metrics_v1_2 {
     v1_impl!();
 
+    fn get_energy_accumulator(&self) -> Option<u64> {
+        Some(self.energy_accumulator)
+    }
+
     fn get_gfx_activity_acc(&self) -> Option<u32> {
         Some(self.gfx_activity_acc)
     }
@@ -260,6 +272,10 @@ impl MetricsInfo for gpu_metrics_v1_2 {
 impl MetricsInfo for gpu_metrics_v1_3 {
     v1_impl!();
 
+    fn get_energy_accumulator(&self) -> Option<u64> {
+        Some(self.energy_accumulator)
+    }
+
     fn get_gfx_activity_acc(&self) -> Option<u32> {
         Some(self.gfx_activity_acc)
     }
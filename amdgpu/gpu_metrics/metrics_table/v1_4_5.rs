@@ -49,6 +49,10 @@ macro_rules! v1_4_v1_5_impl {
             Some(self.system_clock_counter)
         }
 
+        fn get_energy_accumulator(&self) -> Option<u64> {
+            Some(self.energy_accumulator)
+        }
+
         fn get_throttle_status(&self) -> Option<u32> {
             Some(self.throttle_status)
         }
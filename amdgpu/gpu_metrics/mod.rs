@@ -2,6 +2,7 @@ mod metrics_table;
 pub use metrics_table::*;
 
 use crate::AMDGPU::DeviceHandle;
+use crate::Error;
 pub use crate::bindings::{
     NUM_HBM_INSTANCES,
     NUM_VCN,
@@ -28,13 +29,14 @@ use core::mem::{size_of, MaybeUninit};
 use core::ptr;
 
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::Read;
 use std::path::PathBuf;
 
 /// AMD GPU metrics data available from `"{sysfs_path}/gpu_metrics"`.  
 /// Vega12 (dGPU) or later, Renoir (APU) or later supports GPU metrics.  
 /// [DeviceHandle::get_gpu_metrics]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GpuMetrics {
     Unknown,
     V1_0(gpu_metrics_v1_0),
@@ -96,6 +98,7 @@ impl MetricsInfo for GpuMetrics {
     impl_metrics!(get_average_ipu_reads, Option<u16>);
     impl_metrics!(get_average_ipu_writes, Option<u16>);
     impl_metrics!(get_system_clock_counter, Option<u64>);
+    impl_metrics!(get_energy_accumulator, Option<u64>);
     impl_metrics!(get_average_socket_power, Option<u32>);
     impl_metrics!(get_average_cpu_power, Option<u16>);
     impl_metrics!(get_average_soc_power, Option<u16>);
@@ -174,17 +177,17 @@ impl DeviceHandle {
     pub fn get_gpu_metrics_from_sysfs_path<P: Into<PathBuf>>(
         &self,
         path: P,
-    ) -> io::Result<GpuMetrics> {
+    ) -> Result<GpuMetrics, Error> {
         GpuMetrics::get_from_sysfs_path(path)
     }
 
-    pub fn get_gpu_metrics(&self) -> io::Result<GpuMetrics> {
-        let sysfs_path = self.get_sysfs_path().unwrap();
+    pub fn get_gpu_metrics(&self) -> Result<GpuMetrics, Error> {
+        let sysfs_path = self.get_sysfs_path()?;
         GpuMetrics::get_from_sysfs_path(sysfs_path)
     }
 
-    pub fn get_raw_gpu_metrics(&self) -> io::Result<Vec<u8>> {
-        let sysfs_path = self.get_sysfs_path().unwrap();
+    pub fn get_raw_gpu_metrics(&self) -> Result<Vec<u8>, Error> {
+        let sysfs_path = self.get_sysfs_path()?;
         GpuMetrics::get_raw_from_sysfs_path(sysfs_path)
     }
 /*
@@ -192,24 +195,26 @@ impl DeviceHandle {
         &self,
         buf: &mut Vec<u8>,
         sysfs_path: P,
-    ) -> io::Result<GpuMetrics> {
+    ) -> Result<GpuMetrics, Error> {
         GpuMetrics::read_file_with_buffer(buf, sysfs_path.into().join("gpu_metrics"))
     }
 */
 }
 
 impl GpuMetrics {
-    pub fn get_from_sysfs_path<P: Into<PathBuf>>(sysfs_path: P) -> io::Result<Self> {
+    pub fn get_from_sysfs_path<P: Into<PathBuf>>(sysfs_path: P) -> Result<Self, Error> {
         let raw = Self::get_raw_from_sysfs_path(sysfs_path.into())?;
 
         Ok(Self::from_raw(&raw))
     }
 
-    pub fn get_raw_from_sysfs_path<P: Into<PathBuf>>(sysfs_path: P) -> io::Result<Vec<u8>> {
+    pub fn get_raw_from_sysfs_path<P: Into<PathBuf>>(sysfs_path: P) -> Result<Vec<u8>, Error> {
         let mut buf: Vec<u8> = Vec::with_capacity(256);
+        let path = sysfs_path.into().join("gpu_metrics");
 
-        let mut f = File::open(sysfs_path.into().join("gpu_metrics"))?;
-        f.read_to_end(&mut buf)?;
+        File::open(&path)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .map_err(|e| Error::io(&path, e))?;
 
         Ok(buf)
     }
@@ -250,9 +255,12 @@ impl GpuMetrics {
         }
     }
 
-    pub fn read_file_with_buffer<P: Into<PathBuf>>(buf: &mut Vec<u8>, path: P) -> io::Result<Self> {
-        let mut f = File::open(path.into())?;
-        f.read_to_end(buf)?;
+    pub fn read_file_with_buffer<P: Into<PathBuf>>(buf: &mut Vec<u8>, path: P) -> Result<Self, Error> {
+        let path = path.into();
+
+        File::open(&path)
+            .and_then(|mut f| f.read_to_end(buf))
+            .map_err(|e| Error::io(&path, e))?;
 
         Ok(Self::from_raw(buf))
     }
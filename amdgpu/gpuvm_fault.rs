@@ -0,0 +1,191 @@
+use crate::AMDGPU::{DeviceHandle, CHIP_CLASS, GPU_INFO};
+use crate::Error;
+use crate::bindings;
+
+pub use crate::bindings::drm_amdgpu_info_gpuvm_fault;
+
+/// The last GPU VM page fault recorded by the kernel driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GpuVmFault {
+    /// Faulting GPU virtual address
+    pub addr: u64,
+    /// Raw value of `VM_L2_PROTECTION_FAULT_STATUS` (GFX9+)
+    /// or `VM_CONTEXT1_PROTECTION_FAULT_STATUS` (GFX6-8)
+    pub status: u32,
+    /// Index of the VM hub (GFXHUB, MMHUB)
+    pub vmhub: u32,
+    pub decoded: GpuVmFaultStatus,
+}
+
+/// Decoded fault status
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GpuVmFaultStatus {
+    pub vmid: u8,
+    /// Memory client ID (CID)
+    pub client_id: u16,
+    /// The fault was caused by a write access
+    pub write: bool,
+    /// More faults occurred after this fault (GFX9+)
+    pub more_faults: bool,
+    pub permission: VmPermissionFaults,
+    pub protection: VmProtectionFaults,
+    /// The page is not mapped (or the PTE is not valid)
+    pub mapping_error: bool,
+}
+
+/// Access was denied by the PTE permission bits
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VmPermissionFaults {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+/// Faults on the page table itself
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VmProtectionFaults {
+    /// Out of the VM range (GFX6-8)
+    pub range: bool,
+    /// Access to the dummy page (GFX6-8)
+    pub dummy_page: bool,
+    /// Invalid PDE0 (GFX6-8)
+    pub pde0: bool,
+    /// Page table walker error (GFX9+)
+    pub walker_error: u8,
+}
+
+impl VmPermissionFaults {
+    pub fn any(&self) -> bool {
+        self.read || self.write || self.execute
+    }
+}
+
+impl VmProtectionFaults {
+    pub fn any(&self) -> bool {
+        self.range || self.dummy_page || self.pde0 || self.walker_error != 0
+    }
+}
+
+impl GpuVmFaultStatus {
+    pub fn decode(status: u32, chip_class: CHIP_CLASS) -> Self {
+        let field = |shift: u32, mask: u32| (status >> shift) & mask;
+
+        if chip_class >= CHIP_CLASS::GFX9 {
+            /* VM_L2_PROTECTION_FAULT_STATUS */
+            let perm = field(4, 0xF);
+
+            Self {
+                vmid: field(20, 0xF) as u8,
+                client_id: field(9, 0x1FF) as u16,
+                write: field(18, 0x1) != 0,
+                more_faults: field(0, 0x1) != 0,
+                permission: VmPermissionFaults {
+                    read: (perm & 0b0010) != 0,
+                    write: (perm & 0b0100) != 0,
+                    execute: (perm & 0b1000) != 0,
+                },
+                protection: VmProtectionFaults {
+                    walker_error: field(1, 0x7) as u8,
+                    ..Default::default()
+                },
+                mapping_error: field(8, 0x1) != 0 || (perm & 0b0001) != 0,
+            }
+        } else {
+            /* VM_CONTEXT1_PROTECTION_FAULT_STATUS */
+            let prot = field(0, 0xFF);
+            let has = |bit: u32| (prot & (1 << bit)) != 0;
+
+            Self {
+                vmid: field(25, 0xF) as u8,
+                client_id: field(12, 0xFF) as u16,
+                write: field(24, 0x1) != 0,
+                more_faults: false,
+                permission: VmPermissionFaults {
+                    read: has(4),
+                    write: has(5),
+                    execute: has(6),
+                },
+                protection: VmProtectionFaults {
+                    range: has(0),
+                    dummy_page: has(1),
+                    pde0: has(2),
+                    walker_error: 0,
+                },
+                mapping_error: has(3),
+            }
+        }
+    }
+}
+
+impl GpuVmFault {
+    pub fn from_raw(raw: &drm_amdgpu_info_gpuvm_fault, chip_class: CHIP_CLASS) -> Self {
+        Self {
+            addr: raw.addr,
+            status: raw.status,
+            vmhub: raw.vmhub,
+            decoded: GpuVmFaultStatus::decode(raw.status, chip_class),
+        }
+    }
+
+    /// No fault has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.addr == 0 && self.status == 0
+    }
+}
+
+impl DeviceHandle {
+    /// Requires Linux Kernel 6.7+
+    pub fn query_gpuvm_fault_info(&self) -> Result<drm_amdgpu_info_gpuvm_fault, Error> {
+        #[cfg(feature = "link_drm")]
+        unsafe {
+            use core::mem::{size_of, MaybeUninit};
+
+            let mut fault: MaybeUninit<drm_amdgpu_info_gpuvm_fault> = MaybeUninit::zeroed();
+
+            let r = bindings::amdgpu_query_gpuvm_fault_info(
+                self.amdgpu_dev,
+                size_of::<drm_amdgpu_info_gpuvm_fault>() as u32,
+                fault.as_mut_ptr() as *mut ::core::ffi::c_void,
+            );
+
+            crate::query_error!(r, "amdgpu_query_gpuvm_fault_info");
+
+            Ok(fault.assume_init())
+        }
+        /* `amdgpu_query_gpuvm_fault_info` is not available for dynamic loading (libdrm 2.4.119+) */
+        #[cfg(feature = "dynamic_loading")]
+        {
+            self.query(bindings::AMDGPU_INFO_GPUVM_FAULT)
+        }
+    }
+
+    /// Returns the last GPU VM fault decoded for the [CHIP_CLASS] of the device
+    pub fn gpuvm_fault_info(&self) -> Result<GpuVmFault, Error> {
+        let chip_class = self.device_info()?.get_chip_class();
+        let raw = self.query_gpuvm_fault_info()?;
+
+        Ok(GpuVmFault::from_raw(&raw, chip_class))
+    }
+}
+
+#[test]
+fn test_gpuvm_fault_status() {
+    /* GFX9+: VMID 3, CID 0x1B, write, permission fault (write), more faults */
+    let status = (3 << 20) | (1 << 18) | (0x1B << 9) | (0b0100 << 4) | 1;
+    let decoded = GpuVmFaultStatus::decode(status, CHIP_CLASS::GFX10_3);
+
+    assert_eq!(decoded.vmid, 3);
+    assert_eq!(decoded.client_id, 0x1B);
+    assert!(decoded.write && decoded.more_faults);
+    assert_eq!(decoded.permission, VmPermissionFaults { read: false, write: true, execute: false });
+    assert!(!decoded.mapping_error && !decoded.protection.any());
+
+    /* GFX6-8: VMID 5, client 0x20, read, invalid PTE */
+    let status = (5 << 25) | (0x20 << 12) | (1 << 3);
+    let decoded = GpuVmFaultStatus::decode(status, CHIP_CLASS::GFX8);
+
+    assert_eq!(decoded.vmid, 5);
+    assert_eq!(decoded.client_id, 0x20);
+    assert!(!decoded.write);
+    assert!(decoded.mapping_error && !decoded.permission.any());
+}
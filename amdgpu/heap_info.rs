@@ -0,0 +1,195 @@
+use crate::AMDGPU::{BoFlags, DeviceHandle};
+use crate::{query_error, Error};
+use crate::bindings::{self, amdgpu_heap_info, drm_amdgpu_heap_info, drm_amdgpu_memory_info};
+use core::mem::MaybeUninit;
+
+/// Memory heap for [DeviceHandle::heap_info]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heap {
+    Vram,
+    /// CPU-visible VRAM (the BAR)
+    VisibleVram,
+    Gtt,
+}
+
+impl Heap {
+    fn domain_flags(&self) -> (u32, u64) {
+        match self {
+            Self::Vram => (bindings::AMDGPU_GEM_DOMAIN_VRAM, 0),
+            Self::VisibleVram => (
+                bindings::AMDGPU_GEM_DOMAIN_VRAM,
+                bindings::AMDGPU_GEM_CREATE_CPU_ACCESS_REQUIRED as u64,
+            ),
+            Self::Gtt => (bindings::AMDGPU_GEM_DOMAIN_GTT, 0),
+        }
+    }
+
+    fn select_from<'a>(&self, info: &'a drm_amdgpu_memory_info) -> &'a drm_amdgpu_heap_info {
+        match self {
+            Self::Vram => &info.vram,
+            Self::VisibleVram => &info.cpu_accessible_vram,
+            Self::Gtt => &info.gtt,
+        }
+    }
+}
+
+use std::fmt;
+impl fmt::Display for Heap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Size of the heap in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapInfo {
+    pub heap: Heap,
+    /// Physical size of the heap
+    pub total: u64,
+    /// Size of the heap minus the memory reserved/pinned by the kernel driver
+    pub usable: u64,
+    /// Allocated by all processes and the kernel driver
+    pub used: u64,
+    /// Max size of a single buffer
+    pub max_allocation: u64,
+}
+
+impl HeapInfo {
+    /// Memory that can still be allocated
+    pub fn available(&self) -> u64 {
+        self.usable.saturating_sub(self.used)
+    }
+
+    /// Max size of a single buffer that can be allocated now
+    pub fn max_available_allocation(&self) -> u64 {
+        self.available().min(self.max_allocation)
+    }
+
+    fn from_heap_info(heap: Heap, info: &drm_amdgpu_heap_info) -> Self {
+        Self {
+            heap,
+            total: info.total_heap_size,
+            usable: info.usable_heap_size,
+            used: info.heap_usage,
+            max_allocation: info.max_allocation,
+        }
+    }
+}
+
+impl DeviceHandle {
+    /// `flags` are added to the flags implied by `heap`
+    pub fn query_heap_info(&self, heap: Heap, flags: BoFlags) -> Result<amdgpu_heap_info, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_query_heap_info;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_query_heap_info;
+
+        let (domain, heap_flags) = heap.domain_flags();
+
+        unsafe {
+            let mut info: MaybeUninit<amdgpu_heap_info> = MaybeUninit::zeroed();
+
+            let r = func(
+                self.amdgpu_dev,
+                domain,
+                (heap_flags | flags.0) as u32,
+                info.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_query_heap_info");
+
+            Ok(info.assume_init())
+        }
+    }
+
+    /// Combines [DeviceHandle::query_heap_info] (`flags`-aware usable size, usage, max allocation)
+    /// with the physical size from [DeviceHandle::memory_info].
+    pub fn heap_info(&self, heap: Heap, flags: BoFlags) -> Result<HeapInfo, Error> {
+        let info = self.query_heap_info(heap, flags)?;
+        let total = heap.select_from(&self.memory_info()?).total_heap_size;
+
+        Ok(HeapInfo {
+            heap,
+            total,
+            usable: info.heap_size,
+            used: info.heap_usage,
+            max_allocation: info.max_allocation,
+        })
+    }
+
+    /// All heaps from a single `AMDGPU_INFO_MEMORY` query.
+    /// `amdgpu_query_heap_info` is not used here: it issues separate queries per heap
+    /// (so the heaps would not be a consistent snapshot) and adds nothing without [BoFlags].
+    /// Use [DeviceHandle::heap_info] for a heap with specific allocation flags.
+    pub fn memory_report(&self) -> Result<MemoryReport, Error> {
+        self.memory_info().map(|info| MemoryReport::from(&info))
+    }
+}
+
+/// Summary of [Heap]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub vram: HeapInfo,
+    pub visible_vram: HeapInfo,
+    pub gtt: HeapInfo,
+    /// All VRAM is CPU-visible
+    pub resizable_bar: bool,
+}
+
+impl MemoryReport {
+    pub fn heap(&self, heap: Heap) -> &HeapInfo {
+        match heap {
+            Heap::Vram => &self.vram,
+            Heap::VisibleVram => &self.visible_vram,
+            Heap::Gtt => &self.gtt,
+        }
+    }
+
+    /// Memory that can be allocated for CPU-accessible buffers in VRAM.
+    /// Without Resizable BAR, it is limited by the visible VRAM.
+    pub fn available_cpu_accessible_vram(&self) -> u64 {
+        if self.resizable_bar {
+            self.vram.available()
+        } else {
+            self.visible_vram.available().min(self.vram.available())
+        }
+    }
+
+    /// Memory that can be allocated for buffers in VRAM or GTT
+    pub fn available_total(&self) -> u64 {
+        self.vram.available() + self.gtt.available()
+    }
+}
+
+impl From<&drm_amdgpu_memory_info> for MemoryReport {
+    fn from(info: &drm_amdgpu_memory_info) -> Self {
+        Self {
+            vram: HeapInfo::from_heap_info(Heap::Vram, &info.vram),
+            visible_vram: HeapInfo::from_heap_info(Heap::VisibleVram, &info.cpu_accessible_vram),
+            gtt: HeapInfo::from_heap_info(Heap::Gtt, &info.gtt),
+            resizable_bar: info.check_resizable_bar(),
+        }
+    }
+}
+
+#[test]
+fn test_memory_report() {
+    const MIB: u64 = 1 << 20;
+    let heap = |total: u64, usable: u64, used: u64| drm_amdgpu_heap_info {
+        total_heap_size: total * MIB,
+        usable_heap_size: usable * MIB,
+        heap_usage: used * MIB,
+        max_allocation: usable * MIB * 3 / 4,
+    };
+    let info = drm_amdgpu_memory_info {
+        vram: heap(8192, 8000, 2000),
+        cpu_accessible_vram: heap(256, 256, 200),
+        gtt: heap(16384, 16384, 100),
+    };
+    let report = MemoryReport::from(&info);
+
+    assert!(!report.resizable_bar);
+    assert_eq!(report.vram.available(), 6000 * MIB);
+    assert_eq!(report.available_cpu_accessible_vram(), 56 * MIB);
+    assert_eq!(report.heap(Heap::Gtt).max_available_allocation(), 12288 * MIB);
+}
@@ -5,6 +5,7 @@ pub use crate::bindings::drm_amdgpu_info_hw_ip;
 use core::mem::MaybeUninit;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HwIpInfo {
     pub ip_type: HW_IP_TYPE,
     pub info: drm_amdgpu_info_hw_ip,
@@ -12,7 +13,7 @@ pub struct HwIpInfo {
 }
 
 impl DeviceHandle {
-    pub fn get_hw_ip_info(&self, ip_type: HW_IP_TYPE) -> Result<HwIpInfo, i32> {
+    pub fn get_hw_ip_info(&self, ip_type: HW_IP_TYPE) -> Result<HwIpInfo, Error> {
         let info = self.query_hw_ip_info(ip_type, 0)?;
         let count = self.query_hw_ip_count(ip_type)?;
 
@@ -26,7 +27,7 @@ impl DeviceHandle {
     pub fn query_hw_ip_count(
         &self,
         type_: HW_IP_TYPE,
-    ) -> Result<u32, i32> {
+    ) -> Result<u32, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_hw_ip_count;
         #[cfg(feature = "dynamic_loading")]
@@ -43,7 +44,7 @@ impl DeviceHandle {
 
             let hw_ip_count = hw_ip_count.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_hw_ip_count", type_);
 
             Ok(hw_ip_count)
         }
@@ -54,7 +55,7 @@ impl DeviceHandle {
         &self,
         type_: HW_IP_TYPE,
         ip_instance: ::core::ffi::c_uint,
-    ) -> Result<drm_amdgpu_info_hw_ip, i32> {
+    ) -> Result<drm_amdgpu_info_hw_ip, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_hw_ip_info;
         #[cfg(feature = "dynamic_loading")]
@@ -72,7 +73,7 @@ impl DeviceHandle {
 
             let hw_ip_info = hw_ip_info.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_hw_ip_info", type_);
 
             Ok(hw_ip_info)
         }
@@ -105,6 +106,7 @@ use crate::bindings::{
 
 /// Used for [DeviceHandle::query_hw_ip_info] and [DeviceHandle::query_hw_ip_count]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum HW_IP_TYPE {
     GFX = AMDGPU_HW_IP_GFX,
@@ -1,10 +1,11 @@
 use crate::AMDGPU::DeviceHandle;
+use crate::Error;
 use std::fmt;
 use std::path::PathBuf;
 use super::parse_hwmon;
 
 impl DeviceHandle {
-    pub fn get_hwmon_temp(&self, type_: HwmonTempType) -> Option<HwmonTemp> {
+    pub fn get_hwmon_temp(&self, type_: HwmonTempType) -> Result<HwmonTemp, Error> {
         let hwmon_path = self.get_hwmon_path()?;
 
         HwmonTemp::from_hwmon_path(hwmon_path, type_)
@@ -21,28 +22,31 @@ pub struct HwmonTemp {
 }
 
 impl HwmonTemp {
-    pub fn from_hwmon_path<P: Into<PathBuf>>(path: P, type_: HwmonTempType) -> Option<Self> {
+    /// Only `current` is required, the other limits are `None` if they are not exposed.
+    pub fn from_hwmon_path<P: Into<PathBuf>>(path: P, type_: HwmonTempType) -> Result<Self, Error> {
         let path = path.into();
 
         let [current, critical, critical_hyst, emergency] = type_.file_names().map(|name| {
             parse_hwmon::<i64, _>(path.join(name)).map(|v| v.saturating_div(1_000))
         });
-        let current = current?;
 
-        Some(Self {
+        Ok(Self {
             type_,
-            current,
-            critical,
-            critical_hyst,
-            emergency,
+            current: current?,
+            critical: critical.ok(),
+            critical_hyst: critical_hyst.ok(),
+            emergency: emergency.ok(),
         })
     }
 
-    pub fn update<P: Into<PathBuf>>(&mut self, path: P) {
+    /// `current` is left unchanged on error.
+    pub fn update<P: Into<PathBuf>>(&mut self, path: P) -> Result<(), Error> {
         let name = self.type_.current_temp_file_name();
-        if let Some(v) = parse_hwmon::<i64, _>(path.into().join(name)) {
-            self.current = v.saturating_div(1_000);
-        }
+        let v = parse_hwmon::<i64, _>(path.into().join(name))?;
+
+        self.current = v.saturating_div(1_000);
+
+        Ok(())
     }
 }
 
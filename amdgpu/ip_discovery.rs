@@ -1,8 +1,10 @@
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use crate::Error;
 
 /* ref: drivers/gpu/drm/amd/amdgpu/amdgpu_discovery.c */
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IpHwInstance {
     pub hw_id: HwId,
     pub num_instance: u8,
@@ -82,6 +84,7 @@ impl IpHwInstance {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IpHwId {
     pub hw_id: HwId,
     pub instances: Vec<IpHwInstance>,
@@ -89,14 +92,14 @@ pub struct IpHwId {
 
 impl IpHwId {
     /// die_id_path: `ip_discovery/die/#die/`
-    pub fn get_from_die_id_sysfs<P: AsRef<Path>>(hw_id: HwId, die_id_path: P) -> Result<Self, std::io::Error> {
+    pub fn get_from_die_id_sysfs<P: AsRef<Path>>(hw_id: HwId, die_id_path: P) -> Result<Self, Error> {
         Self::get_from_ip_hw_sysfs(hw_id, &die_id_path.as_ref().join(hw_id.to_string()))
     }
 
     /// ip_hw_path: `ip_discovery/die/#die/#hw_id/`
-    pub fn get_from_ip_hw_sysfs<P: AsRef<Path>>(hw_id: HwId, ip_hw_path: P) -> Result<Self, std::io::Error> {
+    pub fn get_from_ip_hw_sysfs<P: AsRef<Path>>(hw_id: HwId, ip_hw_path: P) -> Result<Self, Error> {
         let path = ip_hw_path.as_ref();
-        let inst_count = std::fs::read_dir(path)?.count(); // use count for the order
+        let inst_count = std::fs::read_dir(path).map_err(|e| Error::io(path, e))?.count(); // use count for the order
 
         Ok(IpHwId {
             hw_id,
@@ -109,6 +112,7 @@ impl IpHwId {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IpDieEntry {
     pub die_id: usize,
     pub ip_hw_ids: Vec<IpHwId>,
@@ -234,6 +238,7 @@ const XGBE_HWID: isize = 216;
 const MP0_HWID: isize = 255;
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(isize)]
 pub enum HwId {
     MP1 = self::MP1_HWID,
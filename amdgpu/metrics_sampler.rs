@@ -0,0 +1,513 @@
+use crate::AMDGPU::{GpuMetrics, MetricsInfo, ThrottlerBit};
+use crate::Error;
+use super::sampler_thread::SamplerThread;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Values summarized by [MetricsSeries::summary]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricsField {
+    /// Celsius
+    TemperatureEdge,
+    /// Celsius
+    TemperatureHotspot,
+    /// Celsius
+    TemperatureMem,
+    /// Celsius (APU), converted from centi-Celsius
+    TemperatureGfx,
+    /// Celsius (APU), converted from centi-Celsius
+    TemperatureSoc,
+    /// %
+    GfxActivity,
+    /// %
+    UmcActivity,
+    /// %
+    MmActivity,
+    /// Watts, converted from mW for APU (v2_x/v3_0 tables)
+    SocketPower,
+    /// MHz
+    AverageGfxclk,
+    /// MHz
+    CurrentGfxclk,
+    /// MHz
+    CurrentUclk,
+    /// RPM
+    FanSpeed,
+}
+
+impl MetricsField {
+    pub const ALL: [Self; 13] = [
+        Self::TemperatureEdge,
+        Self::TemperatureHotspot,
+        Self::TemperatureMem,
+        Self::TemperatureGfx,
+        Self::TemperatureSoc,
+        Self::GfxActivity,
+        Self::UmcActivity,
+        Self::MmActivity,
+        Self::SocketPower,
+        Self::AverageGfxclk,
+        Self::CurrentGfxclk,
+        Self::CurrentUclk,
+        Self::FanSpeed,
+    ];
+
+    /// Returns `None` if the table does not have the field, or the value is `0xFFFF` (unsupported)
+    pub fn value<T: MetricsInfo>(&self, metrics: &T) -> Option<u32> {
+        let v = match self {
+            Self::TemperatureEdge => metrics.get_temperature_edge(),
+            Self::TemperatureHotspot => metrics.get_temperature_hotspot(),
+            Self::TemperatureMem => metrics.get_temperature_mem(),
+            /* centi-Celsius (ref: drivers/gpu/drm/amd/include/kgd_pp_interface.h) */
+            Self::TemperatureGfx | Self::TemperatureSoc => {
+                let v = if *self == Self::TemperatureGfx {
+                    metrics.get_temperature_gfx()
+                } else {
+                    metrics.get_temperature_soc()
+                };
+
+                return v.filter(|v| *v != u16::MAX).map(|v| v as u32 / 100);
+            },
+            Self::GfxActivity => metrics.get_average_gfx_activity(),
+            Self::UmcActivity => metrics.get_average_umc_activity(),
+            Self::MmActivity => metrics.get_average_mm_activity(),
+            /* v2_x/v3_0: mW (ref: drivers/gpu/drm/amd/include/kgd_pp_interface.h) */
+            Self::SocketPower => {
+                let div = match metrics.get_header().map(|h| h.format_revision) {
+                    Some(2 | 3) => 1000,
+                    _ => 1,
+                };
+
+                return metrics.get_average_socket_power()
+                    .filter(|v| *v != u16::MAX as u32 && *v != u32::MAX)
+                    .map(|v| v / div);
+            },
+            Self::AverageGfxclk => metrics.get_average_gfxclk_frequency(),
+            Self::CurrentGfxclk => metrics.get_current_gfxclk(),
+            Self::CurrentUclk => metrics.get_current_uclk(),
+            Self::FanSpeed => metrics.get_current_fan_speed(),
+        };
+
+        v.filter(|v| *v != u16::MAX).map(|v| v as u32)
+    }
+}
+
+use std::fmt;
+impl fmt::Display for MetricsField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Average and nearest-rank percentiles of a [MetricsField] over the samples
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StatSummary {
+    pub samples: u32,
+    pub min: u32,
+    pub max: u32,
+    pub average: f32,
+    pub p50: u32,
+    pub p90: u32,
+    pub p99: u32,
+}
+
+impl StatSummary {
+    /// Returns `None` if `values` is empty
+    pub fn from_values(values: &mut [u32]) -> Option<Self> {
+        values.sort_unstable();
+
+        let sum: u64 = values.iter().map(|v| *v as u64).sum();
+
+        Some(Self {
+            samples: values.len() as u32,
+            min: *values.first()?,
+            max: *values.last()?,
+            average: sum as f32 / values.len() as f32,
+            p50: percentile(values, 50.0)?,
+            p90: percentile(values, 90.0)?,
+            p99: percentile(values, 99.0)?,
+        })
+    }
+}
+
+/// Nearest-rank percentile, `sorted` must be sorted in ascending order
+pub fn percentile(sorted: &[u32], p: f32) -> Option<u32> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+/// Statistics over the samples in [MetricsSeries]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsStats {
+    pub samples: u32,
+    /// Nanoseconds between the first and the last `system_clock_counter`
+    pub duration_ns: u64,
+    /// Joules, from `energy_accumulator` deltas.
+    /// `None` if the table does not have it or the SMU does not update it.
+    pub energy: Option<f64>,
+    /// [MetricsField::ALL] with values
+    pub summaries: Vec<(MetricsField, StatSummary)>,
+    /// Number of samples with `indep_throttle_status`
+    pub throttle_samples: u32,
+    /// Number of samples each bit of `indep_throttle_status` is set
+    pub throttle_count: [u32; 64],
+}
+
+impl MetricsStats {
+    pub fn summary(&self, field: MetricsField) -> Option<&StatSummary> {
+        self.summaries.iter().find_map(|(f, s)| (*f == field).then_some(s))
+    }
+
+    /// Watts, `energy / duration`
+    pub fn average_power(&self) -> Option<f64> {
+        if self.duration_ns == 0 {
+            return None;
+        }
+
+        Some(self.energy? * 1_000_000_000.0 / self.duration_ns as f64)
+    }
+
+    /// Percentage of samples throttled by `thr`, returns 0.0 if there is no sample
+    pub fn throttle_residency(&self, thr: ThrottlerBit) -> f32 {
+        if self.throttle_samples == 0 || thr == ThrottlerBit::Unknown {
+            return 0.0;
+        }
+
+        self.throttle_count[thr as usize] as f32 * 100.0 / self.throttle_samples as f32
+    }
+
+    /// Throttlers set at least once, with the residency (%)
+    pub fn all_throttle_residency(&self) -> Vec<(ThrottlerBit, f32)> {
+        if self.throttle_samples == 0 {
+            return Vec::new();
+        }
+
+        self.throttle_count
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count != 0)
+            .map(|(i, count)| {
+                (ThrottlerBit::from(i as u8), *count as f32 * 100.0 / self.throttle_samples as f32)
+            })
+            .collect()
+    }
+}
+
+/// Ring buffer of [GpuMetrics], de-duplicated on `system_clock_counter`.
+/// The SMU updates the table at its own rate, polling faster returns the same table.
+#[derive(Debug, Clone)]
+pub struct MetricsSeries {
+    samples: VecDeque<GpuMetrics>,
+    window: usize,
+}
+
+impl MetricsSeries {
+    /// Energy unit of `energy_accumulator` (15.259uJ)
+    pub const ENERGY_UNIT: f64 = 1.0 / 65536.0;
+
+    /// `window` is the number of samples to keep (at least 2)
+    pub fn new(window: usize) -> Self {
+        let window = window.max(2);
+
+        Self { samples: VecDeque::with_capacity(window), window }
+    }
+
+    /// For recorded `gpu_metrics` files
+    pub fn from_raw_blobs<I, B>(blobs: I, window: usize) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let mut series = Self::new(window);
+
+        for blob in blobs {
+            series.push_raw(blob.as_ref());
+        }
+
+        series
+    }
+
+    /// Returns `false` if the table is unknown or has the same `system_clock_counter`
+    /// as the last sample
+    pub fn push(&mut self, metrics: GpuMetrics) -> bool {
+        if let GpuMetrics::Unknown = metrics {
+            return false;
+        }
+
+        if let Some(last) = self.samples.back()
+        && let Some(counter) = metrics.get_system_clock_counter()
+        && last.get_system_clock_counter() == Some(counter) {
+            return false;
+        }
+
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(metrics);
+
+        true
+    }
+
+    pub fn push_raw(&mut self, raw: &[u8]) -> bool {
+        self.push(GpuMetrics::from_raw(raw))
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &GpuMetrics> {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn summary(&self, field: MetricsField) -> Option<StatSummary> {
+        let mut values: Vec<u32> = self.samples.iter().filter_map(|m| field.value(m)).collect();
+
+        StatSummary::from_values(&mut values)
+    }
+
+    /// Nearest-rank percentile of `field`
+    pub fn percentile(&self, field: MetricsField, p: f32) -> Option<u32> {
+        let mut values: Vec<u32> = self.samples.iter().filter_map(|m| field.value(m)).collect();
+        values.sort_unstable();
+
+        percentile(&values, p)
+    }
+
+    /// Nanoseconds
+    pub fn duration(&self) -> u64 {
+        let first = self.samples.front().and_then(|m| m.get_system_clock_counter());
+        let last = self.samples.back().and_then(|m| m.get_system_clock_counter());
+
+        match (first, last) {
+            (Some(first), Some(last)) => last.saturating_sub(first),
+            _ => 0,
+        }
+    }
+
+    /// Joules, the sum of `energy_accumulator` deltas.
+    /// The u32 accumulator of [crate::AMDGPU::gpu_metrics_v1_0] wraps around,
+    /// a decreasing u64 accumulator (SMU reset) is treated as a restart of the accumulation.
+    pub fn energy(&self) -> Option<f64> {
+        let mut acc = self.samples
+            .iter()
+            .filter_map(|m| Some((matches!(m, GpuMetrics::V1_0(_)), m.get_energy_accumulator()?)));
+        let (_, mut prev) = acc.next()?;
+        let mut total: u64 = 0;
+        let mut updated = false;
+
+        for (is_u32, cur) in acc {
+            updated |= cur != prev;
+            total += if is_u32 {
+                cur.wrapping_sub(prev) & u32::MAX as u64
+            } else {
+                cur.saturating_sub(prev)
+            };
+            prev = cur;
+        }
+
+        updated.then_some(total as f64 * Self::ENERGY_UNIT)
+    }
+
+    pub fn stats(&self) -> MetricsStats {
+        let mut throttle_samples = 0;
+        let mut throttle_count = [0u32; 64];
+
+        for thr in self.samples.iter().filter_map(|m| m.get_indep_throttle_status()) {
+            throttle_samples += 1;
+
+            for (i, count) in throttle_count.iter_mut().enumerate() {
+                *count += ((thr >> i) & 0b1) as u32;
+            }
+        }
+
+        let summaries = MetricsField::ALL
+            .iter()
+            .filter_map(|field| Some((*field, self.summary(*field)?)))
+            .collect();
+
+        MetricsStats {
+            samples: self.samples.len() as u32,
+            duration_ns: self.duration(),
+            energy: self.energy(),
+            summaries,
+            throttle_samples,
+            throttle_count,
+        }
+    }
+}
+
+/// Polls `gpu_metrics` on a background thread.
+/// The thread is stopped when [MetricsSampler] is dropped.
+#[derive(Debug)]
+pub struct MetricsSampler {
+    series: Arc<Mutex<MetricsSeries>>,
+    thread: SamplerThread,
+}
+
+impl MetricsSampler {
+    /// Statistics are computed over the last `window` unique samples.
+    /// Failed reads are skipped.
+    pub fn spawn<P: Into<PathBuf>>(
+        sysfs_path: P,
+        interval: Duration,
+        window: usize,
+    ) -> Result<Self, Error> {
+        let path = sysfs_path.into().join("gpu_metrics");
+        let series = Arc::new(Mutex::new(MetricsSeries::new(window)));
+
+        let thread = {
+            let series = series.clone();
+            let mut buf: Vec<u8> = Vec::with_capacity(256);
+
+            SamplerThread::spawn("MetricsSampler", interval, move || {
+                buf.clear();
+
+                if let Ok(metrics) = GpuMetrics::read_file_with_buffer(&mut buf, &path) {
+                    series.lock().unwrap().push(metrics);
+                }
+            })?
+        };
+
+        Ok(Self { series, thread })
+    }
+
+    pub fn stats(&self) -> MetricsStats {
+        self.series.lock().unwrap().stats()
+    }
+
+    pub fn latest(&self) -> Option<GpuMetrics> {
+        self.series.lock().unwrap().samples.back().cloned()
+    }
+
+    pub fn clear(&self) {
+        self.series.lock().unwrap().clear();
+    }
+
+    pub fn stop(mut self) {
+        self.thread.stop();
+    }
+}
+
+#[test]
+fn test_metrics_series() {
+    use crate::AMDGPU::{gpu_metrics_v1_0, gpu_metrics_v1_3, gpu_metrics_v2_0, metrics_table_header};
+
+    let blob = |counter: u64, energy: u64, power: u16, edge: u16, thr: u64| -> Vec<u8> {
+        let mut m: gpu_metrics_v1_3 = unsafe { core::mem::zeroed() };
+
+        m.common_header = metrics_table_header {
+            structure_size: size_of::<gpu_metrics_v1_3>() as u16,
+            format_revision: 1,
+            content_revision: 3,
+        };
+        m.system_clock_counter = counter;
+        m.energy_accumulator = energy;
+        m.average_socket_power = power;
+        m.temperature_edge = edge;
+        m.temperature_mem = 0xFFFF;
+        m.indep_throttle_status = thr;
+
+        unsafe {
+            std::slice::from_raw_parts(
+                &m as *const gpu_metrics_v1_3 as *const u8,
+                size_of::<gpu_metrics_v1_3>(),
+            ).to_vec()
+        }
+    };
+
+    const SEC: u64 = 1_000_000_000;
+    /* PPT0, TEMP_EDGE */
+    let (ppt0, edge) = (1 << 0, 1 << 35);
+
+    let blobs = [
+        blob(SEC, 0, 100, 50, 0),
+        /* not updated by the SMU */
+        blob(SEC, 0, 100, 50, 0),
+        blob(2 * SEC, 65536 * 150, 150, 60, ppt0),
+        blob(3 * SEC, 65536 * 350, 200, 70, ppt0 | edge),
+        blob(4 * SEC, 65536 * 550, 200, 80, ppt0),
+        vec![0u8; 4],
+    ];
+    let series = MetricsSeries::from_raw_blobs(&blobs, 16);
+    let stats = series.stats();
+
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.duration_ns, 3 * SEC);
+    assert_eq!(stats.energy, Some(550.0));
+    assert_eq!(stats.average_power().map(|p| p.round()), Some(183.0));
+
+    let power = stats.summary(MetricsField::SocketPower).unwrap();
+    assert_eq!(power.average, 162.5);
+    assert_eq!((power.min, power.p50, power.p90, power.max), (100, 150, 200, 200));
+
+    assert_eq!(series.percentile(MetricsField::TemperatureEdge, 25.0), Some(50));
+    assert_eq!(series.percentile(MetricsField::TemperatureEdge, 100.0), Some(80));
+    assert!(stats.summary(MetricsField::TemperatureMem).is_none());
+
+    assert_eq!(stats.throttle_residency(ThrottlerBit::PPT0), 75.0);
+    assert_eq!(stats.throttle_residency(ThrottlerBit::TEMP_EDGE), 25.0);
+    assert_eq!(
+        stats.all_throttle_residency(),
+        vec![(ThrottlerBit::PPT0, 75.0), (ThrottlerBit::TEMP_EDGE, 25.0)],
+    );
+
+    /* the window keeps the last 2 samples */
+    let series = MetricsSeries::from_raw_blobs(&blobs, 2);
+    assert_eq!(series.energy(), Some(200.0));
+
+    /* u32 energy_accumulator of gpu_metrics_v1_0 wraps around */
+    let blob_v1_0 = |counter: u64, energy: u32| -> Vec<u8> {
+        let mut m: gpu_metrics_v1_0 = unsafe { core::mem::zeroed() };
+
+        m.common_header = metrics_table_header {
+            structure_size: size_of::<gpu_metrics_v1_0>() as u16,
+            format_revision: 1,
+            content_revision: 0,
+        };
+        m.system_clock_counter = counter;
+        m.energy_accumulator = energy;
+
+        unsafe {
+            std::slice::from_raw_parts(
+                &m as *const gpu_metrics_v1_0 as *const u8,
+                size_of::<gpu_metrics_v1_0>(),
+            ).to_vec()
+        }
+    };
+    let series = MetricsSeries::from_raw_blobs(
+        [blob_v1_0(SEC, u32::MAX - 65535), blob_v1_0(2 * SEC, 65536)],
+        16,
+    );
+    assert_eq!(series.energy(), Some(2.0));
+
+    /* APU temperatures are centi-Celsius */
+    let mut m: gpu_metrics_v2_0 = unsafe { core::mem::zeroed() };
+    m.temperature_gfx = 4550;
+    assert_eq!(MetricsField::TemperatureGfx.value(&m), Some(45));
+
+    /* APU power is mW */
+    m.common_header = metrics_table_header {
+        structure_size: size_of::<gpu_metrics_v2_0>() as u16,
+        format_revision: 2,
+        content_revision: 0,
+    };
+    m.average_socket_power = 15500;
+    assert_eq!(MetricsField::SocketPower.value(&m), Some(15));
+}
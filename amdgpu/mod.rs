@@ -16,6 +16,12 @@ pub const DEFAULT_DEVICE_NAME: &str = "AMD Radeon Graphics";
 mod device_handle;
 pub use device_handle::*;
 
+mod device_enumerator;
+pub use device_enumerator::*;
+
+mod uevent;
+pub use uevent::*;
+
 mod family;
 pub use family::*;
 
@@ -37,33 +43,89 @@ pub use gfx_target_version::GfxTargetVersion;
 mod context;
 pub use context::*;
 
+mod buffer_object;
+pub use buffer_object::*;
+
+mod command_submission;
+pub use command_submission::*;
+
+mod syncobj;
+pub use syncobj::*;
+
+mod va_range;
+pub use va_range::*;
+
+mod gpuvm_fault;
+pub use gpuvm_fault::*;
+
+mod heap_info;
+pub use heap_info::*;
+
+mod status_reg;
+pub use status_reg::*;
+
+mod sampler_thread;
+
+mod register_sampler;
+pub use register_sampler::*;
+
+mod fdinfo;
+pub use fdinfo::*;
+
 mod gpu_metrics;
 pub use gpu_metrics::*;
 
 mod throttle_status;
 pub use throttle_status::*;
 
+mod metrics_sampler;
+pub use metrics_sampler::*;
+
+mod telemetry_record;
+pub use telemetry_record::*;
+
+#[cfg(feature = "exporter")]
+mod open_metrics;
+#[cfg(feature = "exporter")]
+pub use open_metrics::*;
+
 mod hwmon_temp;
 pub use hwmon_temp::*;
 
+mod fan_control;
+pub use fan_control::*;
+
 mod power_cap;
 pub use power_cap::*;
 
 mod power_profile;
 pub use power_profile::*;
 
+mod power_profile_mode;
+pub use power_profile_mode::*;
+
 mod dpm_forced_level;
 pub use dpm_forced_level::*;
 
+mod dpm_clock;
+pub use dpm_clock::*;
+
+mod od_clk_voltage;
+pub use od_clk_voltage::*;
+
 mod ip_discovery;
 pub use ip_discovery::*;
 
 mod ras_features;
 pub use ras_features::*;
 
-pub(crate) fn parse_hwmon<T: std::str::FromStr, P: Into<std::path::PathBuf>>(path: P) -> Option<T> {
-    std::fs::read_to_string(path.into()).ok()
-        .and_then(|file| file.trim_end().parse::<T>().ok())
+pub(crate) fn parse_hwmon<T: std::str::FromStr, P: Into<std::path::PathBuf>>(
+    path: P,
+) -> Result<T, crate::Error> {
+    let path = path.into();
+    let s = crate::error::read_sysfs(&path)?;
+
+    s.trim_end().parse::<T>().map_err(|_| crate::Error::parse(&path, s.trim_end()))
 }
 
 mod vbios;
@@ -72,6 +134,8 @@ mod video_caps;
 mod hw_ip;
 mod fw_version;
 mod sensor_info;
+mod pm4;
+mod sdma;
 
 pub mod pp_table;
 pub use pp_table::PPTable;
@@ -136,9 +200,20 @@ pub mod FW_VERSION {
 pub mod SENSOR_INFO {
     pub use super::sensor_info::*;
 }
+pub mod PM4 {
+    pub use super::pm4::*;
+}
+pub mod SDMA {
+    pub use super::sdma::*;
+}
 
 pub fn get_all_amdgpu_pci_bus() -> Vec<PCI::BUS_INFO> {
-    let Ok(amdgpu_devices) = std::fs::read_dir("/sys/bus/pci/drivers/amdgpu") else { return Vec::new() };
+    get_all_amdgpu_pci_bus_with_root(&SysfsRoot::global())
+}
+
+/// Find all [PCI::BUS_INFO] bound to the amdgpu driver under `root`
+pub fn get_all_amdgpu_pci_bus_with_root(root: &SysfsRoot) -> Vec<PCI::BUS_INFO> {
+    let Ok(amdgpu_devices) = std::fs::read_dir(root.amdgpu_driver_path()) else { return Vec::new() };
 
     amdgpu_devices.flat_map(|v| {
         let name = v.ok()?.file_name();
@@ -0,0 +1,520 @@
+/*
+    ref: drivers/gpu/drm/amd/pm/amdgpu_pm.c (amdgpu_set_pp_od_clk_voltage)
+    ref: drivers/gpu/drm/amd/pm/powerplay/hwmgr/smu7_hwmgr.c
+    ref: drivers/gpu/drm/amd/pm/powerplay/hwmgr/vega10_hwmgr.c
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu11/navi10_ppt.c
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu11/sienna_cichlid_ppt.c
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu13/smu_v13_0_0_ppt.c
+*/
+
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "pp_od_clk_voltage";
+
+/// `OD_SCLK`/`OD_MCLK` level.
+/// `voltage` is only reported on Polaris/Vega10 (SMU7, Vega10 hwmgr)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OdLevel {
+    pub index: u32,
+    /// MHz
+    pub clock: i32,
+    /// mV
+    pub voltage: Option<i32>,
+}
+
+/// `OD_VDDC_CURVE` point (Vega20, Navi1x)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OdCurvePoint {
+    pub index: u32,
+    /// MHz
+    pub clock: i32,
+    /// mV
+    pub voltage: i32,
+}
+
+/// `OD_RANGE`, `[min, max]`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OdRange {
+    /// MHz
+    pub sclk: Option<[i32; 2]>,
+    /// MHz
+    pub mclk: Option<[i32; 2]>,
+    /// mV
+    pub vddc: Option<[i32; 2]>,
+    /// MHz
+    pub sclk_offset: Option<[i32; 2]>,
+    /// mV
+    pub vddgfx_offset: Option<[i32; 2]>,
+    /// `VDDC_CURVE_SCLK[N]` (MHz)
+    pub vddc_curve_sclk: Vec<[i32; 2]>,
+    /// `VDDC_CURVE_VOLT[N]` (mV)
+    pub vddc_curve_volt: Vec<[i32; 2]>,
+}
+
+/// Parsed `pp_od_clk_voltage`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OdClkVoltage {
+    /// `OD_SCLK`
+    pub sclk: Vec<OdLevel>,
+    /// `OD_MCLK`
+    pub mclk: Vec<OdLevel>,
+    /// `OD_VDDC_CURVE`
+    pub vddc_curve: Vec<OdCurvePoint>,
+    /// `OD_SCLK_OFFSET` (MHz)
+    pub sclk_offset: Option<i32>,
+    /// `OD_VDDGFX_OFFSET` (mV)
+    pub vddgfx_offset: Option<i32>,
+    pub range: OdRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OdSection {
+    Sclk,
+    Mclk,
+    VddcCurve,
+    SclkOffset,
+    VddgfxOffset,
+    Range,
+    Unknown,
+}
+
+fn parse_with_unit(s: &str, unit: &str) -> Option<i32> {
+    let s = s.trim();
+    let i = s.len().checked_sub(unit.len())?;
+    let (num, u) = (s.get(..i)?, s.get(i..)?);
+
+    if !u.eq_ignore_ascii_case(unit) {
+        return None;
+    }
+
+    num.trim().parse().ok()
+}
+
+fn parse_mhz(s: &str) -> Option<i32> {
+    parse_with_unit(s, "MHz")
+}
+
+fn parse_mv(s: &str) -> Option<i32> {
+    parse_with_unit(s, "mV")
+}
+
+impl OdClkVoltage {
+    pub fn from_sysfs<P: Into<PathBuf>>(sysfs_path: P) -> Result<Self, Error> {
+        let path = sysfs_path.into().join(FILE_NAME);
+        let s = read_sysfs(&path)?;
+
+        Self::parse(&s).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    /// Returns `None` if a line of known sections cannot be parsed
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut od = Self::default();
+        let mut section = OdSection::Unknown;
+
+        for line in s.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+            if let Some(name) = line.strip_suffix(':').filter(|name| name.starts_with("OD_")) {
+                section = match name {
+                    "OD_SCLK" => OdSection::Sclk,
+                    "OD_MCLK" => OdSection::Mclk,
+                    "OD_VDDC_CURVE" => OdSection::VddcCurve,
+                    "OD_SCLK_OFFSET" => OdSection::SclkOffset,
+                    "OD_VDDGFX_OFFSET" => OdSection::VddgfxOffset,
+                    "OD_RANGE" => OdSection::Range,
+                    _ => OdSection::Unknown,
+                };
+
+                continue;
+            }
+
+            match section {
+                OdSection::Sclk => od.sclk.push(Self::parse_level(line)?),
+                OdSection::Mclk => od.mclk.push(Self::parse_level(line)?),
+                OdSection::VddcCurve => {
+                    let level = Self::parse_level(line)?;
+
+                    od.vddc_curve.push(OdCurvePoint {
+                        index: level.index,
+                        clock: level.clock,
+                        voltage: level.voltage?,
+                    });
+                },
+                OdSection::SclkOffset => od.sclk_offset = Some(parse_mhz(line)?),
+                OdSection::VddgfxOffset => od.vddgfx_offset = Some(parse_mv(line)?),
+                OdSection::Range => od.parse_range(line)?,
+                OdSection::Unknown => {},
+            }
+        }
+
+        Some(od)
+    }
+
+    /// `0:        300MHz        800mV` or `0: 800Mhz`
+    fn parse_level(line: &str) -> Option<OdLevel> {
+        let (index, values) = line.split_once(':')?;
+        let mut values = values.split_whitespace();
+        let clock = parse_mhz(values.next()?)?;
+        let voltage = match values.next() {
+            Some(v) => Some(parse_mv(v)?),
+            None => None,
+        };
+
+        Some(OdLevel { index: index.trim().parse().ok()?, clock, voltage })
+    }
+
+    /// `SCLK:     300MHz       2000MHz`
+    fn parse_range(&mut self, line: &str) -> Option<()> {
+        let (name, values) = line.split_once(':')?;
+        let mut values = values.split_whitespace();
+        let [min, max] = [values.next()?, values.next()?];
+        let mhz = || Some([parse_mhz(min)?, parse_mhz(max)?]);
+        let mv = || Some([parse_mv(min)?, parse_mv(max)?]);
+
+        match name.trim() {
+            "SCLK" => self.range.sclk = Some(mhz()?),
+            "MCLK" => self.range.mclk = Some(mhz()?),
+            "VDDC" => self.range.vddc = Some(mv()?),
+            "SCLK_OFFSET" => self.range.sclk_offset = Some(mhz()?),
+            "VDDGFX_OFFSET" => self.range.vddgfx_offset = Some(mv()?),
+            name if name.starts_with("VDDC_CURVE_SCLK[") => self.range.vddc_curve_sclk.push(mhz()?),
+            name if name.starts_with("VDDC_CURVE_VOLT[") => self.range.vddc_curve_volt.push(mv()?),
+            _ => {},
+        }
+
+        Some(())
+    }
+
+    /// Check `cmd` against the current table and `OD_RANGE`
+    pub fn validate(&self, cmd: &OdCommand) -> Result<(), Error> {
+        match *cmd {
+            OdCommand::Sclk { index, clock, voltage } =>
+                Self::validate_level(&self.sclk, self.range.sclk, self.range.vddc, index, clock, voltage),
+            OdCommand::Mclk { index, clock, voltage } =>
+                Self::validate_level(&self.mclk, self.range.mclk, self.range.vddc, index, clock, voltage),
+            OdCommand::VddcCurve { index, clock, voltage } => {
+                let i = Self::find_index(self.vddc_curve.iter().map(|p| p.index), index)?;
+
+                check_range(clock, self.range.vddc_curve_sclk.get(i).copied())?;
+                check_range(voltage, self.range.vddc_curve_volt.get(i).copied())
+            },
+            OdCommand::SclkOffset(offset) => {
+                if self.sclk_offset.is_none() {
+                    return Err(Error::InvalidArgument("OD_SCLK_OFFSET is not supported"));
+                }
+
+                check_range(offset, self.range.sclk_offset)
+            },
+            OdCommand::VddgfxOffset(offset) => {
+                if self.vddgfx_offset.is_none() {
+                    return Err(Error::InvalidArgument("OD_VDDGFX_OFFSET is not supported"));
+                }
+
+                check_range(offset, self.range.vddgfx_offset)
+            },
+            OdCommand::Commit | OdCommand::Reset => Ok(()),
+        }
+    }
+
+    fn validate_level(
+        levels: &[OdLevel],
+        clock_range: Option<[i32; 2]>,
+        voltage_range: Option<[i32; 2]>,
+        index: u32,
+        clock: i32,
+        voltage: Option<i32>,
+    ) -> Result<(), Error> {
+        let i = Self::find_index(levels.iter().map(|l| l.index), index)?;
+
+        check_range(clock, clock_range)?;
+
+        match (levels[i].voltage, voltage) {
+            (Some(_), Some(voltage)) => check_range(voltage, voltage_range),
+            (Some(_), None) => Err(Error::InvalidArgument("voltage is required")),
+            (None, Some(_)) => Err(Error::InvalidArgument("voltage is not supported")),
+            (None, None) => Ok(()),
+        }
+    }
+
+    fn find_index<I: Iterator<Item = u32> + Clone>(mut indices: I, index: u32) -> Result<usize, Error> {
+        let max = indices.clone().max();
+
+        indices.position(|i| i == index).ok_or(Error::OutOfRange {
+            value: index as i64,
+            min: 0,
+            max: max.map_or(-1, |v| v as i64),
+        })
+    }
+
+    /// Validate `cmd` and update the table
+    pub fn apply(&mut self, cmd: &OdCommand) -> Result<(), Error> {
+        self.validate(cmd)?;
+
+        match *cmd {
+            OdCommand::Sclk { index, clock, voltage } |
+            OdCommand::Mclk { index, clock, voltage } => {
+                let levels = if matches!(cmd, OdCommand::Sclk { .. }) {
+                    &mut self.sclk
+                } else {
+                    &mut self.mclk
+                };
+                let Some(level) = levels.iter_mut().find(|l| l.index == index) else { return Ok(()) };
+
+                level.clock = clock;
+                level.voltage = voltage;
+            },
+            OdCommand::VddcCurve { index, clock, voltage } => {
+                let Some(point) = self.vddc_curve.iter_mut().find(|p| p.index == index)
+                    else { return Ok(()) };
+
+                point.clock = clock;
+                point.voltage = voltage;
+            },
+            OdCommand::SclkOffset(offset) => self.sclk_offset = Some(offset),
+            OdCommand::VddgfxOffset(offset) => self.vddgfx_offset = Some(offset),
+            OdCommand::Commit | OdCommand::Reset => {},
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn check_range(value: i32, range: Option<[i32; 2]>) -> Result<(), Error> {
+    let Some([min, max]) = range else { return Ok(()) };
+
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::OutOfRange { value: value as i64, min: min as i64, max: max as i64 })
+    }
+}
+
+/// Command written to `pp_od_clk_voltage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdCommand {
+    /// `s <index> <clock> [<voltage>]`
+    Sclk { index: u32, clock: i32, voltage: Option<i32> },
+    /// `m <index> <clock> [<voltage>]`
+    Mclk { index: u32, clock: i32, voltage: Option<i32> },
+    /// `vc <index> <clock> <voltage>`
+    VddcCurve { index: u32, clock: i32, voltage: i32 },
+    /// `s <offset>`
+    SclkOffset(i32),
+    /// `vo <offset>`
+    VddgfxOffset(i32),
+    /// `c`
+    Commit,
+    /// `r`, restore the default table
+    Reset,
+}
+
+impl OdCommand {
+    /// Requires root and `amdgpu.ppfeaturemask` with the overdrive bit
+    pub fn write_to_sysfs<P: Into<PathBuf>>(&self, sysfs_path: P) -> Result<(), Error> {
+        write_sysfs(sysfs_path.into().join(FILE_NAME), &self.to_string())
+    }
+}
+
+use std::fmt;
+impl fmt::Display for OdCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sclk { index, clock, voltage } |
+            Self::Mclk { index, clock, voltage } => {
+                let cmd = if matches!(self, Self::Sclk { .. }) { "s" } else { "m" };
+
+                write!(f, "{cmd} {index} {clock}")?;
+
+                if let Some(voltage) = voltage {
+                    write!(f, " {voltage}")?;
+                }
+
+                Ok(())
+            },
+            Self::VddcCurve { index, clock, voltage } => write!(f, "vc {index} {clock} {voltage}"),
+            Self::SclkOffset(offset) => write!(f, "s {offset}"),
+            Self::VddgfxOffset(offset) => write!(f, "vo {offset}"),
+            Self::Commit => write!(f, "c"),
+            Self::Reset => write!(f, "r"),
+        }
+    }
+}
+
+/// Validated edits of [OdClkVoltage]
+#[derive(Debug, Clone)]
+pub struct OdEditor {
+    pub table: OdClkVoltage,
+    commands: Vec<OdCommand>,
+}
+
+impl OdEditor {
+    pub fn new(table: OdClkVoltage) -> Self {
+        Self { table, commands: Vec::new() }
+    }
+
+    /// [OdCommand::Commit] is added by [OdEditor::commit_to_sysfs],
+    /// use [OdCommand::write_to_sysfs] to write [OdCommand::Reset]
+    pub fn push(&mut self, cmd: OdCommand) -> Result<&mut Self, Error> {
+        if matches!(cmd, OdCommand::Commit | OdCommand::Reset) {
+            return Err(Error::InvalidArgument("commit/reset is not an edit"));
+        }
+
+        self.table.apply(&cmd)?;
+        self.commands.push(cmd);
+
+        Ok(self)
+    }
+
+    pub fn commands(&self) -> &[OdCommand] {
+        &self.commands
+    }
+
+    /// Write all edits and [OdCommand::Commit]
+    pub fn commit_to_sysfs<P: Into<PathBuf>>(&self, sysfs_path: P) -> Result<(), Error> {
+        let sysfs_path = sysfs_path.into();
+
+        for cmd in self.commands.iter().chain(&[OdCommand::Commit]) {
+            cmd.write_to_sysfs(&sysfs_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_od_clk_voltage_parse() {
+    /* Polaris (SMU7) */
+    const POLARIS: &str = "\
+OD_SCLK:
+0:        300MHz        750mV
+1:        600MHz        769mV
+7:       1340MHz       1150mV
+OD_MCLK:
+0:        300MHz        750mV
+1:       1750MHz        800mV
+OD_RANGE:
+SCLK:     300MHz       2000MHz
+MCLK:     300MHz       2250MHz
+VDDC:     750mV        1200mV
+";
+    let od = OdClkVoltage::parse(POLARIS).unwrap();
+
+    assert_eq!(od.sclk.len(), 3);
+    assert_eq!(od.sclk[2], OdLevel { index: 7, clock: 1340, voltage: Some(1150) });
+    assert_eq!(od.mclk[1].clock, 1750);
+    assert_eq!(od.range.vddc, Some([750, 1200]));
+    assert_eq!(od.vddgfx_offset, None);
+
+    /* Navi10 (SMU11) */
+    const NAVI10: &str = "\
+OD_SCLK:
+0: 800Mhz
+1: 2100Mhz
+OD_MCLK:
+1: 875MHz
+OD_VDDC_CURVE:
+0: 800MHz 711mV
+1: 1450MHz 756mV
+2: 2100MHz 1149mV
+OD_RANGE:
+SCLK:     800Mhz       2150Mhz
+MCLK:     625Mhz        950Mhz
+VDDC_CURVE_SCLK[0]:     800Mhz       2150Mhz
+VDDC_CURVE_VOLT[0]:     750mV        1200mV
+VDDC_CURVE_SCLK[1]:     800Mhz       2150Mhz
+VDDC_CURVE_VOLT[1]:     750mV        1200mV
+VDDC_CURVE_SCLK[2]:     800Mhz       2150Mhz
+VDDC_CURVE_VOLT[2]:     750mV        1200mV
+";
+    let od = OdClkVoltage::parse(NAVI10).unwrap();
+
+    assert_eq!(od.sclk[1], OdLevel { index: 1, clock: 2100, voltage: None });
+    assert_eq!(od.mclk[0].index, 1);
+    assert_eq!(od.vddc_curve[2], OdCurvePoint { index: 2, clock: 2100, voltage: 1149 });
+    assert_eq!(od.range.vddc_curve_volt.len(), 3);
+
+    /* Navi31 (SMU13) */
+    const NAVI31: &str = "\
+OD_SCLK:
+0: 500Mhz
+1: 2500Mhz
+OD_MCLK:
+0: 97Mhz
+1: 1250MHz
+OD_VDDGFX_OFFSET:
+0mV
+OD_RANGE:
+SCLK:     500Mhz       3000Mhz
+MCLK:      97Mhz       1500Mhz
+VDDGFX_OFFSET:    -450mv          0mv
+";
+    let od = OdClkVoltage::parse(NAVI31).unwrap();
+
+    assert_eq!(od.sclk[1], OdLevel { index: 1, clock: 2500, voltage: None });
+    assert_eq!(od.mclk[1].clock, 1250);
+    assert_eq!(od.sclk_offset, None);
+    assert_eq!(od.vddgfx_offset, Some(0));
+    assert_eq!(od.range.sclk, Some([500, 3000]));
+    assert_eq!(od.range.vddgfx_offset, Some([-450, 0]));
+
+    /* Navi48 (SMU14) */
+    const NAVI48: &str = "\
+OD_SCLK_OFFSET:
+-50Mhz
+OD_MCLK:
+0: 97Mhz
+1: 1258MHz
+OD_VDDGFX_OFFSET:
+0mV
+OD_RANGE:
+SCLK_OFFSET:    -500Mhz       1000Mhz
+MCLK:      97Mhz       1500Mhz
+VDDGFX_OFFSET:    -200mv          0mv
+";
+    let od = OdClkVoltage::parse(NAVI48).unwrap();
+
+    assert_eq!(od.sclk_offset, Some(-50));
+    assert_eq!(od.range.sclk_offset, Some([-500, 1000]));
+    assert_eq!(od.range.vddgfx_offset, Some([-200, 0]));
+    assert!(od.sclk.is_empty());
+}
+
+#[test]
+fn test_od_editor() {
+    let od = OdClkVoltage {
+        sclk: vec![
+            OdLevel { index: 0, clock: 300, voltage: Some(750) },
+            OdLevel { index: 1, clock: 1340, voltage: Some(1150) },
+        ],
+        vddgfx_offset: Some(0),
+        range: OdRange {
+            sclk: Some([300, 2000]),
+            vddc: Some([750, 1200]),
+            vddgfx_offset: Some([-450, 0]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut editor = OdEditor::new(od);
+
+    editor
+        .push(OdCommand::Sclk { index: 1, clock: 1400, voltage: Some(1100) }).unwrap()
+        .push(OdCommand::VddgfxOffset(-100)).unwrap();
+
+    assert!(matches!(
+        editor.push(OdCommand::Sclk { index: 1, clock: 2100, voltage: Some(1100) }),
+        Err(Error::OutOfRange { value: 2100, min: 300, max: 2000 }),
+    ));
+    assert!(matches!(
+        editor.push(OdCommand::Sclk { index: 2, clock: 1000, voltage: Some(1000) }),
+        Err(Error::OutOfRange { value: 2, min: 0, max: 1 }),
+    ));
+    assert!(editor.push(OdCommand::Sclk { index: 0, clock: 300, voltage: None }).is_err());
+    assert!(editor.push(OdCommand::SclkOffset(100)).is_err());
+
+    let cmds: Vec<String> = editor.commands().iter().map(|cmd| cmd.to_string()).collect();
+
+    assert_eq!(cmds, ["s 1 1400 1100", "vo -100"]);
+    assert_eq!(editor.table.sclk[1].clock, 1400);
+    assert_eq!(OdCommand::VddcCurve { index: 2, clock: 2100, voltage: 1149 }.to_string(), "vc 2 2100 1149");
+}
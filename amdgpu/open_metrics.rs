@@ -0,0 +1,581 @@
+/*
+    ref: https://github.com/prometheus/OpenMetrics/blob/main/specification/OpenMetrics.md
+*/
+
+use crate::AMDGPU::{
+    DeviceHandle,
+    GPU_INFO,
+    GpuMetrics,
+    MetricsInfo,
+    PowerCap,
+    RasBlock,
+    RasErrorCount,
+    ThrottlerType,
+};
+use crate::{Error, PCI};
+use std::fmt::{self, Write};
+
+/// State of one device rendered by [OpenMetrics]
+#[derive(Debug, Clone)]
+pub struct DeviceMetrics {
+    pub pci: PCI::BUS_INFO,
+    pub marketing_name: String,
+    pub gpu_metrics: Option<GpuMetrics>,
+    /// Bytes
+    pub vram_usage: Option<u64>,
+    /// Bytes
+    pub vram_total: Option<u64>,
+    /// Bytes
+    pub gtt_usage: Option<u64>,
+    /// Bytes
+    pub gtt_total: Option<u64>,
+    pub power_cap: Option<PowerCap>,
+    pub ras: Vec<(RasBlock, RasErrorCount)>,
+    pub pcie_current: Option<PCI::LINK>,
+    pub pcie_max: Option<PCI::LINK>,
+}
+
+impl DeviceMetrics {
+    /// Without any value
+    pub fn new(pci: PCI::BUS_INFO, marketing_name: String) -> Self {
+        Self {
+            pci,
+            marketing_name,
+            gpu_metrics: None,
+            vram_usage: None,
+            vram_total: None,
+            gtt_usage: None,
+            gtt_total: None,
+            power_cap: None,
+            ras: Vec::new(),
+            pcie_current: None,
+            pcie_max: None,
+        }
+    }
+
+    /// Values not supported by the device (or the kernel driver) are left as `None`
+    pub fn from_device(dev: &DeviceHandle) -> Result<Self, Error> {
+        let pci = dev.get_pci_bus_info()?;
+        let marketing_name = dev.device_info()?.find_device_name_or_default();
+        let sysfs_path = pci.get_sysfs_path();
+        let memory_info = dev.memory_info().ok();
+
+        let ras = RasBlock::ALL
+            .into_iter()
+            .filter_map(|block| {
+                let count = RasErrorCount::get_from_sysfs_with_ras_block(&sysfs_path, block).ok()?;
+
+                Some((block, count))
+            })
+            .collect();
+
+        Ok(Self {
+            gpu_metrics: dev.get_gpu_metrics().ok(),
+            vram_usage: dev.vram_usage_info().ok(),
+            vram_total: memory_info.map(|m| m.vram.total_heap_size),
+            gtt_usage: dev.gtt_usage_info().ok(),
+            gtt_total: memory_info.map(|m| m.gtt.total_heap_size),
+            power_cap: dev.get_power_cap().ok(),
+            ras,
+            pcie_current: pci.get_current_link_info().ok(),
+            pcie_max: pci.get_max_link_info().ok(),
+            ..Self::new(pci, marketing_name)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricType {
+    Gauge,
+    Counter,
+}
+
+struct Desc {
+    name: &'static str,
+    type_: MetricType,
+    unit: Option<&'static str>,
+    help: &'static str,
+}
+
+impl Desc {
+    const fn gauge(name: &'static str, unit: Option<&'static str>, help: &'static str) -> Self {
+        Self { name, type_: MetricType::Gauge, unit, help }
+    }
+
+    const TEMPERATURE: Self =
+        Self::gauge("amdgpu_temperature_celsius", Some("celsius"), "Temperature");
+    const POWER: Self = Self::gauge("amdgpu_power_watts", Some("watts"), "Average power");
+    const CLOCK: Self = Self::gauge("amdgpu_clock_megahertz", Some("megahertz"), "Current clock");
+    const ACTIVITY: Self =
+        Self::gauge("amdgpu_activity_percent", Some("percent"), "Average activity");
+    const FAN_SPEED: Self = Self::gauge("amdgpu_fan_speed_rpm", Some("rpm"), "Fan speed");
+    const MEMORY_USED: Self =
+        Self::gauge("amdgpu_memory_used_bytes", Some("bytes"), "Memory usage");
+    const MEMORY_TOTAL: Self =
+        Self::gauge("amdgpu_memory_total_bytes", Some("bytes"), "Memory size");
+    const POWER_CAP: Self = Self::gauge("amdgpu_power_cap_watts", Some("watts"), "Power cap");
+    const THROTTLED: Self =
+        Self::gauge("amdgpu_throttled", None, "Throttled by the type of throttlers");
+    const THROTTLER: Self = Self::gauge("amdgpu_throttler_active", None, "Active throttler");
+    const RAS_ERRORS: Self = Self {
+        name: "amdgpu_ras_errors",
+        type_: MetricType::Counter,
+        unit: None,
+        help: "RAS error count",
+    };
+    const PCIE_GEN: Self =
+        Self::gauge("amdgpu_pcie_link_generation", None, "PCIe link generation");
+    const PCIE_WIDTH: Self = Self::gauge("amdgpu_pcie_link_width", None, "PCIe link width");
+}
+
+struct Family {
+    desc: &'static Desc,
+    samples: String,
+}
+
+/// Renders [DeviceMetrics] in the OpenMetrics text format,
+/// every sample has the `pci` and `name` labels.
+#[derive(Default)]
+pub struct OpenMetrics {
+    families: Vec<Family>,
+}
+
+impl OpenMetrics {
+    pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render_devices(devices: &[DeviceMetrics]) -> String {
+        let mut m = Self::new();
+
+        for dev in devices {
+            m.push_device(dev);
+        }
+
+        m.render()
+    }
+
+    pub fn push_device(&mut self, dev: &DeviceMetrics) {
+        let base = format!(
+            "pci=\"{}\",name=\"{}\"",
+            dev.pci,
+            escape_label(&dev.marketing_name),
+        );
+
+        if let Some(m) = &dev.gpu_metrics {
+            self.push_gpu_metrics(&base, m);
+        }
+
+        for (pool, used, total) in [
+            ("vram", dev.vram_usage, dev.vram_total),
+            ("gtt", dev.gtt_usage, dev.gtt_total),
+        ] {
+            let labels = [("pool", pool)];
+
+            if let Some(used) = used {
+                self.add(&Desc::MEMORY_USED, &base, &labels, used);
+            }
+
+            if let Some(total) = total {
+                self.add(&Desc::MEMORY_TOTAL, &base, &labels, total);
+            }
+        }
+
+        if let Some(cap) = &dev.power_cap {
+            let type_ = cap.type_.to_string();
+
+            for (limit, val) in [
+                ("current", cap.current),
+                ("default", cap.default),
+                ("min", cap.min),
+                ("max", cap.max),
+            ] {
+                self.add(&Desc::POWER_CAP, &base, &[("type", &type_), ("limit", limit)], val);
+            }
+        }
+
+        for (block, count) in &dev.ras {
+            let block = block.to_sysfs_name_prefix();
+
+            for (type_, val) in [
+                ("uncorrected", count.uncorrected),
+                ("corrected", count.corrected),
+            ] {
+                self.add(&Desc::RAS_ERRORS, &base, &[("block", block), ("type", type_)], val);
+            }
+        }
+
+        for (status, link) in [("current", dev.pcie_current), ("max", dev.pcie_max)] {
+            let Some(link) = link else { continue };
+            let labels = [("status", status)];
+
+            self.add(&Desc::PCIE_GEN, &base, &labels, link.r#gen);
+            self.add(&Desc::PCIE_WIDTH, &base, &labels, link.width);
+        }
+    }
+
+    fn push_gpu_metrics(&mut self, base: &str, m: &GpuMetrics) {
+        /* 0xFFFF: not supported */
+        let valid = |v: Option<u16>| v.filter(|v| *v != u16::MAX);
+
+        for (sensor, val) in [
+            ("edge", valid(m.get_temperature_edge())),
+            ("hotspot", valid(m.get_temperature_hotspot())),
+            ("mem", valid(m.get_temperature_mem())),
+            ("vrgfx", valid(m.get_temperature_vrgfx())),
+            ("vrsoc", valid(m.get_temperature_vrsoc())),
+            ("vrmem", valid(m.get_temperature_vrmem())),
+        ] {
+            let Some(val) = val else { continue };
+
+            self.add(&Desc::TEMPERATURE, base, &[("sensor", sensor)], val);
+        }
+
+        /* APU, centi-Celsius (ref: drivers/gpu/drm/amd/include/kgd_pp_interface.h) */
+        for (sensor, val) in [
+            ("gfx", valid(m.get_temperature_gfx())),
+            ("soc", valid(m.get_temperature_soc())),
+        ] {
+            let Some(val) = val else { continue };
+
+            self.add(&Desc::TEMPERATURE, base, &[("sensor", sensor)], val as f32 / 100.0);
+        }
+
+        /* v2_x/v3_0 (APU): mW (ref: drivers/gpu/drm/amd/include/kgd_pp_interface.h) */
+        let power_div = match m.get_header().map(|h| h.format_revision) {
+            Some(2 | 3) => 1000.0,
+            _ => 1.0,
+        };
+        let socket_power = m.get_average_socket_power()
+            .filter(|v| *v != u16::MAX as u32 && *v != u32::MAX);
+
+        if let Some(val) = socket_power {
+            self.add(&Desc::POWER, base, &[("sensor", "socket")], val as f32 / power_div);
+        }
+
+        for (sensor, val) in [
+            ("gfx", valid(m.get_average_gfx_power())),
+            ("soc", valid(m.get_average_soc_power())),
+            ("cpu", valid(m.get_average_cpu_power())),
+        ] {
+            let Some(val) = val else { continue };
+
+            self.add(&Desc::POWER, base, &[("sensor", sensor)], val as f32 / power_div);
+        }
+
+        for (clock, cur, avg) in [
+            ("gfx", m.get_current_gfxclk(), m.get_average_gfxclk_frequency()),
+            ("soc", m.get_current_socclk(), m.get_average_socclk_frequency()),
+            ("mem", m.get_current_uclk(), m.get_average_uclk_frequency()),
+            ("fclk", m.get_current_fclk(), m.get_average_fclk_frequency()),
+            ("vclk", m.get_current_vclk(), m.get_average_vclk_frequency()),
+            ("dclk", m.get_current_dclk(), m.get_average_dclk_frequency()),
+        ] {
+            let Some(val) = valid(cur).or(valid(avg)) else { continue };
+
+            self.add(&Desc::CLOCK, base, &[("clock", clock)], val);
+        }
+
+        for (engine, val) in [
+            ("gfx", valid(m.get_average_gfx_activity())),
+            ("umc", valid(m.get_average_umc_activity())),
+            ("mm", valid(m.get_average_mm_activity())),
+        ] {
+            let Some(val) = val else { continue };
+
+            self.add(&Desc::ACTIVITY, base, &[("engine", engine)], val);
+        }
+
+        if let Some(val) = valid(m.get_current_fan_speed()) {
+            self.add(&Desc::FAN_SPEED, base, &[], val);
+        }
+
+        if let Some(thr) = m.get_throttle_status_info() {
+            let bits = thr.get_all_throttler();
+            let types = thr.get_all_throttler_type();
+
+            for type_ in [
+                ThrottlerType::Power,
+                ThrottlerType::Current,
+                ThrottlerType::Temperature,
+                ThrottlerType::Other,
+            ] {
+                let type_name = type_.to_string();
+                let throttled = types.contains(&type_) as u8;
+
+                self.add(&Desc::THROTTLED, base, &[("type", &type_name)], throttled);
+            }
+
+            for bit in bits {
+                self.add(&Desc::THROTTLER, base, &[("throttler", &bit.to_string())], 1);
+            }
+        }
+    }
+
+    fn add<T: fmt::Display>(
+        &mut self,
+        desc: &'static Desc,
+        base: &str,
+        labels: &[(&str, &str)],
+        value: T,
+    ) {
+        let pos = match self.families.iter().position(|f| f.desc.name == desc.name) {
+            Some(pos) => pos,
+            None => {
+                self.families.push(Family { desc, samples: String::new() });
+                self.families.len() - 1
+            },
+        };
+        let s = &mut self.families[pos].samples;
+        let suffix = if desc.type_ == MetricType::Counter { "_total" } else { "" };
+
+        let _ = write!(s, "{}{suffix}{{{base}", desc.name);
+
+        for (key, val) in labels {
+            let _ = write!(s, ",{key}=\"{}\"", escape_label(val));
+        }
+
+        let _ = writeln!(s, "}} {value}");
+    }
+
+    /// Families are in the order first pushed, ends with `# EOF`
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+
+        for f in &self.families {
+            let type_ = match f.desc.type_ {
+                MetricType::Gauge => "gauge",
+                MetricType::Counter => "counter",
+            };
+
+            let _ = writeln!(s, "# TYPE {} {type_}", f.desc.name);
+
+            if let Some(unit) = f.desc.unit {
+                let _ = writeln!(s, "# UNIT {} {unit}", f.desc.name);
+            }
+
+            let _ = writeln!(s, "# HELP {} {}", f.desc.name, f.desc.help);
+            s.push_str(&f.samples);
+        }
+
+        s.push_str("# EOF\n");
+
+        s
+    }
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(feature = "exporter_http")]
+mod http {
+    use super::OpenMetrics;
+    use std::io::{self, BufRead, BufReader, Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+    use std::time::{Duration, Instant};
+
+    /// Minimal HTTP/1.1 server for `GET /metrics`, handles one connection at a time
+    #[derive(Debug)]
+    pub struct OpenMetricsServer {
+        listener: TcpListener,
+    }
+
+    impl OpenMetricsServer {
+        const TIMEOUT: Duration = Duration::from_secs(5);
+        const MAX_HEADER_LEN: usize = 8192;
+
+        pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+            Ok(Self { listener: TcpListener::bind(addr)? })
+        }
+
+        pub fn local_addr(&self) -> io::Result<SocketAddr> {
+            self.listener.local_addr()
+        }
+
+        /// `render` is called for each scrape, e.g. `|| OpenMetrics::render_devices(&devices)`
+        pub fn serve<F: FnMut() -> String>(&self, mut render: F) -> io::Result<()> {
+            loop {
+                self.handle_one(&mut render)?;
+            }
+        }
+
+        /// Accepts and responds to one connection.
+        /// Errors from the client connection are ignored.
+        pub fn handle_one<F: FnMut() -> String>(&self, render: &mut F) -> io::Result<()> {
+            let (stream, _) = self.listener.accept()?;
+            let _ = Self::respond(stream, render);
+
+            Ok(())
+        }
+
+        /// The request line and headers share [Self::MAX_HEADER_LEN] bytes
+        /// and must be received within [Self::TIMEOUT].
+        fn respond<F: FnMut() -> String>(mut stream: TcpStream, render: &mut F) -> io::Result<()> {
+            let deadline = Instant::now() + Self::TIMEOUT;
+            stream.set_write_timeout(Some(Self::TIMEOUT))?;
+
+            let mut reader = BufReader::new(&stream).take(Self::MAX_HEADER_LEN as u64);
+            let mut read_line = |buf: &mut String| -> io::Result<usize> {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                if remaining.is_zero() {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+
+                stream.set_read_timeout(Some(remaining))?;
+                reader.read_line(buf)
+            };
+            let mut request_line = String::new();
+            read_line(&mut request_line)?;
+
+            /* skip headers */
+            loop {
+                let mut line = String::new();
+                let n = read_line(&mut line)?;
+
+                if n == 0 || line == "\r\n" || line == "\n" {
+                    break;
+                }
+            }
+
+            let mut parts = request_line.split_whitespace();
+            let (method, target) = (parts.next(), parts.next());
+
+            let (status, content_type, body) = match (method, target) {
+                (Some("GET"), Some("/metrics")) => ("200 OK", OpenMetrics::CONTENT_TYPE, render()),
+                (Some("GET"), _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+                _ => ("405 Method Not Allowed", "text/plain", "Method Not Allowed\n".to_string()),
+            };
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\n\
+                Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len(),
+            )?;
+
+            stream.flush()
+        }
+    }
+
+    #[test]
+    fn test_open_metrics_server() {
+        let server = OpenMetricsServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let mut res = String::new();
+
+            stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            stream.read_to_string(&mut res).unwrap();
+
+            res
+        });
+
+        server.handle_one(&mut || "# EOF\n".to_string()).unwrap();
+
+        let res = client.join().unwrap();
+
+        assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(res.contains("Content-Length: 6\r\n"));
+        assert!(res.ends_with("\r\n\r\n# EOF\n"));
+    }
+}
+#[cfg(feature = "exporter_http")]
+pub use http::*;
+
+#[test]
+fn test_open_metrics_render() {
+    use crate::AMDGPU::{gpu_metrics_v1_3, gpu_metrics_v2_1, metrics_table_header, PowerCapType};
+
+    let mut m: gpu_metrics_v1_3 = unsafe { core::mem::zeroed() };
+
+    m.common_header = metrics_table_header {
+        structure_size: size_of::<gpu_metrics_v1_3>() as u16,
+        format_revision: 1,
+        content_revision: 3,
+    };
+    m.temperature_edge = 55;
+    m.temperature_mem = 0xFFFF;
+    m.current_fan_speed = 0xFFFF;
+    m.average_socket_power = 180;
+    m.current_gfxclk = 2400;
+    /* PPT0, TEMP_EDGE */
+    m.indep_throttle_status = (1 << 0) | (1 << 35);
+
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let mut dev = DeviceMetrics::new(pci, "AMD Radeon \"Test\"".to_string());
+
+    dev.gpu_metrics = Some(GpuMetrics::V1_3(m));
+    dev.vram_usage = Some(1 << 30);
+    dev.power_cap = Some(PowerCap {
+        type_: PowerCapType::PPT,
+        current: 200,
+        default: 220,
+        min: 0,
+        max: 250,
+    });
+    dev.ras = vec![(RasBlock::UMC, RasErrorCount { uncorrected: 0, corrected: 3 })];
+    dev.pcie_current = Some(PCI::LINK { r#gen: 4, width: 16 });
+
+    let dev1 = DeviceMetrics { pci: "0000:0c:00.0".parse().unwrap(), ..dev.clone() };
+    let s = OpenMetrics::render_devices(&[dev, dev1]);
+    let labels = r#"pci="0000:0b:00.0",name="AMD Radeon \"Test\"""#;
+
+    assert!(s.contains(
+        "# TYPE amdgpu_temperature_celsius gauge\n# UNIT amdgpu_temperature_celsius celsius\n"
+    ));
+    assert!(s.contains(&format!("amdgpu_temperature_celsius{{{labels},sensor=\"edge\"}} 55\n")));
+    assert!(!s.contains("sensor=\"mem\""));
+    assert!(s.contains(&format!("amdgpu_power_watts{{{labels},sensor=\"socket\"}} 180\n")));
+    assert!(s.contains(&format!("amdgpu_clock_megahertz{{{labels},clock=\"gfx\"}} 2400\n")));
+    assert!(s.contains(&format!(
+        "amdgpu_memory_used_bytes{{{labels},pool=\"vram\"}} 1073741824\n"
+    )));
+    assert!(s.contains(&format!(
+        "amdgpu_power_cap_watts{{{labels},type=\"PPT\",limit=\"max\"}} 250\n"
+    )));
+    assert!(s.contains(&format!("amdgpu_throttled{{{labels},type=\"Temperature\"}} 1\n")));
+    assert!(s.contains(&format!("amdgpu_throttled{{{labels},type=\"Current\"}} 0\n")));
+    assert!(s.contains(&format!(
+        "amdgpu_throttler_active{{{labels},throttler=\"TEMP_EDGE\"}} 1\n"
+    )));
+    assert!(s.contains("# TYPE amdgpu_ras_errors counter\n"));
+    assert!(s.contains(&format!(
+        "amdgpu_ras_errors_total{{{labels},block=\"umc\",type=\"corrected\"}} 3\n"
+    )));
+    assert!(s.contains(&format!("amdgpu_pcie_link_width{{{labels},status=\"current\"}} 16\n")));
+    assert!(s.ends_with("# EOF\n"));
+
+    /* samples of a family are grouped across devices */
+    assert_eq!(s.matches("# TYPE amdgpu_temperature_celsius").count(), 1);
+    assert_eq!(s.matches("amdgpu_fan_speed_rpm").count(), 0);
+
+    /* APU: centi-Celsius and mW */
+    let mut m: gpu_metrics_v2_1 = unsafe { core::mem::zeroed() };
+
+    m.common_header = metrics_table_header {
+        structure_size: size_of::<gpu_metrics_v2_1>() as u16,
+        format_revision: 2,
+        content_revision: 1,
+    };
+    m.temperature_gfx = 4550;
+    m.temperature_soc = 0xFFFF;
+    m.average_socket_power = 15500;
+    m.average_gfx_power = 8000;
+    m.average_soc_power = 0xFFFF;
+
+    let mut dev = DeviceMetrics::new("0000:04:00.0".parse().unwrap(), "APU".to_string());
+    dev.gpu_metrics = Some(GpuMetrics::V2_1(m));
+
+    let s = OpenMetrics::render_devices(&[dev]);
+    let labels = r#"pci="0000:04:00.0",name="APU""#;
+
+    assert!(s.contains(&format!("amdgpu_temperature_celsius{{{labels},sensor=\"gfx\"}} 45.5\n")));
+    assert!(!s.contains("sensor=\"soc\""));
+    assert!(s.contains(&format!("amdgpu_power_watts{{{labels},sensor=\"socket\"}} 15.5\n")));
+    assert!(s.contains(&format!("amdgpu_power_watts{{{labels},sensor=\"gfx\"}} 8\n")));
+}
@@ -0,0 +1,645 @@
+/*
+    ref:
+        https://gitlab.freedesktop.org/mesa/mesa/-/blob/main/src/amd/common/sid.h
+        https://gitlab.freedesktop.org/mesa/drm/-/blob/main/tests/amdgpu/basic_tests.c
+        https://gitlab.freedesktop.org/agd5f/linux/-/blob/amd-staging-drm-next/drivers/gpu/drm/amd/amdgpu/soc15d.h
+*/
+
+use crate::AMDGPU::CHIP_CLASS;
+use crate::AMDGPU::HW_IP::HW_IP_TYPE;
+use crate::Error;
+
+pub const PKT3_NOP: u32 = 0x10;
+pub const PKT3_DISPATCH_DIRECT: u32 = 0x15;
+pub const PKT3_INDIRECT_BUFFER_SI: u32 = 0x32;
+pub const PKT3_WRITE_DATA: u32 = 0x37;
+pub const PKT3_WAIT_REG_MEM: u32 = 0x3C;
+pub const PKT3_INDIRECT_BUFFER_CIK: u32 = 0x3F;
+pub const PKT3_COPY_DATA: u32 = 0x40;
+pub const PKT3_CP_DMA: u32 = 0x41;
+pub const PKT3_EVENT_WRITE_EOP: u32 = 0x47;
+pub const PKT3_RELEASE_MEM: u32 = 0x49;
+pub const PKT3_DMA_DATA: u32 = 0x50;
+pub const PKT3_SET_SH_REG: u32 = 0x76;
+
+/// Type-3 NOP packet with the maximum count, for a 1 dword padding
+pub const PKT3_NOP_PAD: u32 = 0xFFFF_1000;
+/// Max dwords of a NOP packet, the count `0x3FFF` is reserved for [PKT3_NOP_PAD]
+pub const PKT3_NOP_MAX_DW: u32 = 0x3FFE + 2;
+
+pub const SI_SH_REG_OFFSET: u32 = 0x0000_B000;
+pub const SI_SH_REG_END: u32 = 0x0000_C000;
+
+/// Type-3 packet header, `count` is the number of body dwords minus 1
+pub const fn pkt3(op: u32, count: u32, predicate: bool) -> u32 {
+    (3 << 30) | ((count & 0x3FFF) << 16) | ((op & 0xFF) << 8) | (predicate as u32)
+}
+
+/// Shader type bit of the packet header, required for compute packets
+const fn pkt3_shader_type_compute(header: u32) -> u32 {
+    header | (1 << 1)
+}
+
+/// Engine which executes the packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EngineSel {
+    ME = 0,
+    PFP = 1,
+    CE = 2,
+}
+
+/// Destination of WRITE_DATA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WriteDataDst {
+    MemMappedRegister = 0,
+    MemGrbm = 1,
+    TcL2 = 2,
+    Gds = 3,
+    Memory = 5,
+}
+
+/// Source of COPY_DATA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyDataSrc {
+    Register,
+    Memory,
+    TcL2,
+    Gds,
+    Perf,
+    Immediate,
+    /// GPU clock counter
+    Timestamp,
+}
+
+impl CopyDataSrc {
+    fn src_sel(&self) -> u32 {
+        match self {
+            Self::Register => 0,
+            Self::Memory => 1,
+            Self::TcL2 => 2,
+            Self::Gds => 3,
+            Self::Perf => 4,
+            Self::Immediate => 5,
+            Self::Timestamp => 9,
+        }
+    }
+}
+
+/// Destination of COPY_DATA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyDataDst {
+    Register,
+    Memory,
+    TcL2,
+    Gds,
+    Perf,
+}
+
+impl CopyDataDst {
+    fn dst_sel(&self, chip_class: CHIP_CLASS) -> u32 {
+        match self {
+            Self::Register => 0,
+            /* GFX6 does not support DST_SEL = 5 (MEM), use MEM_GRBM */
+            Self::Memory if chip_class <= CHIP_CLASS::GFX6 => 1,
+            Self::Memory => 5,
+            Self::TcL2 => 2,
+            Self::Gds => 3,
+            Self::Perf => 4,
+        }
+    }
+}
+
+/// Source of DMA_DATA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaDataSrc {
+    /// Copy from the address
+    Address(u64),
+    /// Fill with the data
+    Data(u32),
+}
+
+/// End-of-pipe (`EVENT_INDEX` 5) event of RELEASE_MEM/EVENT_WRITE_EOP.
+/// The end-of-shader events (`CS_DONE`, `PS_DONE`) use a different packet and are not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EopEvent {
+    CACHE_FLUSH_AND_INV_TS = 0x14,
+    BOTTOM_OF_PIPE_TS = 0x28,
+}
+
+/// Data written by RELEASE_MEM/EVENT_WRITE_EOP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EopData {
+    None,
+    Value32(u32),
+    Value64(u64),
+    /// 64-bit GPU clock counter
+    Timestamp,
+}
+
+impl EopData {
+    fn data_sel(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Value32(_) => 1,
+            Self::Value64(_) => 2,
+            Self::Timestamp => 3,
+        }
+    }
+
+    fn value(&self) -> u64 {
+        match self {
+            Self::Value32(v) => *v as u64,
+            Self::Value64(v) => *v,
+            Self::None | Self::Timestamp => 0,
+        }
+    }
+}
+
+/// Compare function of WAIT_REG_MEM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WaitRegMemFunc {
+    Always = 0,
+    Less = 1,
+    LessEqual = 2,
+    Equal = 3,
+    NotEqual = 4,
+    GreaterEqual = 5,
+    Greater = 6,
+}
+
+/// Address space of WAIT_REG_MEM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitRegMemSpace {
+    /// Register (dword offset)
+    Register(u32),
+    /// Memory address, must be 4-byte aligned
+    Memory(u64),
+}
+
+pub const DISPATCH_INITIATOR_COMPUTE_SHADER_EN: u32 = 1 << 0;
+pub const DISPATCH_INITIATOR_FORCE_START_AT_000: u32 = 1 << 2;
+/// GFX7+
+pub const DISPATCH_INITIATOR_ORDER_MODE: u32 = 1 << 3;
+/// GFX10+
+pub const DISPATCH_INITIATOR_CS_W32_EN: u32 = 1 << 15;
+
+/// PM4 packet builder for the GFX/compute rings.
+/// The encoding of the packets is selected by `chip_class`.
+///
+/// ```
+/// use libdrm_amdgpu_sys::AMDGPU::{CHIP_CLASS, HW_IP::HW_IP_TYPE, PM4::*};
+///
+/// let mut pm4 = Pm4Builder::new(CHIP_CLASS::GFX10_3, HW_IP_TYPE::COMPUTE);
+///
+/// pm4.write_data(WriteDataDst::Memory, EngineSel::ME, 0x1000, &[0xDEADBEEF])
+///     .unwrap()
+///     .pad(8)
+///     .unwrap();
+///
+/// assert_eq!(pm4.len_dw(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pm4Builder {
+    chip_class: CHIP_CLASS,
+    compute: bool,
+    buf: Vec<u32>,
+}
+
+impl Pm4Builder {
+    pub fn new(chip_class: CHIP_CLASS, ip_type: HW_IP_TYPE) -> Self {
+        Self {
+            chip_class,
+            compute: matches!(ip_type, HW_IP_TYPE::COMPUTE),
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn chip_class(&self) -> CHIP_CLASS {
+        self.chip_class
+    }
+
+    pub fn dwords(&self) -> &[u32] {
+        &self.buf
+    }
+
+    pub fn into_dwords(self) -> Vec<u32> {
+        self.buf
+    }
+
+    pub fn len_dw(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Append raw dwords
+    pub fn raw(&mut self, dw: &[u32]) -> &mut Self {
+        self.buf.extend_from_slice(dw);
+        self
+    }
+
+    fn header(&self, op: u32, count: u32) -> u32 {
+        let header = pkt3(op, count, false);
+
+        if self.compute { pkt3_shader_type_compute(header) } else { header }
+    }
+
+    /// NOP packet of `num_dw` dwords (including the header), up to [PKT3_NOP_MAX_DW]
+    pub fn nop(&mut self, num_dw: u32) -> Result<&mut Self, Error> {
+        if num_dw > PKT3_NOP_MAX_DW {
+            return Err(Error::InvalidArgument("NOP packet is too large"));
+        }
+
+        match num_dw {
+            0 => {},
+            1 => self.buf.push(PKT3_NOP_PAD),
+            _ => {
+                self.buf.push(pkt3(PKT3_NOP, num_dw - 2, false));
+                self.buf.extend(core::iter::repeat_n(0, num_dw as usize - 1));
+            },
+        }
+
+        Ok(self)
+    }
+
+    /// Pad with NOP until the length is a multiple of `align_dw`
+    pub fn pad(&mut self, align_dw: u32) -> Result<&mut Self, Error> {
+        if align_dw == 0 {
+            return Err(Error::InvalidArgument("padding alignment is zero"));
+        }
+
+        let rem = self.buf.len() as u32 % align_dw;
+
+        if rem != 0 {
+            self.nop(align_dw - rem)?;
+        }
+
+        Ok(self)
+    }
+
+    /// `data` is up to `PKT3_NOP_MAX_DW - 4` dwords
+    pub fn write_data(
+        &mut self,
+        dst: WriteDataDst,
+        engine: EngineSel,
+        addr: u64,
+        data: &[u32],
+    ) -> Result<&mut Self, Error> {
+        const WR_CONFIRM: u32 = 1 << 20;
+
+        if data.is_empty() {
+            return Err(Error::InvalidArgument("WRITE_DATA without data"));
+        }
+
+        /* the count field of the PKT3 header is 14 bits */
+        if data.len() > (PKT3_NOP_MAX_DW - 4) as usize {
+            return Err(Error::InvalidArgument("WRITE_DATA packet is too large"));
+        }
+
+        self.buf.extend([
+            pkt3(PKT3_WRITE_DATA, 2 + data.len() as u32, false),
+            ((dst as u32) << 8) | WR_CONFIRM | ((engine as u32) << 30),
+            addr as u32,
+            (addr >> 32) as u32,
+        ]);
+        self.buf.extend_from_slice(data);
+
+        Ok(self)
+    }
+
+    /// `count_64bit` copies 2 dwords instead of 1 dword
+    pub fn copy_data(
+        &mut self,
+        src: CopyDataSrc,
+        src_addr: u64,
+        dst: CopyDataDst,
+        dst_addr: u64,
+        count_64bit: bool,
+    ) -> &mut Self {
+        const WR_CONFIRM: u32 = 1 << 20;
+
+        self.buf.extend([
+            pkt3(PKT3_COPY_DATA, 4, false),
+            src.src_sel()
+                | (dst.dst_sel(self.chip_class) << 8)
+                | ((count_64bit as u32) << 16)
+                | WR_CONFIRM,
+            src_addr as u32,
+            (src_addr >> 32) as u32,
+            dst_addr as u32,
+            (dst_addr >> 32) as u32,
+        ]);
+
+        self
+    }
+
+    /// Copy/fill `byte_count` bytes by the CP DMA.
+    /// GFX6 uses the CP_DMA packet, GFX7+ uses the DMA_DATA packet.
+    /// `byte_count` is limited by the chip class (2 MiB - 1 for GFX6-8, 64 MiB - 1 for GFX9+).
+    pub fn dma_data(
+        &mut self,
+        src: DmaDataSrc,
+        dst_addr: u64,
+        byte_count: u32,
+        cp_sync: bool,
+    ) -> Result<&mut Self, Error> {
+        const SRC_SEL_DATA: u32 = 2 << 29;
+        const CP_SYNC: u32 = 1 << 31;
+
+        let max_byte_count = if self.chip_class >= CHIP_CLASS::GFX9 { 0x3FF_FFFF } else { 0x1F_FFFF };
+
+        if byte_count > max_byte_count {
+            return Err(Error::OutOfRange {
+                value: byte_count as i64,
+                min: 0,
+                max: max_byte_count as i64,
+            });
+        }
+
+        let (src_lo, src_hi, src_sel) = match src {
+            DmaDataSrc::Address(addr) => (addr as u32, (addr >> 32) as u32, 0),
+            DmaDataSrc::Data(data) => (data, 0, SRC_SEL_DATA),
+        };
+        let cp_sync = if cp_sync { CP_SYNC } else { 0 };
+
+        if self.chip_class <= CHIP_CLASS::GFX6 {
+            self.buf.extend([
+                pkt3(PKT3_CP_DMA, 4, false),
+                src_lo,
+                (src_hi & 0xFFFF) | src_sel | cp_sync,
+                dst_addr as u32,
+                (dst_addr >> 32) as u32 & 0xFFFF,
+                byte_count,
+            ]);
+        } else {
+            self.buf.extend([
+                pkt3(PKT3_DMA_DATA, 5, false),
+                src_sel | cp_sync,
+                src_lo,
+                src_hi,
+                dst_addr as u32,
+                (dst_addr >> 32) as u32,
+                byte_count,
+            ]);
+        }
+
+        Ok(self)
+    }
+
+    /// Write `data` to `addr` at the end of pipe.
+    /// GFX6 and the GFX ring of GFX7/8 use the EVENT_WRITE_EOP packet, others use the RELEASE_MEM packet.
+    pub fn release_mem(
+        &mut self,
+        event: EopEvent,
+        addr: u64,
+        data: EopData,
+        interrupt: bool,
+    ) -> &mut Self {
+        const EVENT_INDEX_EOP: u32 = 5 << 8;
+
+        let event = (event as u32) | EVENT_INDEX_EOP;
+        let sel = (data.data_sel() << 29) | (if interrupt { 2 << 24 } else { 0 });
+        let value = data.value();
+        let use_release_mem = self.chip_class >= CHIP_CLASS::GFX9
+            || (self.chip_class >= CHIP_CLASS::GFX7 && self.compute);
+
+        if !use_release_mem {
+            self.buf.extend([
+                pkt3(PKT3_EVENT_WRITE_EOP, 4, false),
+                event,
+                addr as u32,
+                ((addr >> 32) as u32 & 0xFFFF) | sel,
+                value as u32,
+                (value >> 32) as u32,
+            ]);
+
+            return self;
+        }
+
+        let count = if self.chip_class >= CHIP_CLASS::GFX9 { 6 } else { 5 };
+
+        self.buf.extend([
+            pkt3(PKT3_RELEASE_MEM, count, false),
+            event,
+            sel,
+            addr as u32,
+            (addr >> 32) as u32,
+            value as u32,
+            (value >> 32) as u32,
+        ]);
+
+        /* INT_CTXID */
+        if self.chip_class >= CHIP_CLASS::GFX9 {
+            self.buf.push(0);
+        }
+
+        self
+    }
+
+    /// Wait until `(value & mask) func reference` is true, `poll_interval` is in units of 16 clocks
+    pub fn wait_reg_mem(
+        &mut self,
+        func: WaitRegMemFunc,
+        space: WaitRegMemSpace,
+        reference: u32,
+        mask: u32,
+        poll_interval: u32,
+        engine: EngineSel,
+    ) -> &mut Self {
+        let (mem_space, addr_lo, addr_hi) = match space {
+            WaitRegMemSpace::Register(reg) => (0, reg, 0),
+            WaitRegMemSpace::Memory(addr) => (1, addr as u32, (addr >> 32) as u32),
+        };
+
+        self.buf.extend([
+            pkt3(PKT3_WAIT_REG_MEM, 5, false),
+            (func as u32) | (mem_space << 4) | ((engine as u32) << 8),
+            addr_lo,
+            addr_hi,
+            reference,
+            mask,
+            poll_interval,
+        ]);
+
+        self
+    }
+
+    /// Set the consecutive SH registers starting at `reg` (byte offset, e.g. `0xB848` for COMPUTE_PGM_RSRC1).
+    /// All registers must be in the SH register range.
+    pub fn set_sh_reg(&mut self, reg: u32, values: &[u32]) -> Result<&mut Self, Error> {
+        if values.is_empty() {
+            return Err(Error::InvalidArgument("SET_SH_REG without values"));
+        }
+
+        /* the last register, byte offset */
+        let last = reg as i64 + (values.len() as i64 - 1) * 4;
+
+        for value in [reg as i64, last] {
+            if !(SI_SH_REG_OFFSET as i64..SI_SH_REG_END as i64).contains(&value) {
+                return Err(Error::OutOfRange {
+                    value,
+                    min: SI_SH_REG_OFFSET as i64,
+                    max: SI_SH_REG_END as i64 - 4,
+                });
+            }
+        }
+
+        self.buf.extend([
+            self.header(PKT3_SET_SH_REG, values.len() as u32),
+            (reg - SI_SH_REG_OFFSET) >> 2,
+        ]);
+        self.buf.extend_from_slice(values);
+
+        Ok(self)
+    }
+
+    /// `initiator` is `DISPATCH_INITIATOR_*`
+    pub fn dispatch_direct(&mut self, x: u32, y: u32, z: u32, initiator: u32) -> &mut Self {
+        self.buf.extend([
+            pkt3_shader_type_compute(pkt3(PKT3_DISPATCH_DIRECT, 3, false)),
+            x,
+            y,
+            z,
+            initiator,
+        ]);
+
+        self
+    }
+
+    /// Execute the IB at `va` (4-byte aligned) with `size_dw` dwords.
+    /// GFX6 uses INDIRECT_BUFFER (0x32), GFX7+ uses INDIRECT_BUFFER (0x3F) with the VALID bit.
+    pub fn indirect_buffer(&mut self, va: u64, size_dw: u32) -> &mut Self {
+        const VALID: u32 = 1 << 23;
+
+        let (op, control) = if self.chip_class <= CHIP_CLASS::GFX6 {
+            (PKT3_INDIRECT_BUFFER_SI, size_dw & 0xF_FFFF)
+        } else {
+            (PKT3_INDIRECT_BUFFER_CIK, (size_dw & 0xF_FFFF) | VALID)
+        };
+
+        self.buf.extend([
+            pkt3(op, 2, false),
+            va as u32 & !0x3,
+            (va >> 32) as u32 & 0xFFFF,
+            control,
+        ]);
+
+        self
+    }
+}
+
+#[test]
+fn test_pm4_nop_write_data() {
+    let mut pm4 = Pm4Builder::new(CHIP_CLASS::GFX9, HW_IP_TYPE::GFX);
+
+    pm4.nop(1).unwrap()
+        .write_data(WriteDataDst::Memory, EngineSel::ME, 0x1_2345_6780, &[0xDEADBEEF]).unwrap()
+        .nop(3).unwrap();
+
+    assert_eq!(pm4.dwords(), &[
+        0xFFFF_1000,
+        0xC003_3700, 0x0010_0500, 0x2345_6780, 0x0000_0001, 0xDEAD_BEEF,
+        0xC001_1000, 0, 0,
+    ]);
+
+    pm4.pad(16).unwrap();
+    assert_eq!(pm4.len_dw(), 16);
+
+    assert!(pm4.pad(0).is_err());
+    assert!(pm4.nop(PKT3_NOP_MAX_DW + 1).is_err());
+    pm4.nop(PKT3_NOP_MAX_DW).unwrap();
+    assert_eq!(pm4.dwords()[16], 0xFFFE_1000);
+
+    assert!(pm4.write_data(WriteDataDst::Memory, EngineSel::ME, 0x1000, &[]).is_err());
+    assert!(pm4.write_data(WriteDataDst::Memory, EngineSel::ME, 0x1000, &[0; 0x3FFF]).is_err());
+}
+
+#[test]
+fn test_pm4_copy_data() {
+    let gfx6 = Pm4Builder::new(CHIP_CLASS::GFX6, HW_IP_TYPE::GFX)
+        .copy_data(CopyDataSrc::Timestamp, 0, CopyDataDst::Memory, 0x1000, true)
+        .dwords().to_vec();
+    let gfx9 = Pm4Builder::new(CHIP_CLASS::GFX9, HW_IP_TYPE::GFX)
+        .copy_data(CopyDataSrc::Timestamp, 0, CopyDataDst::Memory, 0x1000, true)
+        .dwords().to_vec();
+
+    assert_eq!(gfx6, [0xC004_4000, 0x0011_0109, 0, 0, 0x1000, 0]);
+    assert_eq!(gfx9, [0xC004_4000, 0x0011_0509, 0, 0, 0x1000, 0]);
+}
+
+#[test]
+fn test_pm4_dma_data() {
+    let gfx6 = Pm4Builder::new(CHIP_CLASS::GFX6, HW_IP_TYPE::GFX)
+        .dma_data(DmaDataSrc::Data(0xDEADBEEF), 0x2_0000_1000, 4096, true).unwrap()
+        .dwords().to_vec();
+    let gfx8 = Pm4Builder::new(CHIP_CLASS::GFX8, HW_IP_TYPE::GFX)
+        .dma_data(DmaDataSrc::Address(0x1_0000_0000), 0x2_0000_1000, 4096, true).unwrap()
+        .dwords().to_vec();
+
+    assert_eq!(gfx6, [0xC004_4100, 0xDEAD_BEEF, 0xC000_0000, 0x1000, 0x2, 4096]);
+    assert_eq!(gfx8, [0xC005_5000, 0x8000_0000, 0, 1, 0x1000, 0x2, 4096]);
+    assert!(matches!(
+        Pm4Builder::new(CHIP_CLASS::GFX8, HW_IP_TYPE::GFX)
+            .dma_data(DmaDataSrc::Data(0), 0x1000, 0x20_0000, false),
+        Err(Error::OutOfRange { value: 0x20_0000, min: 0, max: 0x1F_FFFF }),
+    ));
+}
+
+#[test]
+fn test_pm4_release_mem() {
+    let addr = 0x1_0000_1000;
+    let data = EopData::Value64(0x1_0000_0002);
+    let gfx8_gfx = Pm4Builder::new(CHIP_CLASS::GFX8, HW_IP_TYPE::GFX)
+        .release_mem(EopEvent::BOTTOM_OF_PIPE_TS, addr, data, true)
+        .dwords().to_vec();
+    let gfx8_comp = Pm4Builder::new(CHIP_CLASS::GFX8, HW_IP_TYPE::COMPUTE)
+        .release_mem(EopEvent::BOTTOM_OF_PIPE_TS, addr, data, true)
+        .dwords().to_vec();
+    let gfx10 = Pm4Builder::new(CHIP_CLASS::GFX10, HW_IP_TYPE::GFX)
+        .release_mem(EopEvent::BOTTOM_OF_PIPE_TS, addr, data, true)
+        .dwords().to_vec();
+
+    assert_eq!(gfx8_gfx, [0xC004_4700, 0x528, 0x1000, 0x4200_0001, 2, 1]);
+    assert_eq!(gfx8_comp, [0xC005_4900, 0x528, 0x4200_0000, 0x1000, 1, 2, 1]);
+    assert_eq!(gfx10, [0xC006_4900, 0x528, 0x4200_0000, 0x1000, 1, 2, 1, 0]);
+}
+
+#[test]
+fn test_pm4_dispatch() {
+    let mut pm4 = Pm4Builder::new(CHIP_CLASS::GFX10_3, HW_IP_TYPE::COMPUTE);
+
+    pm4.wait_reg_mem(
+        WaitRegMemFunc::Equal,
+        WaitRegMemSpace::Memory(0x1000),
+        1,
+        0xFFFF_FFFF,
+        4,
+        EngineSel::ME,
+    )
+        .set_sh_reg(0xB81C, &[8, 1, 1]).unwrap()
+        .dispatch_direct(4, 2, 1, DISPATCH_INITIATOR_COMPUTE_SHADER_EN | DISPATCH_INITIATOR_CS_W32_EN)
+        .indirect_buffer(0x1_0000_2000, 64);
+
+    assert_eq!(pm4.dwords(), &[
+        0xC005_3C00, 0x13, 0x1000, 0, 1, 0xFFFF_FFFF, 4,
+        0xC003_7602, 0x207, 8, 1, 1,
+        0xC003_1502, 4, 2, 1, 0x8001,
+        0xC002_3F00, 0x2000, 0x1, 0x80_0040,
+    ]);
+
+    let gfx6 = Pm4Builder::new(CHIP_CLASS::GFX6, HW_IP_TYPE::GFX)
+        .indirect_buffer(0x1_0000_2000, 64)
+        .dwords().to_vec();
+
+    assert_eq!(gfx6, [0xC002_3200, 0x2000, 0x1, 0x40]);
+
+    assert!(pm4.set_sh_reg(0xB81C, &[]).is_err());
+    assert!(pm4.set_sh_reg(0x8000, &[0]).is_err());
+    assert!(pm4.set_sh_reg(SI_SH_REG_END - 4, &[0, 0]).is_err());
+}
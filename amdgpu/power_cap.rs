@@ -1,10 +1,12 @@
 use crate::AMDGPU::DeviceHandle;
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
 use std::str::FromStr;
 use std::path::PathBuf;
 use super::parse_hwmon;
 
 impl DeviceHandle {
-    pub fn get_power_cap(&self) -> Option<PowerCap> {
+    pub fn get_power_cap(&self) -> Result<PowerCap, Error> {
         let hwmon_path = self.get_hwmon_path()?;
 
         PowerCap::from_hwmon_path(hwmon_path)
@@ -12,6 +14,7 @@ impl DeviceHandle {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerCap {
     pub type_: PowerCapType,
     pub current: u32, // W
@@ -21,19 +24,25 @@ pub struct PowerCap {
 }
 
 impl PowerCap {
-    pub fn from_hwmon_path<P: Into<PathBuf>>(path: P) -> Option<Self> {
+    pub fn from_hwmon_path<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
         let path = path.into();
 
-        let label = match std::fs::read_to_string(path.join("power1_label")) {
-            Ok(s) => s,
-            Err(_) => std::fs::read_to_string(path.join("power2_label")).ok()?,
+        let (label_path, label) = match read_sysfs(path.join("power1_label")) {
+            Ok(s) => (path.join("power1_label"), s),
+            Err(_) => {
+                let label_path = path.join("power2_label");
+                let s = read_sysfs(&label_path)?;
+
+                (label_path, s)
+            },
         };
-        let type_ = PowerCapType::from_str(label.as_str().trim_end()).ok()?;
+        let label = label.trim_end();
+        let type_ = PowerCapType::from_str(label).map_err(|_| Error::parse(label_path, label))?;
         let [current, default, min, max] = type_.file_names().map(|name| {
             parse_hwmon::<u32, _>(path.join(name)).map(|v| v.saturating_div(1_000_000))
         });
 
-        Some(Self {
+        Ok(Self {
             type_,
             current: current?,
             default: default?,
@@ -47,9 +56,35 @@ impl PowerCap {
     pub fn check_if_secondary_die(&self) -> bool {
         self.current == 0 && self.default == 0 && self.max == 0
     }
+
+    /// Set the power cap (W), requires root.
+    /// `watts` must be in `min..=max`.
+    pub fn set_current<P: Into<PathBuf>>(&mut self, hwmon_path: P, watts: u32) -> Result<(), Error> {
+        if !(self.min..=self.max).contains(&watts) {
+            return Err(Error::OutOfRange {
+                value: watts as i64,
+                min: self.min as i64,
+                max: self.max as i64,
+            });
+        }
+
+        let [cap, ..] = self.type_.file_names();
+        let micro_watts = (watts as u64) * 1_000_000;
+
+        write_sysfs(hwmon_path.into().join(cap), &micro_watts.to_string())?;
+        self.current = watts;
+
+        Ok(())
+    }
+
+    /// Reset the power cap to the default value, requires root
+    pub fn reset_to_default<P: Into<PathBuf>>(&mut self, hwmon_path: P) -> Result<(), Error> {
+        self.set_current(hwmon_path, self.default)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PowerCapType {
     PPT,
     FastPPT,
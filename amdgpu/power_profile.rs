@@ -1,4 +1,6 @@
 use crate::AMDGPU::DeviceHandle;
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
 use std::fs;
 use std::path::PathBuf;
 
@@ -40,8 +42,8 @@ impl DeviceHandle {
         PowerProfile::get_all_supported_profiles_from_sysfs(sysfs_path)
     }
 
-    pub fn get_current_profile(&self) -> Option<PowerProfile> {
-        let sysfs_path = self.get_sysfs_path().unwrap();
+    pub fn get_current_profile(&self) -> Result<PowerProfile, Error> {
+        let sysfs_path = self.get_sysfs_path()?;
 
         PowerProfile::get_current_profile_from_sysfs(sysfs_path)
     }
@@ -55,9 +57,9 @@ impl PowerProfile {
         s.lines().filter_map(|line| PowerProfile::parse_line(line)).collect()
     }
 
-    pub fn get_current_profile_from_sysfs<P: Into<PathBuf>>(sysfs: P) -> Option<Self> {
-        let sysfs = sysfs.into();
-        let s = fs::read_to_string(sysfs.join(FILE_NAME)).ok()?;
+    pub fn get_current_profile_from_sysfs<P: Into<PathBuf>>(sysfs: P) -> Result<Self, Error> {
+        let path = sysfs.into().join(FILE_NAME);
+        let s = read_sysfs(&path)?;
 
         s.lines().find_map(|line| {
             let profile = Self::parse_line(line)?;
@@ -67,7 +69,18 @@ impl PowerProfile {
             } else {
                 None
             }
-        })
+        }).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    /// Requires root.
+    /// Older (non-SMU) ASICs accept it only in [crate::AMDGPU::DpmForcedLevel::Manual].
+    /// The parameters of [PowerProfile::CUSTOM] are not changed.
+    pub fn set_current<P: Into<PathBuf>>(&self, sysfs: P) -> Result<(), Error> {
+        if *self == Self::COUNT {
+            return Err(Error::InvalidArgument("PowerProfile::COUNT"));
+        }
+
+        write_sysfs(sysfs.into().join(FILE_NAME), &(*self as u32).to_string())
     }
 
     /*
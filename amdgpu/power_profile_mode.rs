@@ -0,0 +1,401 @@
+/*
+    ref: drivers/gpu/drm/amd/pm/powerplay/hwmgr/smu7_hwmgr.c (smu7_get_power_profile_mode)
+    ref: drivers/gpu/drm/amd/pm/powerplay/hwmgr/vega10_hwmgr.c (vega10_get_power_profile_mode)
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu11/navi10_ppt.c (navi10_get_power_profile_mode)
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu13/smu_v13_0_0_ppt.c
+    ref: drivers/gpu/drm/amd/pm/swsmu/smu13/smu_v13_0_7_ppt.c
+*/
+
+use crate::AMDGPU::{DeviceHandle, PowerProfile};
+use crate::Error;
+use crate::error::{read_sysfs, write_sysfs};
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "pp_power_profile_mode";
+
+impl DeviceHandle {
+    pub fn get_power_profile_mode_table(&self) -> Result<PowerProfileModeTable, Error> {
+        PowerProfileModeTable::from_sysfs(self.get_sysfs_path()?)
+    }
+}
+
+/// Layout of `pp_power_profile_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerProfileModeLayout {
+    /// One row per profile (SMU7, Vega10)
+    Flat,
+    /// Rows per clock domain for each profile (SMU11, SMU13.0.0)
+    PerClock,
+    /// One column per profile (SMU13.0.7)
+    Transposed,
+    /// Profile names only (APUs)
+    NameOnly,
+}
+
+/// Heuristic parameters of a profile for a clock domain.
+/// `None` is printed as `-` (not available).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowerProfileHeuristics {
+    /// `(index, name)` of the clock domain (e.g. `(0, "GFXCLK")`) for [PowerProfileModeLayout::PerClock]
+    pub clock: Option<(u32, String)>,
+    pub values: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowerProfileEntry {
+    pub profile: PowerProfile,
+    pub heuristics: Vec<PowerProfileHeuristics>,
+}
+
+/// Parsed `pp_power_profile_mode`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowerProfileModeTable {
+    pub layout: PowerProfileModeLayout,
+    /// Names of heuristic parameters
+    pub columns: Vec<String>,
+    pub profiles: Vec<PowerProfileEntry>,
+    pub current: Option<PowerProfile>,
+}
+
+fn parse_value(s: &str) -> Option<Option<i64>> {
+    if s == "-" {
+        Some(None)
+    } else {
+        s.parse().ok().map(Some)
+    }
+}
+
+fn profile_from_name(name: &str) -> Option<PowerProfile> {
+    (0..PowerProfile::COUNT as u32)
+        .filter_map(|i| PowerProfile::try_from(i).ok())
+        .find(|profile| profile.to_string() == name)
+}
+
+/// `  1 3D_FULL_SCREEN*`, `  1 3D_FULL_SCREEN *` => (profile, is_current)
+fn parse_profile_label(s: &str) -> Option<(PowerProfile, bool)> {
+    let s = s.trim();
+    let is_current = s.ends_with('*');
+    let mut split = s.trim_end_matches('*').split_whitespace();
+    let index: u32 = split.next()?.parse().ok()?;
+
+    Some((PowerProfile::try_from(index).ok()?, is_current))
+}
+
+impl PowerProfileModeTable {
+    pub fn from_sysfs<P: Into<PathBuf>>(sysfs_path: P) -> Result<Self, Error> {
+        let path = sysfs_path.into().join(FILE_NAME);
+        let s = read_sysfs(&path)?;
+
+        Self::parse(&s).ok_or_else(|| Error::parse(&path, s.trim_end()))
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let first = s.lines().find(|line| !line.trim().is_empty())?;
+        let header = first.trim_start();
+
+        if header.starts_with("NUM") {
+            Self::parse_flat(s)
+        } else if header.starts_with("PROFILE_INDEX(NAME)") {
+            Self::parse_per_clock(s)
+        } else if profile_from_name(header.split_whitespace().next()?).is_some() {
+            Self::parse_transposed(s)
+        } else {
+            Self::parse_name_only(s)
+        }
+    }
+
+    /// ```text
+    /// NUM        MODE_NAME BUSY_SET_POINT FPS USE_RLC_BUSY MIN_ACTIVE_LEVEL
+    ///   0 BOOTUP_DEFAULT :             70  60          0              0
+    ///   1 3D_FULL_SCREEN*:             70  60          1              3
+    /// ```
+    fn parse_flat(s: &str) -> Option<Self> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+        let columns = lines.next()?.split_whitespace().skip(2).map(|s| s.to_string()).collect();
+        let mut table = Self::new(PowerProfileModeLayout::Flat, columns);
+
+        for line in lines {
+            let (label, values) = line.split_once(':')?;
+            let (profile, is_current) = parse_profile_label(label)?;
+            let values = values.split_whitespace().map(parse_value).collect::<Option<_>>()?;
+
+            table.push(profile, is_current, vec![PowerProfileHeuristics { clock: None, values }]);
+        }
+
+        Some(table)
+    }
+
+    /// ```text
+    /// PROFILE_INDEX(NAME) CLOCK_TYPE(NAME) FPS UseRlcBusy ...
+    ///  0 BOOTUP_DEFAULT :
+    ///                     0(       GFXCLK)       0       5 ...
+    /// ```
+    fn parse_per_clock(s: &str) -> Option<Self> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+        let columns = lines.next()?.split_whitespace().skip(2).map(|s| s.to_string()).collect();
+        let mut table = Self::new(PowerProfileModeLayout::PerClock, columns);
+
+        for line in lines {
+            if let Some((index, rest)) = line.split_once('(') {
+                let (name, values) = rest.split_once(')')?;
+                let clock = (index.trim().parse().ok()?, name.trim().to_string());
+                let values = values.split_whitespace().map(parse_value).collect::<Option<_>>()?;
+
+                table.profiles.last_mut()?.heuristics.push(PowerProfileHeuristics {
+                    clock: Some(clock),
+                    values,
+                });
+            } else {
+                let (profile, is_current) = parse_profile_label(line.trim().strip_suffix(':')?)?;
+
+                table.push(profile, is_current, Vec::new());
+            }
+        }
+
+        Some(table)
+    }
+
+    /// ```text
+    ///                               BOOTUP_DEFAULT  3D_FULL_SCREEN* POWER_SAVING ...
+    /// FPS                           0               0               0 ...
+    /// ```
+    fn parse_transposed(s: &str) -> Option<Self> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next()?;
+        let mut table = Self::new(PowerProfileModeLayout::Transposed, Vec::new());
+
+        for name in header.split_whitespace() {
+            let is_current = name.ends_with('*');
+            let profile = profile_from_name(name.trim_end_matches('*'))?;
+
+            table.push(
+                profile,
+                is_current,
+                vec![PowerProfileHeuristics { clock: None, values: Vec::new() }],
+            );
+        }
+
+        for line in lines {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let n = tokens.len().checked_sub(table.profiles.len())?;
+            let values = tokens[n..].iter().map(|v| parse_value(v)).collect::<Option<Vec<_>>>()?;
+
+            table.columns.push(tokens[..n].join(" "));
+
+            for (entry, v) in table.profiles.iter_mut().zip(values) {
+                entry.heuristics[0].values.push(v);
+            }
+        }
+
+        Some(table)
+    }
+
+    fn parse_name_only(s: &str) -> Option<Self> {
+        let mut table = Self::new(PowerProfileModeLayout::NameOnly, Vec::new());
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let label = line.split_once(':').map_or(line, |(label, _)| label);
+            let (profile, is_current) = parse_profile_label(label)?;
+
+            table.push(profile, is_current, Vec::new());
+        }
+
+        Some(table)
+    }
+
+    fn new(layout: PowerProfileModeLayout, columns: Vec<String>) -> Self {
+        Self { layout, columns, profiles: Vec::new(), current: None }
+    }
+
+    fn push(&mut self, profile: PowerProfile, is_current: bool, heuristics: Vec<PowerProfileHeuristics>) {
+        if is_current {
+            self.current = Some(profile);
+        }
+
+        self.profiles.push(PowerProfileEntry { profile, heuristics });
+    }
+
+    pub fn get(&self, profile: PowerProfile) -> Option<&PowerProfileEntry> {
+        self.profiles.iter().find(|entry| entry.profile == profile)
+    }
+
+    /// Build the command to set the heuristic parameters of [PowerProfile::CUSTOM].
+    /// `clock_index` is required for [PowerProfileModeLayout::PerClock].
+    /// `values` must have the same number of values as [PowerProfileModeTable::columns].
+    ///
+    /// [PowerProfileModeLayout::Transposed] (SMU13.0.7) is not supported:
+    /// the driver takes a different set and order of parameters than it prints,
+    /// so the command cannot be built from the table.
+    /// [PowerProfileModeLayout::NameOnly] (APU) has no parameters.
+    pub fn custom_command(&self, clock_index: Option<u32>, values: &[i64]) -> Result<String, Error> {
+        let custom = self.get(PowerProfile::CUSTOM)
+            .ok_or(Error::InvalidArgument("CUSTOM profile is not supported"))?;
+
+        if values.len() != self.columns.len() {
+            return Err(Error::InvalidArgument("number of values does not match the columns"));
+        }
+
+        let mut args = vec![(PowerProfile::CUSTOM as u32).to_string()];
+
+        match (self.layout, clock_index) {
+            (PowerProfileModeLayout::Flat, None) => {},
+            (PowerProfileModeLayout::PerClock, Some(index)) => {
+                let clocks = custom.heuristics.iter().filter_map(|h| h.clock.as_ref());
+
+                if !clocks.clone().any(|(i, _)| *i == index) {
+                    return Err(Error::OutOfRange {
+                        value: index as i64,
+                        min: 0,
+                        max: clocks.map(|(i, _)| *i as i64).max().unwrap_or(-1),
+                    });
+                }
+
+                args.push(index.to_string());
+            },
+            (PowerProfileModeLayout::PerClock, None) =>
+                return Err(Error::InvalidArgument("clock index is required")),
+            (PowerProfileModeLayout::Flat, Some(_)) =>
+                return Err(Error::InvalidArgument("clock index is not supported")),
+            (PowerProfileModeLayout::Transposed, _) =>
+                return Err(Error::InvalidArgument("CUSTOM parameters of the transposed layout are not supported")),
+            (PowerProfileModeLayout::NameOnly, _) =>
+                return Err(Error::InvalidArgument("CUSTOM parameters are not supported")),
+        }
+
+        args.extend(values.iter().map(|v| v.to_string()));
+
+        Ok(args.join(" "))
+    }
+
+    /// Set the heuristic parameters of [PowerProfile::CUSTOM] and switch to it, requires root.
+    pub fn set_custom<P: Into<PathBuf>>(
+        &self,
+        sysfs_path: P,
+        clock_index: Option<u32>,
+        values: &[i64],
+    ) -> Result<(), Error> {
+        let cmd = self.custom_command(clock_index, values)?;
+
+        write_sysfs(sysfs_path.into().join(FILE_NAME), &cmd)
+    }
+}
+
+#[test]
+fn test_power_profile_mode_flat() {
+    /* Polaris (SMU7) */
+    const POLARIS: &str = "\
+NUM        MODE_NAME     SCLK_UP_HYST   SCLK_DOWN_HYST SCLK_ACTIVE_LEVEL     MCLK_UP_HYST   MCLK_DOWN_HYST MCLK_ACTIVE_LEVEL
+  0   BOOTUP_DEFAULT:        -                -                -                -                -                -
+  1 3D_FULL_SCREEN *:        0              100               30                0              100               10
+  2     POWER_SAVING:        10               0               30                -                -                -
+  3            VIDEO:        -                -                -               10               16               31
+  4               VR:        0               11               50                0              100               10
+  5          COMPUTE:        0                5               30                -                -                -
+  6           CUSTOM:        0                0                0                0                0                0
+";
+    let table = PowerProfileModeTable::parse(POLARIS).unwrap();
+
+    assert_eq!(table.layout, PowerProfileModeLayout::Flat);
+    assert_eq!(table.columns.len(), 6);
+    assert_eq!(table.current, Some(PowerProfile::FULLSCREEN3D));
+    assert_eq!(table.get(PowerProfile::BOOTUP_DEFAULT).unwrap().heuristics[0].values[0], None);
+    assert_eq!(table.get(PowerProfile::VIDEO).unwrap().heuristics[0].values[5], Some(31));
+    assert_eq!(
+        table.custom_command(None, &[0, 100, 30, 0, 100, 10]).unwrap(),
+        "6 0 100 30 0 100 10",
+    );
+    assert!(table.custom_command(None, &[0, 100]).is_err());
+
+    /* Vega10 */
+    const VEGA10: &str = "\
+NUM        MODE_NAME BUSY_SET_POINT FPS USE_RLC_BUSY MIN_ACTIVE_LEVEL
+  0 BOOTUP_DEFAULT :             70  60          0              0
+  1 3D_FULL_SCREEN :             70  60          1              3
+  2   POWER_SAVING :             90  60          0              0
+  3          VIDEO :             70  10          0              0
+  4             VR :             70  90          0              0
+  5        COMPUTE*:             30  60          0              6
+  6         CUSTOM :              0   0          0              0
+";
+    let table = PowerProfileModeTable::parse(VEGA10).unwrap();
+
+    assert_eq!(table.columns, ["BUSY_SET_POINT", "FPS", "USE_RLC_BUSY", "MIN_ACTIVE_LEVEL"]);
+    assert_eq!(table.current, Some(PowerProfile::COMPUTE));
+    assert_eq!(table.get(PowerProfile::COMPUTE).unwrap().heuristics[0].values[3], Some(6));
+}
+
+#[test]
+fn test_power_profile_mode_per_clock() {
+    /* Navi10 (SMU11) */
+    const NAVI10: &str = "\
+PROFILE_INDEX(NAME) CLOCK_TYPE(NAME) FPS UseRlcBusy MinActiveFreqType MinActiveFreq BoosterFreqType BoosterFreq PD_Data_limit_c PD_Data_error_coeff PD_Data_error_rate_coeff
+ 0 BOOTUP_DEFAULT*:
+                    0(       GFXCLK)       0       5       1       0       4     800 4587520  -65536       0
+                    1(       SOCCLK)       0       5       1       0       1       0 3276800   -6553  -65536
+                    2(        MEMLK)       0       5       0       0       1       0  327680  -65536       0
+ 1 3D_FULL_SCREEN :
+                    0(       GFXCLK)       0       5       1       0       4     650 4587520  -65536       0
+                    1(       SOCCLK)       0       5       1       0       1       0 3276800   -6553  -65536
+                    2(        MEMLK)       0       5       4     850       1       0  327680  -65536       0
+ 6         CUSTOM :
+                    0(       GFXCLK)       0       0       0       0       0       0       0       0       0
+                    1(       SOCCLK)       0       0       0       0       0       0       0       0       0
+                    2(        MEMLK)       0       0       0       0       0       0       0       0       0
+";
+    let table = PowerProfileModeTable::parse(NAVI10).unwrap();
+
+    assert_eq!(table.layout, PowerProfileModeLayout::PerClock);
+    assert_eq!(table.columns.len(), 9);
+    assert_eq!(table.current, Some(PowerProfile::BOOTUP_DEFAULT));
+
+    let fullscreen = table.get(PowerProfile::FULLSCREEN3D).unwrap();
+
+    assert_eq!(fullscreen.heuristics[2].clock, Some((2, "MEMLK".to_string())));
+    assert_eq!(fullscreen.heuristics[2].values[3], Some(850));
+    assert_eq!(fullscreen.heuristics[1].values[7], Some(-6553));
+
+    let values = [0, 5, 1, 0, 4, 900, 4587520, -65536, 0];
+
+    assert_eq!(
+        table.custom_command(Some(0), &values).unwrap(),
+        "6 0 0 5 1 0 4 900 4587520 -65536 0",
+    );
+    assert!(matches!(
+        table.custom_command(Some(3), &values),
+        Err(Error::OutOfRange { value: 3, min: 0, max: 2 }),
+    ));
+    assert!(table.custom_command(None, &values).is_err());
+}
+
+#[test]
+fn test_power_profile_mode_transposed() {
+    /* Navi33 (SMU13.0.7) */
+    const NAVI33: &str = "\
+                              BOOTUP_DEFAULT  3D_FULL_SCREEN* POWER_SAVING    VIDEO           VR              COMPUTE         CUSTOM          WINDOW_3D
+FPS                           0               1               0               0               0               0               0               1
+MinActiveFreqType             0               1               0               0               1               0               0               1
+MinActiveFreq                 0               1650            0               0               500             0               0               1650
+";
+    let table = PowerProfileModeTable::parse(NAVI33).unwrap();
+
+    assert_eq!(table.layout, PowerProfileModeLayout::Transposed);
+    assert_eq!(table.profiles.len(), 8);
+    assert_eq!(table.columns, ["FPS", "MinActiveFreqType", "MinActiveFreq"]);
+    assert_eq!(table.current, Some(PowerProfile::FULLSCREEN3D));
+    assert_eq!(table.get(PowerProfile::WINDOW3D).unwrap().heuristics[0].values[2], Some(1650));
+    assert!(matches!(
+        table.custom_command(None, &[0, 0, 0]),
+        Err(Error::InvalidArgument("CUSTOM parameters of the transposed layout are not supported")),
+    ));
+    assert!(matches!(
+        table.custom_command(Some(0), &[0, 0, 0]),
+        Err(Error::InvalidArgument("CUSTOM parameters of the transposed layout are not supported")),
+    ));
+
+    /* APU */
+    const RENOIR: &str = " 0 BOOTUP_DEFAULT\n 1 3D_FULL_SCREEN*\n 2 POWER_SAVING\n 3 VIDEO\n";
+    let table = PowerProfileModeTable::parse(RENOIR).unwrap();
+
+    assert_eq!(table.layout, PowerProfileModeLayout::NameOnly);
+    assert_eq!(table.current, Some(PowerProfile::FULLSCREEN3D));
+}
@@ -15,6 +15,7 @@ pub enum PPTableDecodeError {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum PPTable {
     V11_0_0(smu_11_0_powerplay_table),
     V11_0_7(smu_11_0_7_powerplay_table),
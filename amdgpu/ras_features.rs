@@ -1,6 +1,7 @@
 use std::fmt;
 use std::path::PathBuf;
-use std::io;
+use crate::Error;
+use crate::error::read_sysfs;
 
 #[derive(Debug, Clone, Copy)]
 pub struct RasEnabledFeatures(u64);
@@ -23,7 +24,7 @@ use crate::AMDGPU::DeviceHandle;
 use crate::bindings::AMDGPU_INFO_RAS_ENABLED_FEATURES;
 
 impl DeviceHandle {
-    pub fn ras_enabled_features(&self) -> Result<RasEnabledFeatures, i32> {
+    pub fn ras_enabled_features(&self) -> Result<RasEnabledFeatures, Error> {
         let v = Self::query(self, AMDGPU_INFO_RAS_ENABLED_FEATURES)?;
 
         Ok(RasEnabledFeatures::new(v))
@@ -48,6 +49,7 @@ use crate::bindings::{
 };
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum RasBlock {
     UMC = AMDGPU_INFO_RAS_ENABLED_UMC,
@@ -67,6 +69,23 @@ pub enum RasBlock {
 }
 
 impl RasBlock {
+    pub const ALL: [Self; 14] = [
+        Self::UMC,
+        Self::SDMA,
+        Self::GFX,
+        Self::MMHUB,
+        Self::ATHUB,
+        Self::PCIE,
+        Self::HDP,
+        Self::XGMI,
+        Self::DF,
+        Self::SMN,
+        Self::SEM,
+        Self::MP0,
+        Self::MP1,
+        Self::FUSE,
+    ];
+
     /// ref: drivers/gpu/drm/amd/amdgpu/amdgpu_ras.c
     pub fn to_sysfs_name_prefix(&self) -> &str {
         match self {
@@ -95,6 +114,7 @@ impl fmt::Display for RasBlock {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RasErrorCount {
     pub uncorrected: u64,
     pub corrected: u64,
@@ -104,23 +124,20 @@ impl RasErrorCount {
     pub fn get_from_sysfs_with_ras_block<P: Into<PathBuf>>(
         sysfs_path: P,
         ras_block: RasBlock,
-    ) -> io::Result<Self> {
-        let s = {
-            let pre = ras_block.to_sysfs_name_prefix();
-            let path = sysfs_path.into().join("ras").join(format!("{pre}_err_count"));
-
-            std::fs::read_to_string(path)?
-        };
+    ) -> Result<Self, Error> {
+        let pre = ras_block.to_sysfs_name_prefix();
+        let path = sysfs_path.into().join("ras").join(format!("{pre}_err_count"));
+        let s = read_sysfs(&path)?;
 
         let mut lines = s.lines();
 
-        let [ue, ce] = [lines.next(), lines.next()].map(|line| -> io::Result<u64> {
+        let [ue, ce] = [lines.next(), lines.next()].map(|line| -> Result<u64, Error> {
             const PRE: usize = "ue: ".len();
 
             line
                 .and_then(|l| l.get(PRE..))
                 .and_then(|s| s.parse().ok())
-                .ok_or(io::Error::other("Parse Error"))
+                .ok_or_else(|| Error::parse(&path, line.unwrap_or_default()))
         });
 
         Ok(Self { uncorrected: ue?, corrected: ce? })
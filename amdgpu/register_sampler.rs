@@ -0,0 +1,315 @@
+use crate::AMDGPU::{
+    DeviceHandle,
+    CHIP_CLASS,
+    StatusReg,
+    GrbmStatus,
+    Grbm2Status,
+    SrbmStatus,
+    Srbm2Status,
+    CpStat,
+};
+use crate::Error;
+use super::sampler_thread::SamplerThread;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Source of MMIO register values for [RegisterSampler]
+pub trait RegisterReader {
+    fn read_register(&self, offset: u32) -> Result<u32, Error>;
+}
+
+impl RegisterReader for DeviceHandle {
+    fn read_register(&self, offset: u32) -> Result<u32, Error> {
+        self.read_mm_registers(offset)
+    }
+}
+
+impl<T: RegisterReader + ?Sized> RegisterReader for Arc<T> {
+    fn read_register(&self, offset: u32) -> Result<u32, Error> {
+        (**self).read_register(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum GpuBlock {
+    /// Graphics Pipe (`GUI_ACTIVE`)
+    GUI,
+    TA,
+    SX,
+    SPI,
+    CB,
+    DB,
+    /// `SRBM_STATUS2` (GFX6-8), `GRBM_STATUS2` (GFX10+)
+    SDMA,
+    /// UVD (GFX6-8), VCN has no busy bit in these registers
+    Media,
+    CP,
+}
+
+impl GpuBlock {
+    pub const ALL: [Self; 9] = [
+        Self::GUI,
+        Self::TA,
+        Self::SX,
+        Self::SPI,
+        Self::CB,
+        Self::DB,
+        Self::SDMA,
+        Self::Media,
+        Self::CP,
+    ];
+
+    /// The block has a busy bit on the [CHIP_CLASS]
+    pub fn is_supported(&self, chip_class: CHIP_CLASS) -> bool {
+        match self {
+            Self::SDMA => chip_class != CHIP_CLASS::GFX9,
+            Self::Media => chip_class < CHIP_CLASS::GFX9,
+            _ => true,
+        }
+    }
+}
+
+use std::fmt;
+impl fmt::Display for GpuBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Busy bits of one sample, indexed by [GpuBlock]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BusySample(pub [bool; GpuBlock::ALL.len()]);
+
+impl BusySample {
+    pub fn is_busy(&self, block: GpuBlock) -> bool {
+        self.0[block as usize]
+    }
+
+    /// `SRBM_STATUS` and `SRBM_STATUS2` are read only on GFX6-8
+    pub fn read<R: RegisterReader + ?Sized>(
+        reader: &R,
+        chip_class: CHIP_CLASS,
+    ) -> Result<Self, Error> {
+        let read = |offset: u32| reader.read_register(offset);
+
+        let grbm = GrbmStatus::decode(read(GrbmStatus::OFFSET)?, chip_class);
+        let grbm2 = Grbm2Status::decode(read(Grbm2Status::OFFSET)?, chip_class);
+        let cp_stat = CpStat::decode(read(CpStat::OFFSET)?, chip_class);
+        let (srbm, srbm2) = if chip_class < CHIP_CLASS::GFX9 {
+            (
+                Some(SrbmStatus::decode(read(SrbmStatus::OFFSET)?, chip_class)),
+                Some(Srbm2Status::decode(read(Srbm2Status::OFFSET)?, chip_class)),
+            )
+        } else {
+            (None, None)
+        };
+
+        Ok(Self::from_regs(&grbm, &grbm2, srbm.as_ref(), srbm2.as_ref(), &cp_stat))
+    }
+
+    pub fn from_regs(
+        grbm: &GrbmStatus,
+        grbm2: &Grbm2Status,
+        srbm: Option<&SrbmStatus>,
+        srbm2: Option<&Srbm2Status>,
+        cp_stat: &CpStat,
+    ) -> Self {
+        let mut s = Self::default();
+        let mut set = |block: GpuBlock, busy: bool| s.0[block as usize] = busy;
+
+        set(GpuBlock::GUI, grbm.gui_active);
+        set(GpuBlock::TA, grbm.ta_busy);
+        set(GpuBlock::SX, grbm.sx_busy);
+        set(GpuBlock::SPI, grbm.spi_busy);
+        set(GpuBlock::CB, grbm.cb_busy);
+        set(GpuBlock::DB, grbm.db_busy);
+        set(
+            GpuBlock::SDMA,
+            grbm2.sdma_busy || srbm2.is_some_and(|r| r.sdma_busy || r.sdma1_busy),
+        );
+        set(GpuBlock::Media, srbm.is_some_and(|r| r.uvd_busy));
+        set(GpuBlock::CP, grbm.cp_busy || cp_stat.cp_busy);
+
+        s
+    }
+}
+
+/// Busy counts over a window of samples
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterUsage {
+    pub samples: u32,
+    pub busy: [u32; GpuBlock::ALL.len()],
+}
+
+impl RegisterUsage {
+    /// Returns 0.0 if there is no sample
+    pub fn percent(&self, block: GpuBlock) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        self.busy[block as usize] as f32 * 100.0 / self.samples as f32
+    }
+}
+
+/// Ring buffer of [BusySample]s, used by [RegisterSampler]
+#[derive(Debug, Clone)]
+pub struct SampleWindow {
+    pub chip_class: CHIP_CLASS,
+    samples: VecDeque<BusySample>,
+    window: usize,
+}
+
+impl SampleWindow {
+    /// `window` is the number of samples to keep (at least 1)
+    pub fn new(chip_class: CHIP_CLASS, window: usize) -> Self {
+        let window = window.max(1);
+
+        Self { chip_class, samples: VecDeque::with_capacity(window), window }
+    }
+
+    pub fn sample<R: RegisterReader + ?Sized>(&mut self, reader: &R) -> Result<BusySample, Error> {
+        let s = BusySample::read(reader, self.chip_class)?;
+        self.push(s);
+
+        Ok(s)
+    }
+
+    pub fn push(&mut self, sample: BusySample) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn usage(&self) -> RegisterUsage {
+        let mut usage = RegisterUsage { samples: self.samples.len() as u32, ..Default::default() };
+
+        for s in &self.samples {
+            for (busy, count) in s.0.iter().zip(usage.busy.iter_mut()) {
+                *count += *busy as u32;
+            }
+        }
+
+        usage
+    }
+}
+
+/// Polls GRBM/SRBM/CP_STAT registers on a background thread.
+/// The thread is stopped when [RegisterSampler] is dropped.
+#[derive(Debug)]
+pub struct RegisterSampler {
+    window: Arc<Mutex<SampleWindow>>,
+    thread: SamplerThread,
+}
+
+impl RegisterSampler {
+    /// Busy percentages are aggregated over the last `window` samples,
+    /// e.g. `interval: 10ms, window: 100` for a 1 second window.
+    /// Failed reads are skipped.
+    pub fn spawn<R: RegisterReader + Send + 'static>(
+        reader: R,
+        chip_class: CHIP_CLASS,
+        interval: Duration,
+        window: usize,
+    ) -> Result<Self, Error> {
+        let window = Arc::new(Mutex::new(SampleWindow::new(chip_class, window)));
+
+        let thread = {
+            let window = window.clone();
+
+            SamplerThread::spawn("RegisterSampler", interval, move || {
+                if let Ok(s) = BusySample::read(&reader, chip_class) {
+                    window.lock().unwrap().push(s);
+                }
+            })?
+        };
+
+        Ok(Self { window, thread })
+    }
+
+    pub fn usage(&self) -> RegisterUsage {
+        self.window.lock().unwrap().usage()
+    }
+
+    pub fn clear(&self) {
+        self.window.lock().unwrap().clear();
+    }
+
+    pub fn stop(mut self) {
+        self.thread.stop();
+    }
+}
+
+#[test]
+fn test_sample_window() {
+    use crate::AMDGPU::{GRBM_OFFSET, GRBM2_OFFSET, SRBM_OFFSET, SRBM2_OFFSET, CP_STAT_OFFSET};
+    use std::cell::Cell;
+
+    struct Scripted {
+        grbm: Vec<u32>,
+        pos: Cell<usize>,
+    }
+
+    impl RegisterReader for Scripted {
+        fn read_register(&self, offset: u32) -> Result<u32, Error> {
+            Ok(match offset {
+                GRBM_OFFSET => {
+                    let pos = self.pos.get();
+                    self.pos.set(pos + 1);
+                    self.grbm[pos % self.grbm.len()]
+                },
+                /* UVD_BUSY */
+                SRBM_OFFSET => 1 << 19,
+                /* SDMA_BUSY (GFX10+) */
+                GRBM2_OFFSET => 1 << 21,
+                SRBM2_OFFSET | CP_STAT_OFFSET => 0,
+                _ => return Err(Error::InvalidArgument("offset")),
+            })
+        }
+    }
+
+    /* GUI_ACTIVE | CB_BUSY, GUI_ACTIVE, idle, idle */
+    let grbm = vec![(1 << 31) | (1 << 30), 1 << 31, 0, 0];
+
+    let reader = Scripted { grbm: grbm.clone(), pos: Cell::new(0) };
+    let mut window = SampleWindow::new(CHIP_CLASS::GFX10_3, 4);
+
+    for _ in 0..6 {
+        window.sample(&reader).unwrap();
+    }
+
+    /* the window holds samples 2..6 */
+    let usage = window.usage();
+    assert_eq!(usage.samples, 4);
+    assert_eq!(usage.percent(GpuBlock::GUI), 50.0);
+    assert_eq!(usage.percent(GpuBlock::CB), 25.0);
+    assert_eq!(usage.percent(GpuBlock::SDMA), 100.0);
+    assert_eq!(usage.percent(GpuBlock::Media), 0.0);
+
+    let reader = Scripted { grbm, pos: Cell::new(0) };
+    let mut window = SampleWindow::new(CHIP_CLASS::GFX8, 4);
+
+    for _ in 0..4 {
+        window.sample(&reader).unwrap();
+    }
+
+    let usage = window.usage();
+    assert_eq!(usage.percent(GpuBlock::SDMA), 0.0);
+    assert_eq!(usage.percent(GpuBlock::Media), 100.0);
+}
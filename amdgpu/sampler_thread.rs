@@ -0,0 +1,53 @@
+use crate::Error;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Background thread shared by [crate::AMDGPU::RegisterSampler] and [crate::AMDGPU::MetricsSampler].
+/// `sample` is called every `interval` until [SamplerThread::stop] or drop.
+#[derive(Debug)]
+pub(crate) struct SamplerThread {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SamplerThread {
+    pub(crate) fn spawn<F: FnMut() + Send + 'static>(
+        name: &str,
+        interval: Duration,
+        mut sample: F,
+    ) -> Result<Self, Error> {
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let stop = stop.clone();
+
+            thread::Builder::new()
+                .name(name.to_string())
+                .spawn(move || {
+                    while !stop.load(Ordering::Acquire) {
+                        sample();
+                        thread::park_timeout(interval);
+                    }
+                })
+                .map_err(|source| Error::SpawnThread { name: name.to_string(), source })?
+        };
+
+        Ok(Self { stop, thread: Some(thread) })
+    }
+
+    pub(crate) fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else { return };
+
+        self.stop.store(true, Ordering::Release);
+        thread.thread().unpark();
+        let _ = thread.join();
+    }
+}
+
+impl Drop for SamplerThread {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
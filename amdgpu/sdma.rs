@@ -0,0 +1,364 @@
+/*
+    ref:
+        https://gitlab.freedesktop.org/mesa/mesa/-/blob/main/src/amd/common/sid.h
+        https://gitlab.freedesktop.org/mesa/drm/-/blob/main/tests/amdgpu/basic_tests.c
+        https://gitlab.freedesktop.org/agd5f/linux/-/blob/amd-staging-drm-next/drivers/gpu/drm/amd/amdgpu/sdma_v5_2.c
+*/
+
+use crate::AMDGPU::{HwId, IpHwId, HW_IP::{HW_IP_TYPE, HwIpInfo}};
+use crate::AMDGPU::PM4::{WaitRegMemFunc, WaitRegMemSpace};
+use crate::Error;
+
+pub const SDMA_OPCODE_NOP: u32 = 0;
+pub const SDMA_OPCODE_COPY: u32 = 1;
+pub const SDMA_OPCODE_FENCE: u32 = 5;
+pub const SDMA_OPCODE_TRAP: u32 = 6;
+pub const SDMA_OPCODE_POLL_REGMEM: u32 = 8;
+pub const SDMA_OPCODE_CONSTANT_FILL: u32 = 11;
+pub const SDMA_OPCODE_TIMESTAMP: u32 = 13;
+
+pub const SDMA_COPY_SUB_OPCODE_LINEAR: u32 = 0;
+pub const SDMA_TS_SUB_OPCODE_GET_LOCAL_TIMESTAMP: u32 = 1;
+pub const SDMA_TS_SUB_OPCODE_GET_GLOBAL_TIMESTAMP: u32 = 2;
+
+/// Max dwords of a burst NOP packet (14-bit count)
+pub const SDMA_NOP_MAX_DW: u32 = 0x3FFF + 1;
+
+/// SDMA packet header
+pub const fn sdma_packet(op: u32, sub_op: u32, extra: u32) -> u32 {
+    ((extra & 0xFFFF) << 16) | ((sub_op & 0xFF) << 8) | (op & 0xFF)
+}
+
+/// SDMA IP version (CIK+), e.g. `5.2` for Navi2x
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SdmaVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl SdmaVersion {
+    pub const SDMA_2_0: Self = Self::new(2, 0);
+    pub const SDMA_2_4: Self = Self::new(2, 4);
+    pub const SDMA_3_0: Self = Self::new(3, 0);
+    pub const SDMA_4_0: Self = Self::new(4, 0);
+    pub const SDMA_5_0: Self = Self::new(5, 0);
+    pub const SDMA_5_2: Self = Self::new(5, 2);
+    pub const SDMA_6_0: Self = Self::new(6, 0);
+    pub const SDMA_7_0: Self = Self::new(7, 0);
+
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns `None` for SI DMA (1.x) and non-DMA IP
+    pub fn from_hw_ip_info(hw_ip_info: &HwIpInfo) -> Option<Self> {
+        if !matches!(hw_ip_info.ip_type, HW_IP_TYPE::DMA) {
+            return None;
+        }
+
+        let (major, minor) = hw_ip_info.info.version();
+
+        Self::check(Self::new(major, minor))
+    }
+
+    /// Returns `None` for non-SDMA IP
+    pub fn from_ip_hw_id(ip_hw_id: &IpHwId) -> Option<Self> {
+        if !matches!(ip_hw_id.hw_id, HwId::SDMA0 | HwId::SDMA1 | HwId::SDMA2 | HwId::SDMA3) {
+            return None;
+        }
+
+        let inst = ip_hw_id.instances.first()?;
+
+        Self::check(Self::new(inst.major as u32, inst.minor as u32))
+    }
+
+    fn check(ver: Self) -> Option<Self> {
+        (Self::SDMA_2_0 <= ver).then_some(ver)
+    }
+
+    /// Max bytes of a COPY_LINEAR/CONSTANT_FILL packet
+    pub fn max_size(&self) -> u32 {
+        if *self >= Self::SDMA_5_2 { (1 << 30) - 4 } else { (1 << 22) - 32 }
+    }
+
+    /// SDMA 4.0+ (Vega+) encodes the byte count as `count - 1`
+    fn count(&self, bytes: u32) -> u32 {
+        if *self >= Self::SDMA_4_0 { bytes - 1 } else { bytes }
+    }
+
+    /// SDMA 2.4+ supports the burst NOP
+    fn burst_nop(&self) -> bool {
+        *self >= Self::SDMA_2_4
+    }
+}
+
+/// SDMA packet builder for the DMA rings.
+/// The encoding of the packets is selected by [SdmaVersion].
+///
+/// ```
+/// use libdrm_amdgpu_sys::AMDGPU::SDMA::*;
+///
+/// let mut sdma = SdmaBuilder::new(SdmaVersion::SDMA_5_2);
+///
+/// sdma.copy_linear(0x1000, 0x2000, 4096)
+///     .fence(0x3000, 1)
+///     .pad(8)
+///     .unwrap();
+///
+/// assert_eq!(sdma.len_dw(), 16);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SdmaBuilder {
+    version: SdmaVersion,
+    buf: Vec<u32>,
+}
+
+impl SdmaBuilder {
+    pub fn new(version: SdmaVersion) -> Self {
+        Self { version, buf: Vec::new() }
+    }
+
+    pub fn version(&self) -> SdmaVersion {
+        self.version
+    }
+
+    pub fn dwords(&self) -> &[u32] {
+        &self.buf
+    }
+
+    pub fn into_dwords(self) -> Vec<u32> {
+        self.buf
+    }
+
+    pub fn len_dw(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Append raw dwords
+    pub fn raw(&mut self, dw: &[u32]) -> &mut Self {
+        self.buf.extend_from_slice(dw);
+        self
+    }
+
+    /// NOP of `num_dw` dwords, up to [SDMA_NOP_MAX_DW] for the burst NOP
+    pub fn nop(&mut self, num_dw: u32) -> Result<&mut Self, Error> {
+        if num_dw == 0 {
+            return Ok(self);
+        }
+
+        if self.version.burst_nop() {
+            if num_dw > SDMA_NOP_MAX_DW {
+                return Err(Error::InvalidArgument("NOP packet is too large"));
+            }
+
+
+            self.buf.push(sdma_packet(SDMA_OPCODE_NOP, 0, num_dw - 1));
+            self.buf.extend(core::iter::repeat_n(0, num_dw as usize - 1));
+        } else {
+            self.buf.extend(core::iter::repeat_n(sdma_packet(SDMA_OPCODE_NOP, 0, 0), num_dw as usize));
+        }
+
+        Ok(self)
+    }
+
+    /// Pad with NOP until the length is a multiple of `align_dw`
+    pub fn pad(&mut self, align_dw: u32) -> Result<&mut Self, Error> {
+        if align_dw == 0 {
+            return Err(Error::InvalidArgument("padding alignment is zero"));
+        }
+
+        let rem = self.buf.len() as u32 % align_dw;
+
+        if rem != 0 {
+            self.nop(align_dw - rem)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Copy `bytes` bytes from `src` to `dst`, split into multiple packets if needed
+    pub fn copy_linear(&mut self, src: u64, dst: u64, bytes: u64) -> &mut Self {
+        let max = self.version.max_size() as u64;
+        let mut offset = 0;
+
+        while offset < bytes {
+            let size = (bytes - offset).min(max);
+            let (src, dst) = (src + offset, dst + offset);
+
+            self.buf.extend([
+                sdma_packet(SDMA_OPCODE_COPY, SDMA_COPY_SUB_OPCODE_LINEAR, 0),
+                self.version.count(size as u32),
+                0, // parameter
+                src as u32,
+                (src >> 32) as u32,
+                dst as u32,
+                (dst >> 32) as u32,
+            ]);
+
+            offset += size;
+        }
+
+        self
+    }
+
+    /// Fill `bytes` bytes at `dst` with `data`, split into multiple packets if needed.
+    /// `dst` and `bytes` must be 4-byte aligned.
+    pub fn constant_fill(&mut self, dst: u64, data: u32, bytes: u64) -> Result<&mut Self, Error> {
+        /* FILLSIZE: dword */
+        const FILL_SIZE_DW: u32 = 2 << 14;
+
+        if !dst.is_multiple_of(4) || !bytes.is_multiple_of(4) {
+            return Err(Error::InvalidArgument("CONSTANT_FILL must be 4-byte aligned"));
+        }
+
+        let max = (self.version.max_size() & !0x3) as u64;
+        let mut offset = 0;
+
+        while offset < bytes {
+            let size = (bytes - offset).min(max);
+            let dst = dst + offset;
+
+            self.buf.extend([
+                sdma_packet(SDMA_OPCODE_CONSTANT_FILL, 0, FILL_SIZE_DW),
+                dst as u32,
+                (dst >> 32) as u32,
+                data,
+                self.version.count(size as u32),
+            ]);
+
+            offset += size;
+        }
+
+        Ok(self)
+    }
+
+    /// Write `data` to `addr` (4-byte aligned) after the previous packets are completed
+    pub fn fence(&mut self, addr: u64, data: u32) -> &mut Self {
+        /* MTYPE: UC */
+        let extra = if self.version >= SdmaVersion::SDMA_5_2 { 0x3 } else { 0 };
+
+        self.buf.extend([
+            sdma_packet(SDMA_OPCODE_FENCE, 0, extra),
+            addr as u32 & !0x3,
+            (addr >> 32) as u32,
+            data,
+        ]);
+
+        self
+    }
+
+    /// Raise an interrupt
+    pub fn trap(&mut self) -> &mut Self {
+        self.buf.extend([
+            sdma_packet(SDMA_OPCODE_TRAP, 0, 0),
+            0, // INT_CONTEXT
+        ]);
+
+        self
+    }
+
+    /// Wait until `(value & mask) func reference` is true,
+    /// `interval` is the poll interval, `retry_count` is `0xFFF` for infinite
+    pub fn poll_regmem(
+        &mut self,
+        func: WaitRegMemFunc,
+        space: WaitRegMemSpace,
+        reference: u32,
+        mask: u32,
+        interval: u16,
+        retry_count: u16,
+    ) -> &mut Self {
+        let (mem_poll, addr_lo, addr_hi) = match space {
+            WaitRegMemSpace::Register(reg) => (0, reg << 2, 0),
+            WaitRegMemSpace::Memory(addr) => (1, addr as u32, (addr >> 32) as u32),
+        };
+        let extra = ((func as u32) << 12) | (mem_poll << 15);
+
+        self.buf.extend([
+            sdma_packet(SDMA_OPCODE_POLL_REGMEM, 0, extra),
+            addr_lo,
+            addr_hi,
+            reference,
+            mask,
+            (interval as u32) | (((retry_count & 0xFFF) as u32) << 16),
+        ]);
+
+        self
+    }
+
+    /// Write the 64-bit global timestamp to `addr` (8-byte aligned)
+    pub fn timestamp(&mut self, addr: u64) -> &mut Self {
+        self.buf.extend([
+            sdma_packet(SDMA_OPCODE_TIMESTAMP, SDMA_TS_SUB_OPCODE_GET_GLOBAL_TIMESTAMP, 0),
+            addr as u32,
+            (addr >> 32) as u32,
+        ]);
+
+        self
+    }
+}
+
+#[test]
+fn test_sdma_copy_fill() {
+    let cik = SdmaBuilder::new(SdmaVersion::SDMA_2_0)
+        .copy_linear(0x1_0000_1000, 0x2_0000_2000, 4096)
+        .dwords().to_vec();
+    let vega = SdmaBuilder::new(SdmaVersion::SDMA_4_0)
+        .copy_linear(0x1_0000_1000, 0x2_0000_2000, 4096)
+        .constant_fill(0x2_0000_2000, 0xDEADBEEF, 4096).unwrap()
+        .dwords().to_vec();
+
+    assert_eq!(cik, [0x0000_0001, 4096, 0, 0x1000, 0x1, 0x2000, 0x2]);
+    assert_eq!(vega, [
+        0x0000_0001, 4095, 0, 0x1000, 0x1, 0x2000, 0x2,
+        0x8000_000B, 0x2000, 0x2, 0xDEAD_BEEF, 4095,
+    ]);
+
+    /* split into 2 packets */
+    let split = SdmaBuilder::new(SdmaVersion::SDMA_3_0)
+        .copy_linear(0, 0x1000_0000, (1 << 22) - 32 + 64)
+        .dwords().to_vec();
+
+    assert_eq!(split.len(), 14);
+    assert_eq!(split[1], (1 << 22) - 32);
+    assert_eq!(split[8], 64);
+    assert_eq!(split[10..12], [(1 << 22) - 32, 0]);
+
+    let mut sdma = SdmaBuilder::new(SdmaVersion::SDMA_4_0);
+
+    assert!(sdma.constant_fill(0x1002, 0, 4096).is_err());
+    assert!(sdma.constant_fill(0x1000, 0, 4098).is_err());
+    assert!(sdma.is_empty());
+}
+
+#[test]
+fn test_sdma_sync() {
+    let mut sdma = SdmaBuilder::new(SdmaVersion::SDMA_5_2);
+
+    sdma.fence(0x1_0000_1000, 7)
+        .trap()
+        .poll_regmem(WaitRegMemFunc::Equal, WaitRegMemSpace::Memory(0x1000), 1, 0xFFFF_FFFF, 4, 0xFFF)
+        .timestamp(0x2000)
+        .nop(3).unwrap();
+
+    assert_eq!(sdma.dwords(), &[
+        0x0003_0005, 0x1000, 0x1, 7,
+        0x0000_0006, 0,
+        0xB000_0008, 0x1000, 0, 1, 0xFFFF_FFFF, 0x0FFF_0004,
+        0x0000_020D, 0x2000, 0,
+        0x0002_0000, 0, 0,
+    ]);
+
+    let cik = SdmaBuilder::new(SdmaVersion::SDMA_2_0)
+        .fence(0x1000, 7)
+        .nop(2).unwrap()
+        .dwords().to_vec();
+
+    assert_eq!(cik, [0x0000_0005, 0x1000, 0, 7, 0, 0]);
+    assert!(sdma.pad(0).is_err());
+    assert!(sdma.nop(SDMA_NOP_MAX_DW + 1).is_err());
+}
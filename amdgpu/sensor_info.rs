@@ -3,7 +3,7 @@ use crate::*;
 use core::mem::{size_of, MaybeUninit};
 
 impl DeviceHandle {
-    pub fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, i32> {
+    pub fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_sensor_info;
         #[cfg(feature = "dynamic_loading")]
@@ -21,7 +21,7 @@ impl DeviceHandle {
 
             let val = val.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_sensor_info", sensor_type);
 
             Ok(val)
         }
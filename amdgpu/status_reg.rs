@@ -0,0 +1,346 @@
+/*
+    ref:
+        drivers/gpu/drm/amd/include/asic_reg/gc/gc_9_0_sh_mask.h
+        drivers/gpu/drm/amd/include/asic_reg/gc/gc_10_1_0_sh_mask.h
+        drivers/gpu/drm/amd/include/asic_reg/gc/gc_11_0_0_sh_mask.h
+        drivers/gpu/drm/amd/include/asic_reg/gca/gfx_8_0_sh_mask.h
+        drivers/gpu/drm/amd/include/asic_reg/oss/oss_3_0_sh_mask.h
+*/
+
+use crate::AMDGPU::{
+    DeviceHandle,
+    CHIP_CLASS,
+    GRBM_OFFSET,
+    GRBM2_OFFSET,
+    SRBM_OFFSET,
+    SRBM2_OFFSET,
+    CP_STAT_OFFSET,
+};
+use crate::Error;
+
+/// Status register decoded per [CHIP_CLASS]
+pub trait StatusReg: Sized {
+    /// Offset for [DeviceHandle::read_mm_registers]
+    const OFFSET: u32;
+
+    fn decode(value: u32, chip_class: CHIP_CLASS) -> Self;
+
+    /// Raw register value
+    fn value(&self) -> u32;
+}
+
+impl DeviceHandle {
+    pub fn read_status_reg<T: StatusReg>(&self, chip_class: CHIP_CLASS) -> Result<T, Error> {
+        self.read_mm_registers(T::OFFSET).map(|value| T::decode(value, chip_class))
+    }
+}
+
+const fn bit(value: u32, n: u32) -> bool {
+    (value >> n) & 0b1 != 0
+}
+
+/// `GRBM_STATUS`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GrbmStatus {
+    pub value: u32,
+    /// Texture Addresser
+    pub ta_busy: bool,
+    /// Global Data Share
+    pub gds_busy: bool,
+    /// Vertex Grouper Tessellator (GFX6-9)
+    pub vgt_busy: bool,
+    /// Input Assembler (GFX6-9)
+    pub ia_busy: bool,
+    /// Work Distributor (GFX6-9)
+    pub wd_busy: bool,
+    /// Geometry Engine (GFX10+)
+    pub ge_busy: bool,
+    /// Shader Export
+    pub sx_busy: bool,
+    /// Shader Processor Interpolator
+    pub spi_busy: bool,
+    /// Barycentric Coordinate Interpolator
+    pub bci_busy: bool,
+    /// Scan Converter
+    pub sc_busy: bool,
+    /// Primitive Assembly
+    pub pa_busy: bool,
+    /// Depth Block
+    pub db_busy: bool,
+    /// Any block is active (GFX11+)
+    pub any_active: bool,
+    /// Command Processor
+    pub cp_busy: bool,
+    /// Color Block
+    pub cb_busy: bool,
+    /// Graphics Pipe
+    pub gui_active: bool,
+}
+
+impl StatusReg for GrbmStatus {
+    const OFFSET: u32 = GRBM_OFFSET;
+
+    fn decode(value: u32, chip_class: CHIP_CLASS) -> Self {
+        let is_gfx10_or_later = chip_class >= CHIP_CLASS::GFX10;
+
+        Self {
+            value,
+            ta_busy: bit(value, 14),
+            gds_busy: bit(value, 15),
+            vgt_busy: !is_gfx10_or_later && bit(value, 17),
+            ia_busy: !is_gfx10_or_later && bit(value, 19),
+            wd_busy: !is_gfx10_or_later && bit(value, 21),
+            /* GE_BUSY replaces WD_BUSY */
+            ge_busy: is_gfx10_or_later && bit(value, 21),
+            sx_busy: bit(value, 20),
+            spi_busy: bit(value, 22),
+            bci_busy: bit(value, 23),
+            sc_busy: bit(value, 24),
+            pa_busy: bit(value, 25),
+            db_busy: bit(value, 26),
+            any_active: chip_class >= CHIP_CLASS::GFX11 && bit(value, 27),
+            cp_busy: bit(value, 29),
+            cb_busy: bit(value, 30),
+            gui_active: bit(value, 31),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// `GRBM_STATUS2`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Grbm2Status {
+    pub value: u32,
+    /// Unified Translation Cache Level-2 (GFX9+)
+    pub utcl2_busy: bool,
+    /// Efficiency Arbiter (GFX9+)
+    pub ea_busy: bool,
+    /// Render Backend Memory Interface (GFX9+)
+    pub rmi_busy: bool,
+    /// SDMA (GFX10+)
+    pub sdma_busy: bool,
+    /// RunList Controller
+    pub rlc_busy: bool,
+    /// Texture Cache
+    pub tc_busy: bool,
+    /// Command Processor Fetcher
+    pub cpf_busy: bool,
+    /// Command Processor Compute
+    pub cpc_busy: bool,
+    /// Command Processor Graphics
+    pub cpg_busy: bool,
+}
+
+impl StatusReg for Grbm2Status {
+    const OFFSET: u32 = GRBM2_OFFSET;
+
+    fn decode(value: u32, chip_class: CHIP_CLASS) -> Self {
+        let is_gfx9_or_later = chip_class >= CHIP_CLASS::GFX9;
+
+        Self {
+            value,
+            utcl2_busy: is_gfx9_or_later && bit(value, 15),
+            ea_busy: is_gfx9_or_later && bit(value, 16),
+            rmi_busy: is_gfx9_or_later && bit(value, 17),
+            sdma_busy: chip_class >= CHIP_CLASS::GFX10 && bit(value, 21),
+            rlc_busy: bit(value, 24),
+            tc_busy: bit(value, 25),
+            cpf_busy: bit(value, 28),
+            cpc_busy: bit(value, 29),
+            cpg_busy: bit(value, 30),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// `SRBM_STATUS` (GFX6-8)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SrbmStatus {
+    pub value: u32,
+    /// Virtual Memory Controller
+    pub vmc_busy: bool,
+    /// Memory Controller Bus
+    pub mcb_busy: bool,
+    /// Semaphore
+    pub sem_busy: bool,
+    /// Interrupt Handler
+    pub ih_busy: bool,
+    /// Unified Video Decoder
+    pub uvd_busy: bool,
+    /// Bus Interface
+    pub bif_busy: bool,
+}
+
+impl StatusReg for SrbmStatus {
+    const OFFSET: u32 = SRBM_OFFSET;
+
+    fn decode(value: u32, _chip_class: CHIP_CLASS) -> Self {
+        Self {
+            value,
+            vmc_busy: bit(value, 8),
+            mcb_busy: bit(value, 9),
+            sem_busy: bit(value, 14),
+            ih_busy: bit(value, 17),
+            uvd_busy: bit(value, 19),
+            bif_busy: bit(value, 29),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// `SRBM_STATUS2` (GFX6-8)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Srbm2Status {
+    pub value: u32,
+    pub sdma_busy: bool,
+    pub sdma1_busy: bool,
+    /// Video Compression Engine
+    pub vce0_busy: bool,
+    pub vce1_busy: bool,
+}
+
+impl StatusReg for Srbm2Status {
+    const OFFSET: u32 = SRBM2_OFFSET;
+
+    fn decode(value: u32, _chip_class: CHIP_CLASS) -> Self {
+        Self {
+            value,
+            sdma_busy: bit(value, 5),
+            sdma1_busy: bit(value, 6),
+            vce0_busy: bit(value, 7),
+            vce1_busy: bit(value, 14),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// `CP_STAT`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CpStat {
+    pub value: u32,
+    /// Prefetch Parser
+    pub pfp_busy: bool,
+    /// Micro Engine Queue
+    pub meq_busy: bool,
+    /// Micro Engine
+    pub me_busy: bool,
+    pub query_busy: bool,
+    pub semaphore_busy: bool,
+    pub interrupt_busy: bool,
+    pub surface_sync_busy: bool,
+    pub dma_busy: bool,
+    pub scratch_ram_busy: bool,
+    /// Constant Engine (GFX6-10)
+    pub ce_busy: bool,
+    pub cp_busy: bool,
+}
+
+impl StatusReg for CpStat {
+    const OFFSET: u32 = CP_STAT_OFFSET;
+
+    fn decode(value: u32, chip_class: CHIP_CLASS) -> Self {
+        Self {
+            value,
+            pfp_busy: bit(value, 15),
+            meq_busy: bit(value, 16),
+            me_busy: bit(value, 17),
+            query_busy: bit(value, 18),
+            semaphore_busy: bit(value, 19),
+            interrupt_busy: bit(value, 20),
+            surface_sync_busy: bit(value, 21),
+            dma_busy: bit(value, 22),
+            scratch_ram_busy: bit(value, 24),
+            /* GFX11 removed the CE */
+            ce_busy: chip_class < CHIP_CLASS::GFX11 && bit(value, 26),
+            cp_busy: bit(value, 31),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+#[test]
+fn test_grbm_status() {
+    /* GUI_ACTIVE | CP_BUSY | bit 21 | VGT_BUSY */
+    let value = (1 << 31) | (1 << 29) | (1 << 21) | (1 << 17);
+    let gfx9 = GrbmStatus::decode(value, CHIP_CLASS::GFX9);
+    let gfx10 = GrbmStatus::decode(value, CHIP_CLASS::GFX10_3);
+
+    assert!(gfx9.gui_active && gfx9.cp_busy);
+    assert!(gfx9.wd_busy && gfx9.vgt_busy && !gfx9.ge_busy);
+    assert!(gfx10.ge_busy && !gfx10.wd_busy && !gfx10.vgt_busy);
+
+    /* UTCL2_BUSY | SDMA_BUSY | CPC_BUSY */
+    let value = (1 << 15) | (1 << 21) | (1 << 29);
+    let gfx8 = Grbm2Status::decode(value, CHIP_CLASS::GFX8);
+    let gfx11 = Grbm2Status::decode(value, CHIP_CLASS::GFX11);
+
+    assert!(gfx8.cpc_busy && !gfx8.utcl2_busy && !gfx8.sdma_busy);
+    assert!(gfx11.cpc_busy && gfx11.utcl2_busy && gfx11.sdma_busy);
+}
+
+#[test]
+fn test_srbm_status() {
+    /* VMC_BUSY | MCB_NON_DISPLAY_BUSY | IH_BUSY | BIF_BUSY */
+    let value = (1 << 8) | (1 << 10) | (1 << 17) | (1 << 29);
+
+    for chip_class in [CHIP_CLASS::GFX6, CHIP_CLASS::GFX8] {
+        let srbm = SrbmStatus::decode(value, chip_class);
+
+        assert_eq!(srbm, SrbmStatus {
+            value,
+            vmc_busy: true,
+            mcb_busy: false,
+            sem_busy: false,
+            ih_busy: true,
+            uvd_busy: false,
+            bif_busy: true,
+        });
+    }
+
+    /* SDMA_BUSY | XDMA_BUSY | VCE1_BUSY */
+    let value = (1 << 5) | (1 << 8) | (1 << 14);
+    let srbm2 = Srbm2Status::decode(value, CHIP_CLASS::GFX7);
+
+    assert_eq!(srbm2, Srbm2Status {
+        value,
+        sdma_busy: true,
+        sdma1_busy: false,
+        vce0_busy: false,
+        vce1_busy: true,
+    });
+}
+
+#[test]
+fn test_cp_stat() {
+    /* CP_BUSY | CE_BUSY | SCRATCH_RAM_BUSY | RCIU_BUSY | ME_BUSY | PFP_BUSY */
+    let value = (1 << 31) | (1 << 26) | (1 << 24) | (1 << 23) | (1 << 17) | (1 << 15);
+    let gfx10 = CpStat::decode(value, CHIP_CLASS::GFX10_3);
+    let gfx11 = CpStat::decode(value, CHIP_CLASS::GFX11);
+    let expected = CpStat {
+        value,
+        pfp_busy: true,
+        me_busy: true,
+        scratch_ram_busy: true,
+        ce_busy: true,
+        cp_busy: true,
+        ..Default::default()
+    };
+
+    assert_eq!(gfx10, expected);
+    assert_eq!(gfx11, CpStat { ce_busy: false, ..expected });
+}
@@ -0,0 +1,410 @@
+use crate::AMDGPU::{DeviceHandle, DEVICE_HANDLE};
+use crate::{query_error, Error};
+use crate::bindings;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::BitOr;
+
+#[cfg(feature = "dynamic_loading")]
+use std::sync::Arc;
+#[cfg(feature = "dynamic_loading")]
+use crate::DynLibDrmAmdgpu;
+
+/// RAII wrapper for a DRM sync object, destroyed on drop.
+/// A `SyncObj` can be used as a binary syncobj ([SyncObj::signal], [SyncObj::wait])
+/// or a timeline syncobj ([SyncObj::timeline_signal], [SyncObj::timeline_wait]).
+/// `SyncObj` borrows the [DeviceHandle], it cannot outlive the device.
+pub struct SyncObj<'dev> {
+    pub(crate) handle: u32,
+    pub(crate) amdgpu_dev: DEVICE_HANDLE,
+    #[cfg(feature = "dynamic_loading")]
+    pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
+    _dev: PhantomData<&'dev DeviceHandle>,
+}
+
+unsafe impl Send for SyncObj<'_> {}
+unsafe impl Sync for SyncObj<'_> {}
+
+impl DeviceHandle {
+    /// Create a syncobj, `signaled` creates it in the signaled state
+    pub fn create_syncobj(&self, signaled: bool) -> Result<SyncObj<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_create_syncobj2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_create_syncobj2;
+
+        let flags = if signaled { bindings::DRM_SYNCOBJ_CREATE_SIGNALED } else { 0 };
+
+        unsafe {
+            let mut handle: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(self.amdgpu_dev, flags, handle.as_mut_ptr());
+
+            query_error!(r, "amdgpu_cs_create_syncobj2");
+
+            Ok(SyncObj::new(self, handle.assume_init()))
+        }
+    }
+
+    /// Import a syncobj from the fd exported by [SyncObj::export_fd]
+    pub fn import_syncobj(&self, shared_fd: i32) -> Result<SyncObj<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_import_syncobj;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_import_syncobj;
+
+        unsafe {
+            let mut handle: MaybeUninit<u32> = MaybeUninit::zeroed();
+
+            let r = func(self.amdgpu_dev, shared_fd, handle.as_mut_ptr());
+
+            query_error!(r, "amdgpu_cs_import_syncobj");
+
+            Ok(SyncObj::new(self, handle.assume_init()))
+        }
+    }
+
+    /// Wait for the binary syncobjs up to `timeout_ns` (relative, as [Fence::wait](crate::AMDGPU::Fence::wait)).
+    /// Returns the index of the first signaled syncobj, or `None` on timeout.
+    pub fn wait_syncobjs(
+        &self,
+        syncobjs: &[&SyncObj],
+        timeout_ns: u64,
+        flags: SyncObjWaitFlags,
+    ) -> Result<Option<u32>, Error> {
+        self.timeline_wait_syncobjs(syncobjs, None, timeout_ns, flags)
+    }
+
+    /// Wait for `points` of the timeline syncobjs up to `timeout_ns` (relative),
+    /// `points` is `None` for binary syncobjs.
+    /// Returns the index of the first signaled syncobj, or `None` on timeout.
+    pub fn timeline_wait_syncobjs(
+        &self,
+        syncobjs: &[&SyncObj],
+        points: Option<&[u64]>,
+        timeout_ns: u64,
+        flags: SyncObjWaitFlags,
+    ) -> Result<Option<u32>, Error> {
+        let mut handles: Vec<u32> = syncobjs.iter().map(|obj| obj.handle).collect();
+
+        syncobj_wait(
+            #[cfg(feature = "dynamic_loading")]
+            &self.libdrm_amdgpu,
+            self.amdgpu_dev,
+            &mut handles,
+            points,
+            timeout_ns,
+            flags,
+        )
+    }
+}
+
+/// `drmSyncobjWait` and `drmSyncobjTimelineWait` take an absolute `CLOCK_MONOTONIC` deadline
+#[allow(clippy::unnecessary_cast)] // `time_t` and `c_long` are `i32` on 32-bit targets
+fn abs_timeout(timeout_ns: u64) -> i64 {
+    let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+        return i64::MAX;
+    }
+
+    (now.tv_sec as i64)
+        .saturating_mul(1_000_000_000)
+        .saturating_add(now.tv_nsec as i64)
+        .saturating_add(i64::try_from(timeout_ns).unwrap_or(i64::MAX))
+}
+
+fn syncobj_wait(
+    #[cfg(feature = "dynamic_loading")]
+    libdrm_amdgpu: &DynLibDrmAmdgpu,
+    amdgpu_dev: DEVICE_HANDLE,
+    handles: &mut [u32],
+    points: Option<&[u64]>,
+    timeout_ns: u64,
+    flags: SyncObjWaitFlags,
+) -> Result<Option<u32>, Error> {
+    let mut first_signaled: MaybeUninit<u32> = MaybeUninit::zeroed();
+    let timeout_ns = abs_timeout(timeout_ns);
+
+    let (r, func_name) = if let Some(points) = points {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_timeline_wait;
+        #[cfg(feature = "dynamic_loading")]
+        let func = libdrm_amdgpu.amdgpu_cs_syncobj_timeline_wait;
+
+        if points.len() != handles.len() {
+            return Err(Error::InvalidArgument("the number of points does not match the number of syncobjs"));
+        }
+
+        let mut points = points.to_vec();
+
+        let r = unsafe { func(
+            amdgpu_dev,
+            handles.as_mut_ptr(),
+            points.as_mut_ptr(),
+            handles.len() as u32,
+            timeout_ns,
+            flags.0,
+            first_signaled.as_mut_ptr(),
+        ) };
+
+        (r, "amdgpu_cs_syncobj_timeline_wait")
+    } else {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_wait;
+        #[cfg(feature = "dynamic_loading")]
+        let func = libdrm_amdgpu.amdgpu_cs_syncobj_wait;
+
+        let r = unsafe { func(
+            amdgpu_dev,
+            handles.as_mut_ptr(),
+            handles.len() as u32,
+            timeout_ns,
+            flags.0,
+            first_signaled.as_mut_ptr(),
+        ) };
+
+        (r, "amdgpu_cs_syncobj_wait")
+    };
+
+    if r == -libc::ETIME {
+        return Ok(None);
+    }
+
+    query_error!(r, func_name);
+
+    Ok(Some(unsafe { first_signaled.assume_init() }))
+}
+
+impl<'dev> SyncObj<'dev> {
+    pub fn new(amdgpu_dev: &'dev DeviceHandle, handle: u32) -> Self {
+        Self {
+            handle,
+            amdgpu_dev: amdgpu_dev.amdgpu_dev,
+            #[cfg(feature = "dynamic_loading")]
+            libdrm_amdgpu: amdgpu_dev.libdrm_amdgpu.clone(),
+            _dev: PhantomData,
+        }
+    }
+
+    /// Handle of the syncobj, used for [CommandSubmission](crate::AMDGPU::CommandSubmission)
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    pub fn reset(&self) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_reset;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_reset;
+
+        let r = unsafe { func(self.amdgpu_dev, &self.handle, 1) };
+
+        query_error!(r, "amdgpu_cs_syncobj_reset");
+
+        Ok(())
+    }
+
+    pub fn signal(&self) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_signal;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_signal;
+
+        let r = unsafe { func(self.amdgpu_dev, &self.handle, 1) };
+
+        query_error!(r, "amdgpu_cs_syncobj_signal");
+
+        Ok(())
+    }
+
+    pub fn timeline_signal(&self, point: u64) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_timeline_signal;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_timeline_signal;
+
+        let mut point = point;
+        let r = unsafe { func(self.amdgpu_dev, &self.handle, &mut point, 1) };
+
+        query_error!(r, "amdgpu_cs_syncobj_timeline_signal");
+
+        Ok(())
+    }
+
+    /// Wait up to `timeout_ns` (relative), returns `false` on timeout
+    pub fn wait(&self, timeout_ns: u64, flags: SyncObjWaitFlags) -> Result<bool, Error> {
+        syncobj_wait(
+            #[cfg(feature = "dynamic_loading")]
+            &self.libdrm_amdgpu,
+            self.amdgpu_dev,
+            &mut [self.handle],
+            None,
+            timeout_ns,
+            flags,
+        ).map(|v| v.is_some())
+    }
+
+    /// Wait for `point` up to `timeout_ns` (relative), returns `false` on timeout
+    pub fn timeline_wait(
+        &self,
+        point: u64,
+        timeout_ns: u64,
+        flags: SyncObjWaitFlags,
+    ) -> Result<bool, Error> {
+        syncobj_wait(
+            #[cfg(feature = "dynamic_loading")]
+            &self.libdrm_amdgpu,
+            self.amdgpu_dev,
+            &mut [self.handle],
+            Some(&[point]),
+            timeout_ns,
+            flags,
+        ).map(|v| v.is_some())
+    }
+
+    /// Returns the current (signaled) timeline point,
+    /// or the last submitted point if `last_submitted` is true.
+    pub fn query(&self, last_submitted: bool) -> Result<u64, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_query2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_query2;
+
+        let flags = if last_submitted { bindings::DRM_SYNCOBJ_QUERY_FLAGS_LAST_SUBMITTED } else { 0 };
+        let mut handle = self.handle;
+        let mut point = 0u64;
+
+        let r = unsafe { func(self.amdgpu_dev, &mut handle, &mut point, 1, flags) };
+
+        query_error!(r, "amdgpu_cs_syncobj_query2");
+
+        Ok(point)
+    }
+
+    /// Transfer the fence at `src_point` of `src` to `dst_point` of `self`.
+    /// A point of `0` means the binary syncobj.
+    pub fn transfer_from(
+        &self,
+        dst_point: u64,
+        src: &SyncObj,
+        src_point: u64,
+        flags: SyncObjWaitFlags,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_transfer;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_transfer;
+
+        let r = unsafe { func(
+            self.amdgpu_dev,
+            self.handle,
+            dst_point,
+            src.handle,
+            src_point,
+            flags.0,
+        ) };
+
+        query_error!(r, "amdgpu_cs_syncobj_transfer");
+
+        Ok(())
+    }
+
+    /// Export the syncobj as a fd, can be imported by [DeviceHandle::import_syncobj]
+    pub fn export_fd(&self) -> Result<i32, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_export_syncobj;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_export_syncobj;
+
+        let mut fd = -1;
+        let r = unsafe { func(self.amdgpu_dev, self.handle, &mut fd) };
+
+        query_error!(r, "amdgpu_cs_export_syncobj");
+
+        Ok(fd)
+    }
+
+    /// Export the fence at `point` (`0` for the binary syncobj) as a sync_file fd
+    pub fn export_sync_file(&self, point: u64) -> Result<i32, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_export_sync_file2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_export_sync_file2;
+
+        let mut fd = -1;
+        let r = unsafe { func(self.amdgpu_dev, self.handle, point, 0, &mut fd) };
+
+        query_error!(r, "amdgpu_cs_syncobj_export_sync_file2");
+
+        Ok(fd)
+    }
+
+    /// Import the sync_file fd to `point` (`0` for the binary syncobj)
+    pub fn import_sync_file(&self, point: u64, sync_file_fd: i32) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_syncobj_import_sync_file2;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_syncobj_import_sync_file2;
+
+        let r = unsafe { func(self.amdgpu_dev, self.handle, point, sync_file_fd) };
+
+        query_error!(r, "amdgpu_cs_syncobj_import_sync_file2");
+
+        Ok(())
+    }
+
+    unsafe fn free(&self) -> Result<(), Error> { unsafe {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_cs_destroy_syncobj;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_cs_destroy_syncobj;
+
+        let r = func(self.amdgpu_dev, self.handle);
+
+        query_error!(r, "amdgpu_cs_destroy_syncobj");
+
+        Ok(())
+    }}
+}
+
+impl Drop for SyncObj<'_> {
+    fn drop(&mut self) {
+        /* the syncobj handle is invalid after this, there is nothing to do on failure */
+        let _ = unsafe { self.free() };
+    }
+}
+
+use crate::bindings::{
+    DRM_SYNCOBJ_WAIT_FLAGS_WAIT_ALL,
+    DRM_SYNCOBJ_WAIT_FLAGS_WAIT_FOR_SUBMIT,
+    DRM_SYNCOBJ_WAIT_FLAGS_WAIT_AVAILABLE,
+};
+
+/// Flags (`DRM_SYNCOBJ_WAIT_FLAGS_*`) for waiting/transferring syncobjs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncObjWaitFlags(pub u32);
+
+impl SyncObjWaitFlags {
+    pub const NONE: Self = Self(0);
+    pub const WAIT_ALL: Self = Self(DRM_SYNCOBJ_WAIT_FLAGS_WAIT_ALL);
+    pub const WAIT_FOR_SUBMIT: Self = Self(DRM_SYNCOBJ_WAIT_FLAGS_WAIT_FOR_SUBMIT);
+    pub const WAIT_AVAILABLE: Self = Self(DRM_SYNCOBJ_WAIT_FLAGS_WAIT_AVAILABLE);
+}
+
+impl BitOr for SyncObjWaitFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[test]
+fn test_abs_timeout() {
+    let now = abs_timeout(0);
+
+    assert!(now > 0);
+    assert!(abs_timeout(1_000_000_000) >= now + 1_000_000_000);
+    assert_eq!(abs_timeout(u64::MAX), i64::MAX);
+}
@@ -0,0 +1,707 @@
+/*
+    Recording format (little-endian):
+        header: b"AMDGPUTL", version: u16, pci_len: u16, pci: [u8; pci_len]
+        record: kind: u8, len: u32, payload: [u8; len]
+
+    A frame starts with a `FRAME` record (timestamp_ns: u64),
+    the following records belong to the frame until the next `FRAME` record.
+    Failed reads are recorded with the error kind and replayed as the same error variant
+    (version 1 recorded the status (negative errno) only).
+*/
+
+use crate::AMDGPU::{
+    DeviceHandle,
+    DpmClockType,
+    FdInfoScanner,
+    FdInfoUsage,
+    GpuMetrics,
+    ProcessUsage,
+};
+use crate::AMDGPU::SENSOR_INFO::SENSOR_TYPE;
+use crate::{Error, PCI};
+use crate::error::read_sysfs;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Query interface shared by a live [DeviceHandle] and [TelemetryReplay]
+pub trait TelemetrySource {
+    /// `"{sysfs_path}/gpu_metrics"`
+    fn get_raw_gpu_metrics(&self) -> Result<Vec<u8>, Error>;
+    fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, Error>;
+    /// A file in the sysfs directory of the device, e.g. `"pp_dpm_sclk"`
+    fn read_sysfs_file(&self, name: &str) -> Result<String, Error>;
+    /// DRM clients of the device from `/proc/*/fdinfo`
+    fn fdinfo(&self) -> Vec<ProcessUsage>;
+
+    fn get_gpu_metrics(&self) -> Result<GpuMetrics, Error> {
+        Ok(GpuMetrics::from_raw(&self.get_raw_gpu_metrics()?))
+    }
+}
+
+impl TelemetrySource for DeviceHandle {
+    fn get_raw_gpu_metrics(&self) -> Result<Vec<u8>, Error> {
+        DeviceHandle::get_raw_gpu_metrics(self)
+    }
+
+    fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, Error> {
+        DeviceHandle::sensor_info(self, sensor_type)
+    }
+
+    fn read_sysfs_file(&self, name: &str) -> Result<String, Error> {
+        read_sysfs(self.get_sysfs_path()?.join(name))
+    }
+
+    fn fdinfo(&self) -> Vec<ProcessUsage> {
+        self.get_pci_bus_info()
+            .and_then(FdInfoScanner::new)
+            .map(|scanner| scanner.scan())
+            .unwrap_or_default()
+    }
+}
+
+/// What [TelemetryRecorder::capture] reads from the source
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureConfig {
+    pub gpu_metrics: bool,
+    pub sensors: Vec<SENSOR_TYPE>,
+    pub sysfs_files: Vec<String>,
+    pub fdinfo: bool,
+}
+
+impl Default for CaptureConfig {
+    /// `gpu_metrics`, clock/temperature/load/power/voltage sensors, `pp_dpm_*`,
+    /// `power_dpm_force_performance_level` and fdinfo
+    fn default() -> Self {
+        let sysfs_files = DpmClockType::ALL
+            .iter()
+            .map(|type_| type_.file_name().to_string())
+            .chain(["power_dpm_force_performance_level".to_string()])
+            .collect();
+
+        Self {
+            gpu_metrics: true,
+            sensors: vec![
+                SENSOR_TYPE::GFX_SCLK,
+                SENSOR_TYPE::GFX_MCLK,
+                SENSOR_TYPE::GPU_TEMP,
+                SENSOR_TYPE::GPU_LOAD,
+                SENSOR_TYPE::GPU_AVG_POWER,
+                SENSOR_TYPE::GPU_INPUT_POWER,
+                SENSOR_TYPE::VDDNB,
+                SENSOR_TYPE::VDDGFX,
+            ],
+            sysfs_files,
+            fdinfo: true,
+        }
+    }
+}
+
+/// Values read at one point in time
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RecordedFrame {
+    /// Nanoseconds since the recording started
+    pub timestamp_ns: u64,
+    pub gpu_metrics: Option<Result<Vec<u8>, RecordedError>>,
+    /// Keyed by `SENSOR_TYPE as u32`
+    pub sensors: BTreeMap<u32, Result<u32, RecordedError>>,
+    pub sysfs: BTreeMap<String, Result<String, RecordedError>>,
+    pub fdinfo: Vec<ProcessUsage>,
+}
+
+/// A failed read, replayed as the same [Error] variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedError {
+    /// The (negative) errno of [Error::Query], [Error::Io], [Error::Unsupported]
+    /// or [Error::PermissionDenied]
+    Errno(i32),
+    /// [Error::Parse] with the text that failed to parse
+    Parse(String),
+    /// [Error::OutOfRange]
+    OutOfRange { value: i64, min: i64, max: i64 },
+    /// [Error::InvalidArgument], the message is not recorded
+    InvalidArgument,
+}
+
+impl From<&Error> for RecordedError {
+    fn from(e: &Error) -> Self {
+        match e {
+            Error::Parse { text, .. } => Self::Parse(text.clone()),
+            Error::OutOfRange { value, min, max } =>
+                Self::OutOfRange { value: *value, min: *min, max: *max },
+            Error::InvalidArgument(_) => Self::InvalidArgument,
+            Error::Unsupported { source: None, .. } => Self::Errno(-libc::ENOENT),
+            Error::PermissionDenied { source: None, .. } => Self::Errno(-libc::EACCES),
+            _ => Self::Errno(e.errno().unwrap_or(-libc::EINVAL)),
+        }
+    }
+}
+
+impl RecordedError {
+    const ERRNO: u8 = 1;
+    const PARSE: u8 = 2;
+    const OUT_OF_RANGE: u8 = 3;
+    const INVALID_ARGUMENT: u8 = 4;
+
+    /// `errno_error` builds the error for [RecordedError::Errno], `name` is the path of [Error::Parse]
+    fn to_error<F: FnOnce(i32) -> Error>(&self, name: &str, errno_error: F) -> Error {
+        match self {
+            Self::Errno(errno) => errno_error(*errno),
+            Self::Parse(text) => Error::parse(name, text.as_str()),
+            Self::OutOfRange { value, min, max } =>
+                Error::OutOfRange { value: *value, min: *min, max: *max },
+            Self::InvalidArgument => Error::InvalidArgument("recorded invalid argument"),
+        }
+    }
+}
+
+struct RecordKind;
+
+impl RecordKind {
+    const FRAME: u8 = 0;
+    const GPU_METRICS: u8 = 1;
+    const SENSOR: u8 = 2;
+    const SYSFS: u8 = 3;
+    const FDINFO: u8 = 4;
+}
+
+/// Writes [RecordedFrame]s to a file (or any [Write])
+#[derive(Debug)]
+pub struct TelemetryRecorder<W: Write> {
+    writer: W,
+    start: Instant,
+}
+
+impl TelemetryRecorder<io::BufWriter<std::fs::File>> {
+    pub fn create<P: AsRef<Path>>(path: P, pci: Option<PCI::BUS_INFO>) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        std::fs::File::create(path)
+            .and_then(|f| Self::new(io::BufWriter::new(f), pci))
+            .map_err(|e| Error::io(path, e))
+    }
+}
+
+impl<W: Write> TelemetryRecorder<W> {
+    pub const MAGIC: [u8; 8] = *b"AMDGPUTL";
+    pub const VERSION: u16 = 2;
+
+    /// Writes the header
+    pub fn new(mut writer: W, pci: Option<PCI::BUS_INFO>) -> io::Result<Self> {
+        let pci = pci.map(|pci| pci.to_string()).unwrap_or_default();
+
+        writer.write_all(&Self::MAGIC)?;
+        writer.write_all(&Self::VERSION.to_le_bytes())?;
+        writer.write_all(&(pci.len() as u16).to_le_bytes())?;
+        writer.write_all(pci.as_bytes())?;
+
+        Ok(Self { writer, start: Instant::now() })
+    }
+
+    /// Reads the values in `config` from `source` and writes them as a frame
+    pub fn capture<S: TelemetrySource + ?Sized>(
+        &mut self,
+        source: &S,
+        config: &CaptureConfig,
+    ) -> io::Result<RecordedFrame> {
+        let timestamp_ns = self.start.elapsed().as_nanos() as u64;
+        let gpu_metrics = config.gpu_metrics
+            .then(|| source.get_raw_gpu_metrics().map_err(|e| RecordedError::from(&e)));
+        let sensors = config.sensors
+            .iter()
+            .map(|s| (*s as u32, source.sensor_info(*s).map_err(|e| RecordedError::from(&e))))
+            .collect();
+        let sysfs = config.sysfs_files
+            .iter()
+            .map(|name| {
+                (name.clone(), source.read_sysfs_file(name).map_err(|e| RecordedError::from(&e)))
+            })
+            .collect();
+        let fdinfo = if config.fdinfo { source.fdinfo() } else { Vec::new() };
+
+        let frame = RecordedFrame { timestamp_ns, gpu_metrics, sensors, sysfs, fdinfo };
+        self.write_frame(&frame)?;
+
+        Ok(frame)
+    }
+
+    pub fn write_frame(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        let mut p = Vec::new();
+
+        put_u64(&mut p, frame.timestamp_ns);
+        self.write_record(RecordKind::FRAME, &p)?;
+
+        if let Some(gpu_metrics) = &frame.gpu_metrics {
+            p.clear();
+            put_error(&mut p, gpu_metrics.as_ref().err());
+            p.extend_from_slice(gpu_metrics.as_deref().unwrap_or_default());
+            self.write_record(RecordKind::GPU_METRICS, &p)?;
+        }
+
+        for (sensor_type, val) in &frame.sensors {
+            p.clear();
+            put_u32(&mut p, *sensor_type);
+            put_error(&mut p, val.as_ref().err());
+            put_u32(&mut p, *val.as_ref().unwrap_or(&0));
+            self.write_record(RecordKind::SENSOR, &p)?;
+        }
+
+        for (name, s) in &frame.sysfs {
+            p.clear();
+            put_error(&mut p, s.as_ref().err());
+            put_str(&mut p, name);
+            p.extend_from_slice(s.as_deref().unwrap_or_default().as_bytes());
+            self.write_record(RecordKind::SYSFS, &p)?;
+        }
+
+        for proc in &frame.fdinfo {
+            let u = &proc.usage;
+
+            p.clear();
+            p.extend_from_slice(&proc.pid.to_le_bytes());
+            put_str(&mut p, &proc.name);
+
+            for v in [u.client_id, u.vram, u.gtt, u.gfx, u.compute, u.dec, u.enc] {
+                put_u64(&mut p, v);
+            }
+
+            self.write_record(RecordKind::FDINFO, &p)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_record(&mut self, kind: u8, payload: &[u8]) -> io::Result<()> {
+        self.writer.write_all(&[kind])?;
+        self.writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+        self.writer.write_all(payload)
+    }
+}
+
+fn put_u32(p: &mut Vec<u8>, v: u32) {
+    p.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_u64(p: &mut Vec<u8>, v: u64) {
+    p.extend_from_slice(&v.to_le_bytes());
+}
+
+/// kind: u8 (0: no error), followed by the fields of [RecordedError]
+fn put_error(p: &mut Vec<u8>, e: Option<&RecordedError>) {
+    match e {
+        None => p.push(0),
+        Some(RecordedError::Errno(errno)) => {
+            p.push(RecordedError::ERRNO);
+            p.extend_from_slice(&errno.to_le_bytes());
+        },
+        Some(RecordedError::Parse(text)) => {
+            p.push(RecordedError::PARSE);
+            put_u32(p, text.len() as u32);
+            p.extend_from_slice(text.as_bytes());
+        },
+        Some(RecordedError::OutOfRange { value, min, max }) => {
+            p.push(RecordedError::OUT_OF_RANGE);
+
+            for v in [value, min, max] {
+                p.extend_from_slice(&v.to_le_bytes());
+            }
+        },
+        Some(RecordedError::InvalidArgument) => p.push(RecordedError::INVALID_ARGUMENT),
+    }
+}
+
+fn put_str(p: &mut Vec<u8>, s: &str) {
+    p.extend_from_slice(&(s.len() as u16).to_le_bytes());
+    p.extend_from_slice(s.as_bytes());
+}
+
+/// Cursor over a record payload, `version` of the recording
+struct Payload<'a>(&'a [u8], u16);
+
+impl<'a> Payload<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("truncated record"));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        let len = self.u16()? as usize;
+
+        to_string(self.take(len)?)
+    }
+
+    fn error(&mut self) -> io::Result<Result<(), RecordedError>> {
+        /* version 1: status (negative errno) */
+        if self.1 == 1 {
+            return Ok(match self.i32()? {
+                0 => Ok(()),
+                status => Err(RecordedError::Errno(status)),
+            });
+        }
+
+        Ok(Err(match self.take(1)?[0] {
+            0 => return Ok(Ok(())),
+            RecordedError::ERRNO => RecordedError::Errno(self.i32()?),
+            RecordedError::PARSE => {
+                let len = self.u32()? as usize;
+
+                RecordedError::Parse(to_string(self.take(len)?)?)
+            },
+            RecordedError::OUT_OF_RANGE => RecordedError::OutOfRange {
+                value: self.i64()?,
+                min: self.i64()?,
+                max: self.i64()?,
+            },
+            RecordedError::INVALID_ARGUMENT => RecordedError::InvalidArgument,
+            _ => return Err(invalid_data("unknown error kind")),
+        }))
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.0)
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn to_string(bytes: &[u8]) -> io::Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("invalid UTF-8"))
+}
+
+/// Replays a recording made by [TelemetryRecorder].
+/// Queries return the values of the current frame, [TelemetryReplay::advance] moves to the next frame.
+#[derive(Debug, Clone)]
+pub struct TelemetryReplay {
+    pub pci: Option<PCI::BUS_INFO>,
+    frames: Vec<RecordedFrame>,
+    pos: usize,
+}
+
+impl TelemetryReplay {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        std::fs::File::open(path)
+            .and_then(|f| Self::from_reader(io::BufReader::new(f)))
+            .map_err(|e| Error::io(path, e))
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        let mut r = Payload(&buf, 0);
+
+        if r.take(8)? != TelemetryRecorder::<io::Sink>::MAGIC {
+            return Err(invalid_data("not a telemetry recording"));
+        }
+
+        let version = r.u16()?;
+
+        if !(1..=TelemetryRecorder::<io::Sink>::VERSION).contains(&version) {
+            return Err(invalid_data("unsupported recording version"));
+        }
+
+        let pci = r.str()?.parse().ok();
+        let mut frames: Vec<RecordedFrame> = Vec::new();
+
+        while !r.0.is_empty() {
+            let kind = r.take(1)?[0];
+            let len = r.u32()? as usize;
+            let mut p = Payload(r.take(len)?, version);
+
+            if kind == RecordKind::FRAME {
+                frames.push(RecordedFrame { timestamp_ns: p.u64()?, ..Default::default() });
+                continue;
+            }
+
+            let Some(frame) = frames.last_mut() else {
+                return Err(invalid_data("record before the first frame"));
+            };
+
+            match kind {
+                RecordKind::GPU_METRICS => {
+                    let error = p.error()?;
+                    frame.gpu_metrics = Some(error.map(|_| p.rest().to_vec()));
+                },
+                RecordKind::SENSOR => {
+                    let sensor_type = p.u32()?;
+                    let error = p.error()?;
+                    let val = p.u32()?;
+                    frame.sensors.insert(sensor_type, error.map(|_| val));
+                },
+                RecordKind::SYSFS => {
+                    let error = p.error()?;
+                    let name = p.str()?;
+                    let s = to_string(p.rest())?;
+                    frame.sysfs.insert(name, error.map(|_| s));
+                },
+                RecordKind::FDINFO => {
+                    let pid = p.i32()?;
+                    let name = p.str()?;
+                    let mut v = [0u64; 7];
+
+                    for v in v.iter_mut() {
+                        *v = p.u64()?;
+                    }
+
+                    let [client_id, vram, gtt, gfx, compute, dec, enc] = v;
+                    let usage = FdInfoUsage { client_id, vram, gtt, gfx, compute, dec, enc };
+
+                    frame.fdinfo.push(ProcessUsage { pid, name, usage });
+                },
+                /* skip unknown records for forward compatibility */
+                _ => {},
+            }
+        }
+
+        Ok(Self { pci, frames, pos: 0 })
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn current(&self) -> Option<&RecordedFrame> {
+        self.frames.get(self.pos)
+    }
+
+    /// Returns `false` at the last frame
+    pub fn advance(&mut self) -> bool {
+        if self.pos + 1 >= self.frames.len() {
+            return false;
+        }
+
+        self.pos += 1;
+
+        true
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos.min(self.frames.len().saturating_sub(1));
+    }
+
+    /// Recorded `gpu_metrics` blobs of all frames, e.g. for [crate::AMDGPU::MetricsSeries::from_raw_blobs]
+    pub fn raw_gpu_metrics(&self) -> impl Iterator<Item = &[u8]> {
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.gpu_metrics.as_ref()?.as_deref().ok())
+    }
+
+    fn frame(&self) -> Result<&RecordedFrame, Error> {
+        self.current().ok_or(Error::InvalidArgument("empty recording"))
+    }
+
+    fn file_error(name: &str, e: &RecordedError) -> Error {
+        e.to_error(name, |errno| {
+            Error::io(PathBuf::from(name), io::Error::from_raw_os_error(-errno))
+        })
+    }
+}
+
+impl TelemetrySource for TelemetryReplay {
+    fn get_raw_gpu_metrics(&self) -> Result<Vec<u8>, Error> {
+        match &self.frame()?.gpu_metrics {
+            Some(Ok(raw)) => Ok(raw.clone()),
+            Some(Err(e)) => Err(Self::file_error("gpu_metrics", e)),
+            None => Err(Error::InvalidArgument("gpu_metrics is not recorded")),
+        }
+    }
+
+    fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, Error> {
+        match self.frame()?.sensors.get(&(sensor_type as u32)) {
+            Some(Ok(val)) => Ok(*val),
+            Some(Err(e)) => Err(e.to_error("amdgpu_query_sensor_info", |errno| Error::Query {
+                func: "amdgpu_query_sensor_info",
+                query: Some(sensor_type as u32),
+                errno,
+            })),
+            None => Err(Error::InvalidArgument("the sensor is not recorded")),
+        }
+    }
+
+    fn read_sysfs_file(&self, name: &str) -> Result<String, Error> {
+        match self.frame()?.sysfs.get(name) {
+            Some(Ok(s)) => Ok(s.clone()),
+            Some(Err(e)) => Err(Self::file_error(name, e)),
+            None => Err(Error::InvalidArgument("the sysfs file is not recorded")),
+        }
+    }
+
+    fn fdinfo(&self) -> Vec<ProcessUsage> {
+        self.current().map(|frame| frame.fdinfo.clone()).unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_telemetry_record_replay() {
+    use std::cell::Cell;
+
+    struct Fake {
+        tick: Cell<u32>,
+    }
+
+    impl TelemetrySource for Fake {
+        fn get_raw_gpu_metrics(&self) -> Result<Vec<u8>, Error> {
+            Ok(vec![0x78, 0x00, 1, 3, self.tick.get() as u8])
+        }
+
+        fn sensor_info(&self, sensor_type: SENSOR_TYPE) -> Result<u32, Error> {
+            match sensor_type {
+                SENSOR_TYPE::GFX_SCLK => Ok(500 + self.tick.get() * 100),
+                _ => Err(Error::Query {
+                    func: "amdgpu_query_sensor_info",
+                    query: Some(sensor_type as u32),
+                    errno: -libc::EINVAL,
+                }),
+            }
+        }
+
+        fn read_sysfs_file(&self, name: &str) -> Result<String, Error> {
+            match name {
+                "pp_dpm_sclk" => Ok("0: 500Mhz\n1: 2600Mhz *\n".to_string()),
+                "pp_od_clk_voltage" => Err(Error::parse(name, "OD_SCLK:\n0: ???Mhz")),
+                "pp_power_profile_mode" => Err(Error::InvalidArgument("unknown layout")),
+                _ => Err(Error::Unsupported { path: PathBuf::from(name), source: None }),
+            }
+        }
+
+        fn fdinfo(&self) -> Vec<ProcessUsage> {
+            let usage = FdInfoUsage {
+                client_id: 7,
+                vram: 1 << 30,
+                gfx: self.tick.get() as u64,
+                ..Default::default()
+            };
+
+            vec![ProcessUsage { pid: 1234, name: "glxgears".to_string(), usage }]
+        }
+    }
+
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let fake = Fake { tick: Cell::new(0) };
+    let config = CaptureConfig {
+        sensors: vec![SENSOR_TYPE::GFX_SCLK, SENSOR_TYPE::VDDNB],
+        sysfs_files: [
+            "pp_dpm_sclk",
+            "pp_dpm_dcefclk",
+            "pp_od_clk_voltage",
+            "pp_power_profile_mode",
+        ].map(|s| s.to_string()).to_vec(),
+        ..Default::default()
+    };
+    let mut recorder = TelemetryRecorder::new(Vec::new(), Some(pci)).unwrap();
+    let mut recorded = Vec::new();
+
+    for tick in 0..3 {
+        fake.tick.set(tick);
+        recorded.push(recorder.capture(&fake, &config).unwrap());
+    }
+
+    let buf = recorder.into_inner();
+    let mut replay = TelemetryReplay::from_reader(buf.as_slice()).unwrap();
+
+    assert_eq!(replay.pci, Some(pci));
+    assert_eq!(replay.frames(), recorded.as_slice());
+    assert_eq!(replay.sensor_info(SENSOR_TYPE::GFX_SCLK).unwrap(), 500);
+
+    assert!(replay.advance());
+    assert!(replay.advance());
+    assert!(!replay.advance());
+
+    assert_eq!(replay.sensor_info(SENSOR_TYPE::GFX_SCLK).unwrap(), 700);
+    assert_eq!(replay.sensor_info(SENSOR_TYPE::VDDNB).unwrap_err().errno(), Some(-libc::EINVAL));
+    assert!(replay.sensor_info(SENSOR_TYPE::GPU_TEMP).is_err());
+    assert_eq!(replay.read_sysfs_file("pp_dpm_sclk").unwrap(), "0: 500Mhz\n1: 2600Mhz *\n");
+    assert!(replay.read_sysfs_file("pp_dpm_dcefclk").unwrap_err().is_unsupported());
+    assert!(matches!(
+        replay.read_sysfs_file("pp_od_clk_voltage"),
+        Err(Error::Parse { path, text }) if path == Path::new("pp_od_clk_voltage")
+            && text == "OD_SCLK:\n0: ???Mhz",
+    ));
+    assert!(matches!(
+        replay.read_sysfs_file("pp_power_profile_mode"),
+        Err(Error::InvalidArgument(_)),
+    ));
+    assert_eq!(replay.fdinfo()[0].usage.gfx, 2);
+    assert_eq!(replay.get_raw_gpu_metrics().unwrap(), [0x78, 0x00, 1, 3, 2]);
+    assert_eq!(replay.raw_gpu_metrics().count(), 3);
+
+    /* re-recording the replay gives the same frames */
+    let mut recorder = TelemetryRecorder::new(Vec::new(), replay.pci).unwrap();
+
+    for frame in replay.frames() {
+        recorder.write_frame(frame).unwrap();
+    }
+
+    assert_eq!(recorder.into_inner(), buf);
+    assert!(TelemetryReplay::from_reader(&buf[..buf.len() - 1]).is_err());
+
+    /* version 1: status (negative errno) */
+    let mut v1 = TelemetryRecorder::<io::Sink>::MAGIC.to_vec();
+    v1.extend_from_slice(&1u16.to_le_bytes());
+    v1.extend_from_slice(&0u16.to_le_bytes());
+
+    for (kind, payload) in [
+        (RecordKind::FRAME, 42u64.to_le_bytes().to_vec()),
+        (RecordKind::GPU_METRICS, (-libc::ENOENT).to_le_bytes().to_vec()),
+    ] {
+        v1.push(kind);
+        v1.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        v1.extend_from_slice(&payload);
+    }
+
+    let replay = TelemetryReplay::from_reader(v1.as_slice()).unwrap();
+
+    assert_eq!(replay.frames()[0].gpu_metrics, Some(Err(RecordedError::Errno(-libc::ENOENT))));
+    assert!(replay.get_raw_gpu_metrics().unwrap_err().is_unsupported());
+}
@@ -0,0 +1,310 @@
+/*
+    ref: lib/kobject_uevent.c
+    ref: drivers/gpu/drm/drm_sysfs.c
+*/
+
+use crate::{Error, PCI};
+use std::collections::{HashMap, HashSet};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+/// Kernel uevent message: `ACTION@DEVPATH\0KEY=VALUE\0...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uevent {
+    pub action: String,
+    pub devpath: String,
+    pub vars: HashMap<String, String>,
+}
+
+impl Uevent {
+    /// Returns `None` for messages re-broadcast by udev (`libudev\0` header)
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        let mut fields = buf.split(|b| *b == 0).filter(|f| !f.is_empty());
+        let header = std::str::from_utf8(fields.next()?).ok()?;
+        let (action, devpath) = header.split_once('@')?;
+        let vars = fields
+            .filter_map(|f| {
+                let (key, val) = std::str::from_utf8(f).ok()?.split_once('=')?;
+
+                Some((key.to_string(), val.to_string()))
+            })
+            .collect();
+
+        Some(Self { action: action.to_string(), devpath: devpath.to_string(), vars })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(|s| s.as_str())
+    }
+
+    pub fn subsystem(&self) -> Option<&str> {
+        self.get("SUBSYSTEM")
+    }
+
+    /// `PCI_SLOT_NAME` for the pci subsystem, otherwise the last PCI device in `DEVPATH`
+    /// (e.g. `/devices/pci0000:00/0000:00:01.1/0000:0b:00.0/drm/card1`)
+    pub fn pci_bus_info(&self) -> Option<PCI::BUS_INFO> {
+        if let Some(slot) = self.get("PCI_SLOT_NAME") {
+            return slot.parse().ok();
+        }
+
+        self.devpath
+            .split('/')
+            .rev()
+            .find_map(|s| if s.len() == 12 { s.parse().ok() } else { None })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// The device was bound to the amdgpu driver
+    Added(PCI::BUS_INFO),
+    /// The device was unbound from the amdgpu driver, or removed
+    Removed(PCI::BUS_INFO),
+    /// Display hotplug, `connector` is the DRM connector ID if the kernel reports it
+    Changed { pci: PCI::BUS_INFO, connector: Option<u32> },
+    /// GPU reset (`RESET=1` or `WEDGED=`)
+    Reset(PCI::BUS_INFO),
+}
+
+impl DeviceEvent {
+    pub fn pci(&self) -> PCI::BUS_INFO {
+        match self {
+            Self::Added(pci) |
+            Self::Removed(pci) |
+            Self::Changed { pci, .. } |
+            Self::Reset(pci) => *pci,
+        }
+    }
+
+    /// PCI events are filtered by `DRIVER=amdgpu`,
+    /// DRM events are not, because they do not have the driver name.
+    /// A PCI `remove` uevent does not have the driver name either, use [DeviceEventTracker] for it.
+    pub fn from_uevent(uevent: &Uevent) -> Option<Self> {
+        let pci = uevent.pci_bus_info()?;
+
+        match uevent.subsystem()? {
+            "pci" => {
+                if uevent.get("DRIVER") != Some("amdgpu") {
+                    return None;
+                }
+
+                match uevent.action.as_str() {
+                    "bind" => Some(Self::Added(pci)),
+                    "unbind" => Some(Self::Removed(pci)),
+                    _ => None,
+                }
+            },
+            "drm" => {
+                if uevent.action != "change" {
+                    return None;
+                }
+
+                if uevent.get("RESET") == Some("1") || uevent.get("WEDGED").is_some() {
+                    Some(Self::Reset(pci))
+                } else if uevent.get("HOTPLUG") == Some("1") {
+                    let connector = uevent.get("CONNECTOR").and_then(|s| s.parse().ok());
+
+                    Some(Self::Changed { pci, connector })
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Tracks the devices bound to amdgpu, to report PCI `remove` uevents
+/// and to drop DRM events of other devices.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceEventTracker {
+    bound: HashSet<PCI::BUS_INFO>,
+}
+
+impl DeviceEventTracker {
+    /// `bound`: devices already bound to amdgpu (e.g. [crate::AMDGPU::get_all_amdgpu_pci_bus])
+    pub fn new<I: IntoIterator<Item = PCI::BUS_INFO>>(bound: I) -> Self {
+        Self { bound: bound.into_iter().collect() }
+    }
+
+    pub fn is_bound(&self, pci: &PCI::BUS_INFO) -> bool {
+        self.bound.contains(pci)
+    }
+
+    pub fn handle(&mut self, uevent: &Uevent) -> Option<DeviceEvent> {
+        /* the device is removed without unbind (e.g. hot-unplug) */
+        if uevent.subsystem() == Some("pci") && uevent.action == "remove" {
+            let pci = uevent.pci_bus_info()?;
+
+            return self.bound.remove(&pci).then_some(DeviceEvent::Removed(pci));
+        }
+
+        let event = DeviceEvent::from_uevent(uevent)?;
+
+        match event {
+            DeviceEvent::Added(pci) => { self.bound.insert(pci); },
+            DeviceEvent::Removed(pci) => { self.bound.remove(&pci); },
+            DeviceEvent::Changed { pci, .. } |
+            DeviceEvent::Reset(pci) => if !self.bound.contains(&pci) {
+                return None;
+            },
+        }
+
+        Some(event)
+    }
+}
+
+/// Listens on the kernel uevent netlink socket (`NETLINK_KOBJECT_UEVENT`)
+#[derive(Debug)]
+pub struct UeventWatcher {
+    fd: OwnedFd,
+    buf: Vec<u8>,
+    tracker: DeviceEventTracker,
+}
+
+impl UeventWatcher {
+    const SOCKET_NAME: &str = "NETLINK_KOBJECT_UEVENT";
+    const BUF_SIZE: usize = 8192;
+
+    pub fn new() -> Result<Self, Error> {
+        let last_error = || Error::io(Self::SOCKET_NAME, std::io::Error::last_os_error());
+
+        unsafe {
+            let fd = libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_KOBJECT_UEVENT,
+            );
+
+            if fd < 0 {
+                return Err(last_error());
+            }
+
+            let fd = OwnedFd::from_raw_fd(fd);
+            let mut addr: libc::sockaddr_nl = core::mem::zeroed();
+
+            addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            /* kernel multicast group */
+            addr.nl_groups = 1;
+
+            let r = libc::bind(
+                fd.as_raw_fd(),
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            );
+
+            if r < 0 {
+                return Err(last_error());
+            }
+
+            let tracker = DeviceEventTracker::new(super::get_all_amdgpu_pci_bus());
+
+            Ok(Self { fd, buf: vec![0; Self::BUF_SIZE], tracker })
+        }
+    }
+
+    /// Blocks until a uevent is received
+    pub fn recv_uevent(&mut self) -> Result<Option<Uevent>, Error> {
+        let len = unsafe {
+            libc::recv(
+                self.fd.as_raw_fd(),
+                self.buf.as_mut_ptr() as *mut libc::c_void,
+                self.buf.len(),
+                0,
+            )
+        };
+
+        if len < 0 {
+            return Err(Error::io(Self::SOCKET_NAME, std::io::Error::last_os_error()));
+        }
+
+        Ok(Uevent::parse(&self.buf[..len as usize]))
+    }
+
+    /// Blocks until an event for amdgpu devices is received.
+    /// DRM events are dropped if the device is not bound to amdgpu.
+    pub fn recv(&mut self) -> Result<DeviceEvent, Error> {
+        loop {
+            let Some(uevent) = self.recv_uevent()? else { continue };
+            let Some(event) = self.tracker.handle(&uevent) else { continue };
+
+            return Ok(event);
+        }
+    }
+}
+
+impl AsRawFd for UeventWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[test]
+fn test_uevent_parse() {
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+
+    let buf = b"bind@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        ACTION=bind\0DEVPATH=/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        SUBSYSTEM=pci\0DRIVER=amdgpu\0PCI_CLASS=30000\0PCI_ID=1002:73BF\0\
+        PCI_SLOT_NAME=0000:0b:00.0\0SEQNUM=4242\0";
+    let uevent = Uevent::parse(buf).unwrap();
+
+    assert_eq!(uevent.action, "bind");
+    assert_eq!(DeviceEvent::from_uevent(&uevent), Some(DeviceEvent::Added(pci)));
+
+    let buf = b"change@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0/drm/card1\0\
+        ACTION=change\0DEVPATH=/devices/pci0000:00/0000:00:01.1/0000:0b:00.0/drm/card1\0\
+        SUBSYSTEM=drm\0HOTPLUG=1\0CONNECTOR=95\0DEVNAME=dri/card1\0SEQNUM=4243\0";
+    let uevent = Uevent::parse(buf).unwrap();
+
+    assert_eq!(
+        DeviceEvent::from_uevent(&uevent),
+        Some(DeviceEvent::Changed { pci, connector: Some(95) }),
+    );
+
+    let buf = b"change@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0/drm/card1\0\
+        ACTION=change\0SUBSYSTEM=drm\0WEDGED=rebind\0";
+
+    assert_eq!(DeviceEvent::from_uevent(&Uevent::parse(buf).unwrap()), Some(DeviceEvent::Reset(pci)));
+
+    let buf = b"libudev\0\xfe\xed\xca\xfe";
+
+    assert_eq!(Uevent::parse(buf), None);
+}
+
+#[test]
+fn test_device_event_tracker() {
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let other: PCI::BUS_INFO = "0000:0c:00.0".parse().unwrap();
+    let mut tracker = DeviceEventTracker::new([other]);
+
+    let bind = b"bind@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        ACTION=bind\0DEVPATH=/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        SUBSYSTEM=pci\0DRIVER=amdgpu\0PCI_CLASS=30000\0PCI_ID=1002:73BF\0\
+        PCI_SLOT_NAME=0000:0b:00.0\0SEQNUM=4242\0";
+    /* hot-unplug, without unbind */
+    let remove = b"remove@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        ACTION=remove\0DEVPATH=/devices/pci0000:00/0000:00:01.1/0000:0b:00.0\0\
+        SUBSYSTEM=pci\0PCI_CLASS=30000\0PCI_ID=1002:73BF\0PCI_SUBSYS_ID=1002:0E3A\0\
+        PCI_SLOT_NAME=0000:0b:00.0\0MODALIAS=pci:v00001002d000073BFsv00001002sd00000E3Abc03sc00i00\0\
+        SEQNUM=4250\0";
+    let hotplug = b"change@/devices/pci0000:00/0000:00:01.1/0000:0b:00.0/drm/card1\0\
+        ACTION=change\0SUBSYSTEM=drm\0HOTPLUG=1\0";
+    let [bind, remove, hotplug] = [&bind[..], remove, hotplug].map(|b| Uevent::parse(b).unwrap());
+
+    assert_eq!(DeviceEvent::from_uevent(&remove), None);
+    /* not bound */
+    assert_eq!(tracker.handle(&hotplug), None);
+    assert_eq!(tracker.handle(&remove), None);
+
+    assert_eq!(tracker.handle(&bind), Some(DeviceEvent::Added(pci)));
+    assert!(tracker.is_bound(&pci));
+    assert_eq!(
+        tracker.handle(&hotplug),
+        Some(DeviceEvent::Changed { pci, connector: None }),
+    );
+    assert_eq!(tracker.handle(&remove), Some(DeviceEvent::Removed(pci)));
+    assert!(!tracker.is_bound(&pci));
+    assert_eq!(tracker.handle(&remove), None);
+    assert!(tracker.is_bound(&other));
+}
@@ -0,0 +1,444 @@
+use crate::AMDGPU::{BufferObject, DeviceHandle, DEVICE_HANDLE};
+use crate::{query_error, Error};
+use crate::bindings::{self, amdgpu_va_handle};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{BitOr, BitOrAssign};
+
+#[cfg(feature = "dynamic_loading")]
+use std::sync::Arc;
+#[cfg(feature = "dynamic_loading")]
+use crate::DynLibDrmAmdgpu;
+
+/// GPU page size, the address/offset/size of VA operations must be aligned to it.
+pub const AMDGPU_GPU_PAGE_SIZE: u64 = 4096;
+
+/// GPU virtual address space available for UMDs.
+/// ref: `amdgpu_va_range_query`, [drm_amdgpu_info_device](crate::AMDGPU::drm_amdgpu_info_device)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaSpace {
+    /// Start address of the general VA range managed by libdrm
+    pub start: u64,
+    /// End address of the general VA range managed by libdrm
+    pub end: u64,
+    /// Required alignment of virtual addresses
+    pub alignment: u32,
+    /// Page table entry fragment size
+    pub pte_fragment_size: u32,
+    /// Starting/maximum address for [VaRangeFlags::HIGH]
+    pub high_start: u64,
+    pub high_end: u64,
+}
+
+impl VaSpace {
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn high_size(&self) -> u64 {
+        self.high_end.saturating_sub(self.high_start)
+    }
+}
+
+/// RAII wrapper for `amdgpu_va_handle`, freed on drop.
+/// Allocate by [DeviceHandle::alloc_va_range].
+/// `VaRange` borrows the [DeviceHandle], it cannot outlive the device.
+pub struct VaRange<'dev> {
+    pub(crate) va_handle: amdgpu_va_handle,
+    pub(crate) amdgpu_dev: DEVICE_HANDLE,
+    pub(crate) start: u64,
+    pub(crate) size: u64,
+    #[cfg(feature = "dynamic_loading")]
+    pub(crate) libdrm_amdgpu: Arc<DynLibDrmAmdgpu>,
+    _dev: PhantomData<&'dev DeviceHandle>,
+}
+
+unsafe impl Send for VaRange<'_> {}
+unsafe impl Sync for VaRange<'_> {}
+
+impl DeviceHandle {
+    pub fn va_space(&self) -> Result<VaSpace, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_va_range_query;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_va_range_query;
+
+        let dev_info = self.device_info()?;
+
+        unsafe {
+            let mut start: MaybeUninit<u64> = MaybeUninit::zeroed();
+            let mut end: MaybeUninit<u64> = MaybeUninit::zeroed();
+
+            let r = func(
+                self.amdgpu_dev,
+                bindings::amdgpu_gpu_va_range_amdgpu_gpu_va_range_general,
+                start.as_mut_ptr(),
+                end.as_mut_ptr(),
+            );
+
+            query_error!(r, "amdgpu_va_range_query");
+
+            Ok(VaSpace {
+                start: start.assume_init(),
+                end: end.assume_init(),
+                alignment: dev_info.virtual_address_alignment,
+                pte_fragment_size: dev_info.pte_fragment_size,
+                high_start: dev_info.high_va_offset,
+                high_end: dev_info.high_va_max,
+            })
+        }
+    }
+
+    /// Allocate a GPU virtual address range.
+    /// `alignment` is the base address alignment in bytes (`0` for the default),
+    /// `required_base` is the required base address (`0` for any address).
+    pub fn alloc_va_range(
+        &self,
+        size: u64,
+        alignment: u64,
+        required_base: u64,
+        flags: VaRangeFlags,
+    ) -> Result<VaRange<'_>, Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_va_range_alloc;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_va_range_alloc;
+
+        if size == 0 || !size.is_multiple_of(AMDGPU_GPU_PAGE_SIZE) {
+            return Err(Error::InvalidArgument("VA range size must be a non-zero multiple of the GPU page size"));
+        }
+
+        unsafe {
+            let mut start: MaybeUninit<u64> = MaybeUninit::zeroed();
+            let mut va_handle: MaybeUninit<amdgpu_va_handle> = MaybeUninit::zeroed();
+
+            let r = func(
+                self.amdgpu_dev,
+                bindings::amdgpu_gpu_va_range_amdgpu_gpu_va_range_general,
+                size,
+                alignment,
+                required_base,
+                start.as_mut_ptr(),
+                va_handle.as_mut_ptr(),
+                flags.0,
+            );
+
+            query_error!(r, "amdgpu_va_range_alloc");
+
+            Ok(VaRange {
+                va_handle: va_handle.assume_init(),
+                amdgpu_dev: self.amdgpu_dev,
+                start: start.assume_init(),
+                size,
+                #[cfg(feature = "dynamic_loading")]
+                libdrm_amdgpu: self.libdrm_amdgpu.clone(),
+                _dev: PhantomData,
+            })
+        }
+    }
+}
+
+impl VaRange<'_> {
+    /// Start address of the range.
+    /// With `link_drm`, this is from `amdgpu_va_get_start_addr`.
+    pub fn start(&self) -> u64 {
+        #[cfg(feature = "link_drm")]
+        {
+            unsafe { bindings::amdgpu_va_get_start_addr(self.va_handle) }
+        }
+        /* `amdgpu_va_get_start_addr` is not available for dynamic loading (libdrm 2.4.118+) */
+        #[cfg(feature = "dynamic_loading")]
+        {
+            self.start
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn end(&self) -> u64 {
+        self.start + self.size
+    }
+
+    /// Map `size` bytes of `bo` starting at `bo_offset` to `addr`
+    pub fn map(
+        &self,
+        bo: &BufferObject,
+        bo_offset: u64,
+        addr: u64,
+        size: u64,
+        flags: VmPageFlags,
+    ) -> Result<(), Error> {
+        self.va_op(Some(bo), bo_offset, addr, size, flags, VaOp::MAP)
+    }
+
+    pub fn unmap(
+        &self,
+        bo: &BufferObject,
+        bo_offset: u64,
+        addr: u64,
+        size: u64,
+    ) -> Result<(), Error> {
+        self.va_op(Some(bo), bo_offset, addr, size, VmPageFlags::NONE, VaOp::UNMAP)
+    }
+
+    /// Replace the existing mappings in `addr..addr+size` with `bo`
+    pub fn replace(
+        &self,
+        bo: &BufferObject,
+        bo_offset: u64,
+        addr: u64,
+        size: u64,
+        flags: VmPageFlags,
+    ) -> Result<(), Error> {
+        self.va_op(Some(bo), bo_offset, addr, size, flags, VaOp::REPLACE)
+    }
+
+    /// Clear all mappings in `addr..addr+size`
+    pub fn clear(&self, addr: u64, size: u64) -> Result<(), Error> {
+        self.va_op(None, 0, addr, size, VmPageFlags::NONE, VaOp::CLEAR)
+    }
+
+    fn va_op(
+        &self,
+        bo: Option<&BufferObject>,
+        bo_offset: u64,
+        addr: u64,
+        size: u64,
+        flags: VmPageFlags,
+        op: VaOp,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_bo_va_op_raw;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_bo_va_op_raw;
+
+        validate_va_op(
+            (self.start, self.size),
+            bo.map(|bo| bo.size()),
+            bo_offset,
+            addr,
+            size,
+            flags,
+        )?;
+
+        let bo_handle = bo.map_or(core::ptr::null_mut(), |bo| bo.bo_handle);
+
+        let r = unsafe { func(
+            self.amdgpu_dev,
+            bo_handle,
+            bo_offset,
+            size,
+            addr,
+            flags.0,
+            op as u32,
+        ) };
+
+        query_error!(r, "amdgpu_bo_va_op_raw");
+
+        Ok(())
+    }
+
+    unsafe fn free(&self) -> Result<(), Error> { unsafe {
+        #[cfg(feature = "link_drm")]
+        let func = bindings::amdgpu_va_range_free;
+        #[cfg(feature = "dynamic_loading")]
+        let func = self.libdrm_amdgpu.amdgpu_va_range_free;
+
+        let r = func(self.va_handle);
+
+        query_error!(r, "amdgpu_va_range_free");
+
+        Ok(())
+    }}
+}
+
+impl Drop for VaRange<'_> {
+    fn drop(&mut self) {
+        /* the VA handle is invalid after this, there is nothing to do on failure */
+        let _ = unsafe { self.free() };
+    }
+}
+
+fn validate_va_op(
+    (range_start, range_size): (u64, u64),
+    bo_size: Option<u64>,
+    bo_offset: u64,
+    addr: u64,
+    size: u64,
+    flags: VmPageFlags,
+) -> Result<(), Error> {
+    if size == 0 {
+        return Err(Error::InvalidArgument("VA operation size is zero"));
+    }
+
+    if [bo_offset, addr, size].iter().any(|v| !v.is_multiple_of(AMDGPU_GPU_PAGE_SIZE)) {
+        return Err(Error::InvalidArgument("VA operation is not aligned to the GPU page size"));
+    }
+
+    let Some(end) = addr.checked_add(size) else {
+        return Err(Error::InvalidArgument("VA operation overflows the address space"));
+    };
+
+    let range_end = range_start.checked_add(range_size);
+
+    if addr < range_start || range_end.is_none_or(|range_end| range_end < end) {
+        return Err(Error::VaOutOfRange {
+            addr,
+            size,
+            start: range_start,
+            end: range_end.unwrap_or(u64::MAX),
+        });
+    }
+
+    if bo_size.is_some_and(|bo_size| bo_size < bo_offset.saturating_add(size)) {
+        return Err(Error::InvalidArgument("VA operation is out of the buffer object"));
+    }
+
+    if (flags.0 & !VmPageFlags::VALID_MASK) != 0 {
+        return Err(Error::InvalidArgument("unknown VM page flags"));
+    }
+
+    if (flags.0 & VmPageFlags::MTYPE_MASK) > VmPageFlags::MTYPE_RW.0 {
+        return Err(Error::InvalidArgument("unknown VM MTYPE"));
+    }
+
+    Ok(())
+}
+
+use crate::bindings::{
+    AMDGPU_VA_OP_MAP,
+    AMDGPU_VA_OP_UNMAP,
+    AMDGPU_VA_OP_CLEAR,
+    AMDGPU_VA_OP_REPLACE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum VaOp {
+    MAP = AMDGPU_VA_OP_MAP,
+    UNMAP = AMDGPU_VA_OP_UNMAP,
+    CLEAR = AMDGPU_VA_OP_CLEAR,
+    REPLACE = AMDGPU_VA_OP_REPLACE,
+}
+
+use crate::bindings::{
+    AMDGPU_VA_RANGE_32_BIT,
+    AMDGPU_VA_RANGE_HIGH,
+    AMDGPU_VA_RANGE_REPLAYABLE,
+};
+
+/// Flags (`AMDGPU_VA_RANGE_*`) for [DeviceHandle::alloc_va_range]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VaRangeFlags(pub u64);
+
+impl VaRangeFlags {
+    pub const NONE: Self = Self(0);
+    pub const RANGE_32_BIT: Self = Self(AMDGPU_VA_RANGE_32_BIT as u64);
+    pub const HIGH: Self = Self(AMDGPU_VA_RANGE_HIGH as u64);
+    pub const REPLAYABLE: Self = Self(AMDGPU_VA_RANGE_REPLAYABLE as u64);
+}
+
+impl BitOr for VaRangeFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+use crate::bindings::{
+    AMDGPU_VM_DELAY_UPDATE,
+    AMDGPU_VM_PAGE_READABLE,
+    AMDGPU_VM_PAGE_WRITEABLE,
+    AMDGPU_VM_PAGE_EXECUTABLE,
+    AMDGPU_VM_PAGE_PRT,
+    AMDGPU_VM_PAGE_NOALLOC,
+    AMDGPU_VM_MTYPE_MASK,
+    AMDGPU_VM_MTYPE_NC,
+    AMDGPU_VM_MTYPE_WC,
+    AMDGPU_VM_MTYPE_CC,
+    AMDGPU_VM_MTYPE_UC,
+    AMDGPU_VM_MTYPE_RW,
+};
+
+/// Mapping flags (`AMDGPU_VM_PAGE_*`, `AMDGPU_VM_MTYPE_*`) for [VaRange::map]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VmPageFlags(pub u64);
+
+impl VmPageFlags {
+    pub const NONE: Self = Self(0);
+    pub const DELAY_UPDATE: Self = Self(AMDGPU_VM_DELAY_UPDATE as u64);
+    pub const READABLE: Self = Self(AMDGPU_VM_PAGE_READABLE as u64);
+    pub const WRITEABLE: Self = Self(AMDGPU_VM_PAGE_WRITEABLE as u64);
+    pub const EXECUTABLE: Self = Self(AMDGPU_VM_PAGE_EXECUTABLE as u64);
+    pub const PRT: Self = Self(AMDGPU_VM_PAGE_PRT as u64);
+    pub const NOALLOC: Self = Self(AMDGPU_VM_PAGE_NOALLOC as u64);
+    pub const MTYPE_NC: Self = Self(AMDGPU_VM_MTYPE_NC as u64);
+    pub const MTYPE_WC: Self = Self(AMDGPU_VM_MTYPE_WC as u64);
+    pub const MTYPE_CC: Self = Self(AMDGPU_VM_MTYPE_CC as u64);
+    pub const MTYPE_UC: Self = Self(AMDGPU_VM_MTYPE_UC as u64);
+    pub const MTYPE_RW: Self = Self(AMDGPU_VM_MTYPE_RW as u64);
+    /// READABLE | WRITEABLE | EXECUTABLE
+    pub const RWX: Self = Self(
+        (AMDGPU_VM_PAGE_READABLE | AMDGPU_VM_PAGE_WRITEABLE | AMDGPU_VM_PAGE_EXECUTABLE) as u64
+    );
+
+    const MTYPE_MASK: u64 = AMDGPU_VM_MTYPE_MASK as u64;
+    const VALID_MASK: u64 = (
+        AMDGPU_VM_DELAY_UPDATE
+        | AMDGPU_VM_PAGE_READABLE
+        | AMDGPU_VM_PAGE_WRITEABLE
+        | AMDGPU_VM_PAGE_EXECUTABLE
+        | AMDGPU_VM_PAGE_PRT
+        | AMDGPU_VM_PAGE_NOALLOC
+        | AMDGPU_VM_MTYPE_MASK
+    ) as u64;
+}
+
+impl BitOr for VmPageFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for VmPageFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[test]
+fn test_validate_va_op() {
+    const RANGE: (u64, u64) = (0x1_0000_0000, 0x10_0000);
+    let flags = VmPageFlags::RWX | VmPageFlags::MTYPE_UC;
+
+    assert!(validate_va_op(RANGE, Some(0x1000), 0, 0x1_0000_0000, 0x1000, flags).is_ok());
+    assert!(validate_va_op(RANGE, None, 0, 0x1_000F_F000, 0x1000, VmPageFlags::NONE).is_ok());
+    // misaligned
+    assert!(validate_va_op(RANGE, Some(0x2000), 0x10, 0x1_0000_0000, 0x1000, flags).is_err());
+    // out of the VA range
+    assert!(matches!(
+        validate_va_op(RANGE, Some(0x2000), 0, 0x1_000F_F000, 0x2000, flags),
+        Err(Error::VaOutOfRange { addr: 0x1_000F_F000, size: 0x2000, start: 0x1_0000_0000, end: 0x1_0010_0000 }),
+    ));
+    // the end of the VA range overflows
+    assert!(matches!(
+        validate_va_op((0xFFFF_FFFF_FFFF_0000, 0x2_0000), None, 0, 0xFFFF_FFFF_FFFF_0000, 0x1000, flags),
+        Err(Error::VaOutOfRange { end: u64::MAX, .. }),
+    ));
+    // VAs above i64::MAX
+    let e = validate_va_op((0x8000_0000_0000_0000, 0x10_0000), None, 0, 0x1000, 0x1000, flags)
+        .unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "VA 0x1000..0x2000 is out of range (0x8000000000000000..0x8000000000100000)",
+    );
+    // out of the BO
+    assert!(validate_va_op(RANGE, Some(0x1000), 0x1000, 0x1_0000_0000, 0x1000, flags).is_err());
+    // unknown flag
+    assert!(validate_va_op(RANGE, Some(0x1000), 0, 0x1_0000_0000, 0x1000, VmPageFlags(1 << 20)).is_err());
+}
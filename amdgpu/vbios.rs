@@ -8,6 +8,7 @@ use bindings::{AMDGPU_INFO_VBIOS, DRM_AMDGPU_INFO, drm_amdgpu_info};
 
 /// VBIOS information
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VbiosInfo {
     pub name: String,
     pub pn: String,
@@ -17,7 +18,7 @@ pub struct VbiosInfo {
 }
 
 impl DeviceHandle {
-    pub fn get_vbios_info(&self) -> Result<VbiosInfo, i32> {
+    pub fn get_vbios_info(&self) -> Result<VbiosInfo, Error> {
         let vbios = self.vbios_info()?;
         let size = self.vbios_size()?;
 
@@ -41,7 +42,7 @@ impl DeviceHandle {
     unsafe fn query_vbios<T>(
         &self,
         info_id: ::core::ffi::c_uint,
-    ) -> Result<T, i32> { unsafe {
+    ) -> Result<T, Error> { unsafe {
         #[cfg(feature = "link_drm")]
         let func = bindings::drmCommandWrite;
         #[cfg(feature = "dynamic_loading")]
@@ -69,24 +70,24 @@ impl DeviceHandle {
 
         let (_, vbios) = (device_info.assume_init(), vbios.assume_init());
 
-        query_error!(r);
+        query_error!(r, "drmCommandWrite", info_id);
 
         Ok(vbios)
     }}
 
-    pub fn vbios_info(&self) -> Result<bindings::drm_amdgpu_info_vbios, i32> {
+    pub fn vbios_info(&self) -> Result<bindings::drm_amdgpu_info_vbios, Error> {
         use bindings::AMDGPU_INFO_VBIOS_INFO;
 
         unsafe { Self::query_vbios(self, AMDGPU_INFO_VBIOS_INFO) }
     }
 
-    pub fn vbios_size(&self) -> Result<u32, i32> {
+    pub fn vbios_size(&self) -> Result<u32, Error> {
         use bindings::AMDGPU_INFO_VBIOS_SIZE;
 
         unsafe { Self::query_vbios(self, AMDGPU_INFO_VBIOS_SIZE) }
     }
 
-    unsafe fn get_vbios_image_with_size(&self, vbios_size: u32) -> Result<Vec<u8>, i32> { unsafe {
+    unsafe fn get_vbios_image_with_size(&self, vbios_size: u32) -> Result<Vec<u8>, Error> { unsafe {
         use bindings::AMDGPU_INFO_VBIOS_IMAGE;
 
         #[cfg(feature = "link_drm")]
@@ -117,13 +118,13 @@ impl DeviceHandle {
 
         let _ = device_info.assume_init();
 
-        query_error!(r);
+        query_error!(r, "drmCommandWrite", AMDGPU_INFO_VBIOS_IMAGE);
 
         Ok(vbios_image)
     }}
 
 
-    pub fn get_vbios_image(&self) -> Result<Vec<u8>, i32> {
+    pub fn get_vbios_image(&self) -> Result<Vec<u8>, Error> {
         let size = self.vbios_size()?;
 
         unsafe { self.get_vbios_image_with_size(size) }
@@ -4,6 +4,7 @@ use core::mem::{size_of, MaybeUninit};
 pub use bindings::{drm_amdgpu_info_video_caps, drm_amdgpu_info_video_codec_info};
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VideoCapsInfo {
     pub cap_type: CAP_TYPE,
     pub mpeg2: Option<drm_amdgpu_info_video_codec_info>,
@@ -41,7 +42,7 @@ impl From<(&CAP_TYPE, &drm_amdgpu_info_video_caps)> for VideoCapsInfo {
 }
 
 impl DeviceHandle {
-    pub fn get_video_caps_info(&self, cap_type: CAP_TYPE) -> Result<VideoCapsInfo, i32> {
+    pub fn get_video_caps_info(&self, cap_type: CAP_TYPE) -> Result<VideoCapsInfo, Error> {
         let cap = self.get_video_caps(cap_type)?;
 
         Ok(VideoCapsInfo::from((&cap_type, &cap)))
@@ -55,6 +56,7 @@ use bindings::{
 
 /// Used for [DeviceHandle::get_video_caps]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum CAP_TYPE {
     DECODE = AMDGPU_INFO_VIDEO_CAPS_DECODE,
@@ -62,7 +64,7 @@ pub enum CAP_TYPE {
 }
 
 impl DeviceHandle {
-    pub fn get_video_caps(&self, type_: CAP_TYPE) -> Result<drm_amdgpu_info_video_caps, i32> {
+    pub fn get_video_caps(&self, type_: CAP_TYPE) -> Result<drm_amdgpu_info_video_caps, Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::amdgpu_query_video_caps_info;
         #[cfg(feature = "dynamic_loading")]
@@ -80,7 +82,7 @@ impl DeviceHandle {
 
             let video_caps = video_caps.assume_init();
 
-            query_error!(r);
+            query_error!(r, "amdgpu_query_video_caps_info", type_);
 
             Ok(video_caps)
         }
@@ -8382,6 +8382,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_device {
     #[doc = " PCI Device ID"]
     pub device_id: __u32,
@@ -8594,6 +8595,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_hw_ip {
     #[doc = " Version of h/w IP"]
     pub hw_ip_version_major: __u32,
@@ -8696,6 +8698,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_video_codec_info {
     pub valid: __u32,
     pub max_width: __u32,
@@ -8886,6 +8889,7 @@ pub type atombios_image_offset = ::core::ffi::c_uint;
 #[doc = " Common header for all tables (Data table, Command function).\n Every table pointed in _ATOM_MASTER_DATA_TABLE has this common header.\n And the pointer actually points to this header."]
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct atom_common_table_header {
     pub structuresize: u16,
     pub format_revision: u8,
@@ -19992,6 +19996,7 @@ pub const atom_master_command_table_id_VBIOS_CMD_TBL_ID__UNDEFINED: atom_master_
 pub type atom_master_command_table_id = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct metrics_table_header {
     pub structure_size: u16,
     pub format_revision: u8,
@@ -20010,6 +20015,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_0 {
     pub common_header: metrics_table_header,
     pub system_clock_counter: u64,
@@ -20112,6 +20118,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_1 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -20226,6 +20233,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_2 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -20343,6 +20351,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_3 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -20475,6 +20484,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_4 {
     pub common_header: metrics_table_header,
     pub temperature_hotspot: u16,
@@ -20580,6 +20590,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_5 {
     pub common_header: metrics_table_header,
     pub temperature_hotspot: u16,
@@ -20694,6 +20705,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_0 {
     pub common_header: metrics_table_header,
     pub system_clock_counter: u64,
@@ -20793,6 +20805,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_1 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -20892,6 +20905,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_2 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -20994,6 +21008,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_3 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -21108,6 +21123,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_4 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -21240,6 +21256,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v3_0 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -5049,6 +5049,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_device {
     #[doc = " PCI Device ID"]
     pub device_id: __u32,
@@ -5261,6 +5262,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_hw_ip {
     #[doc = " Version of h/w IP"]
     pub hw_ip_version_major: __u32,
@@ -5363,6 +5365,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct drm_amdgpu_info_video_codec_info {
     pub valid: __u32,
     pub max_width: __u32,
@@ -5553,6 +5556,7 @@ pub type atombios_image_offset = ::core::ffi::c_uint;
 #[doc = " Common header for all tables (Data table, Command function).\n Every table pointed in _ATOM_MASTER_DATA_TABLE has this common header.\n And the pointer actually points to this header."]
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct atom_common_table_header {
     pub structuresize: u16,
     pub format_revision: u8,
@@ -16659,6 +16663,7 @@ pub const atom_master_command_table_id_VBIOS_CMD_TBL_ID__UNDEFINED: atom_master_
 pub type atom_master_command_table_id = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct metrics_table_header {
     pub structure_size: u16,
     pub format_revision: u8,
@@ -16677,6 +16682,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_0 {
     pub common_header: metrics_table_header,
     pub system_clock_counter: u64,
@@ -16779,6 +16785,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_1 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -16893,6 +16900,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_2 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -17010,6 +17018,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_3 {
     pub common_header: metrics_table_header,
     pub temperature_edge: u16,
@@ -17142,6 +17151,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_4 {
     pub common_header: metrics_table_header,
     pub temperature_hotspot: u16,
@@ -17247,6 +17257,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v1_5 {
     pub common_header: metrics_table_header,
     pub temperature_hotspot: u16,
@@ -17361,6 +17372,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_0 {
     pub common_header: metrics_table_header,
     pub system_clock_counter: u64,
@@ -17460,6 +17472,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_1 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -17559,6 +17572,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_2 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -17661,6 +17675,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_3 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -17775,6 +17790,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v2_4 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
@@ -17907,6 +17923,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct gpu_metrics_v3_0 {
     pub common_header: metrics_table_header,
     pub temperature_gfx: u16,
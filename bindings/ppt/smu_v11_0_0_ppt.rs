@@ -678,6 +678,7 @@ pub type atombios_image_offset = ::core::ffi::c_uint;
 #[doc = " Common header for all tables (Data table, Command function).\n Every table pointed in _ATOM_MASTER_DATA_TABLE has this common header.\n And the pointer actually points to this header."]
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct atom_common_table_header {
     pub structuresize: u16,
     pub format_revision: u8,
@@ -11816,6 +11817,7 @@ pub const I2cControllerProtocol_e_I2C_CONTROLLER_PROTOCOL_COUNT: I2cControllerPr
 pub type I2cControllerProtocol_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct I2cControllerConfig_t {
     pub Enabled: u8,
     pub Speed: u8,
@@ -11927,6 +11929,7 @@ pub const PowerGatingSettings_e_PG_POWER_UP: PowerGatingSettings_e = 1;
 pub type PowerGatingSettings_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct QuadraticInt_t {
     pub a: u32,
     pub b: u32,
@@ -11942,6 +11945,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LinearInt_t {
     pub m: u32,
     pub b: u32,
@@ -11955,6 +11959,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DroopInt_t {
     pub a: u32,
     pub b: u32,
@@ -12020,6 +12025,7 @@ pub const PwrConfig_e_PWR_CONFIG_TCP_MEASURED: PwrConfig_e = 3;
 pub type PwrConfig_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DpmDescriptor_t {
     pub VoltageMode: u8,
     pub SnapToDiscrete: u8,
@@ -12137,6 +12143,7 @@ const _: () = {
 };
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PPTable_t {
     pub Version: u32,
     pub FeaturesToRun: [u32; 2usize],
@@ -13383,6 +13390,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AvfsFuseOverride_t {
     pub AvfsVersion: u8,
     pub Padding: u8,
@@ -13729,6 +13737,7 @@ pub const SMU_11_0_ODSETTING_ID_SMU_11_0_ODSETTING_COUNT: SMU_11_0_ODSETTING_ID
 pub type SMU_11_0_ODSETTING_ID = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_overdrive_table {
     pub revision: u8,
     pub reserve: [u8; 3usize],
@@ -13773,6 +13782,7 @@ pub const SMU_11_0_PPCLOCK_ID_SMU_11_0_PPCLOCK_COUNT: SMU_11_0_PPCLOCK_ID = 10;
 pub type SMU_11_0_PPCLOCK_ID = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_power_saving_clock_table {
     pub revision: u8,
     pub reserve: [u8; 3usize],
@@ -13799,6 +13809,7 @@ const _: () = {
 };
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_powerplay_table {
     pub header: atom_common_table_header,
     pub table_revision: u8,
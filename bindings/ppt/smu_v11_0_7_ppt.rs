@@ -701,6 +701,7 @@ pub type atombios_image_offset = ::core::ffi::c_uint;
 #[doc = " Common header for all tables (Data table, Command function).\n Every table pointed in _ATOM_MASTER_DATA_TABLE has this common header.\n And the pointer actually points to this header."]
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct atom_common_table_header {
     pub structuresize: u16,
     pub format_revision: u8,
@@ -11889,6 +11890,7 @@ pub const I2cControllerProtocol_e_I2C_CONTROLLER_PROTOCOL_COUNT: I2cControllerPr
 pub type I2cControllerProtocol_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct I2cControllerConfig_t {
     pub Enabled: u8,
     pub Speed: u8,
@@ -12011,6 +12013,7 @@ pub const PowerGatingSettings_e_PG_POWER_UP: PowerGatingSettings_e = 1;
 pub type PowerGatingSettings_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct QuadraticInt_t {
     pub a: u32,
     pub b: u32,
@@ -12045,6 +12048,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LinearInt_t {
     pub m: u32,
     pub b: u32,
@@ -12058,6 +12062,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DroopInt_t {
     pub a: u32,
     pub b: u32,
@@ -12078,6 +12083,7 @@ pub const DfllDroopModelSelect_e_PERPART_PIECEWISE_LINEAR_PP_MODEL: DfllDroopMod
 pub type DfllDroopModelSelect_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PiecewiseLinearDroopInt_t {
     pub Fset: [u32; 5usize],
     pub Vdroop: [u32; 5usize],
@@ -12160,6 +12166,7 @@ pub const XGMI_LINK_WIDTH_e_XGMI_LINK_WIDTH_COUNT: XGMI_LINK_WIDTH_e = 6;
 pub type XGMI_LINK_WIDTH_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DpmDescriptor_t {
     pub VoltageMode: u8,
     pub SnapToDiscrete: u8,
@@ -12219,6 +12226,7 @@ pub const CUSTOMER_VARIANT_e_CUSTOMER_VARIANT_COUNT: CUSTOMER_VARIANT_e = 2;
 pub type CUSTOMER_VARIANT_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct UclkDpmChangeRange_t {
     pub Fmin: u16,
     pub Fmax: u16,
@@ -12234,6 +12242,7 @@ const _: () = {
 };
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PPTable_t {
     pub Version: u32,
     pub FeaturesToRun: [u32; 2usize],
@@ -14411,6 +14420,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AvfsFuseOverride_t {
     pub AvfsVersion: u8,
     pub Padding: u8,
@@ -14869,13 +14879,16 @@ pub const SMU_11_0_7_PWRMODE_SETTING_SMU_11_0_7_PMSETTING_ACOUSTIC_TEMP_RAGE:
 pub type SMU_11_0_7_PWRMODE_SETTING = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_7_overdrive_table {
     pub revision: u8,
     pub reserve: [u8; 3usize],
     pub feature_count: u32,
     pub setting_count: u32,
     pub cap: [u8; 32usize],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub max: [u32; 64usize],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub min: [u32; 64usize],
     pub pm_setting: [i16; 32usize],
 }
@@ -14919,6 +14932,7 @@ pub const SMU_11_0_7_PPCLOCK_ID_SMU_11_0_7_PPCLOCK_COUNT: SMU_11_0_7_PPCLOCK_ID
 pub type SMU_11_0_7_PPCLOCK_ID = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_7_power_saving_clock_table {
     pub revision: u8,
     pub reserve: [u8; 3usize],
@@ -14945,6 +14959,7 @@ const _: () = {
 };
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_11_0_7_powerplay_table {
     pub header: atom_common_table_header,
     pub table_revision: u8,
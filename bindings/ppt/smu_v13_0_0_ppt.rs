@@ -692,6 +692,7 @@ pub type atombios_image_offset = ::core::ffi::c_uint;
 #[doc = " Common header for all tables (Data table, Command function).\n Every table pointed in _ATOM_MASTER_DATA_TABLE has this common header.\n And the pointer actually points to this header."]
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct atom_common_table_header {
     pub structuresize: u16,
     pub format_revision: u8,
@@ -11865,6 +11866,7 @@ pub const I2cControllerProtocol_e_I2C_CONTROLLER_PROTOCOL_COUNT: I2cControllerPr
 pub type I2cControllerProtocol_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct I2cControllerConfig_t {
     pub Enabled: u8,
     pub Speed: u8,
@@ -12020,6 +12022,7 @@ pub const PowerGatingSettings_e_PG_POWER_UP: PowerGatingSettings_e = 1;
 pub type PowerGatingSettings_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct QuadraticInt_t {
     pub a: u32,
     pub b: u32,
@@ -12035,6 +12038,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LinearInt_t {
     pub m: u32,
     pub b: u32,
@@ -12048,6 +12052,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DroopInt_t {
     pub a: u32,
     pub b: u32,
@@ -12112,6 +12117,7 @@ pub const PwrConfig_e_PWR_CONFIG_TCP_MEASURED: PwrConfig_e = 3;
 pub type PwrConfig_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DpmDescriptor_t {
     pub Padding: u8,
     pub SnapToDiscrete: u8,
@@ -12317,6 +12323,7 @@ pub const PP_GRTAVFS_FW_SEP_FUSE_e_PP_GRTAVFS_FW_SEP_FUSE_COUNT: PP_GRTAVFS_FW_S
 pub type PP_GRTAVFS_FW_SEP_FUSE_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SviTelemetryScale_t {
     pub Offset: i8,
     pub Padding: u8,
@@ -12424,6 +12431,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OverDriveLimits_t {
     pub FeatureCtrlMask: u32,
     pub VoltageOffsetPerZoneBoundary: i16,
@@ -12541,6 +12549,7 @@ pub const BOARD_GPIO_TYPE_e_BOARD_GPIO_DC_SWAPLOCK_B: BOARD_GPIO_TYPE_e = 43;
 pub type BOARD_GPIO_TYPE_e = ::core::ffi::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BootValues_t {
     pub InitGfxclk_bypass: u16,
     pub InitSocclk: u16,
@@ -12652,6 +12661,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MsgLimits_t {
     pub Power: [[u16; 2usize]; 4usize],
     pub Tdc: [u16; 3usize],
@@ -12715,6 +12725,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DriverReportedClocks_t {
     pub BaseClockAc: u16,
     pub GameClockAc: u16,
@@ -12746,6 +12757,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AvfsDcBtcParams_t {
     pub DcBtcEnabled: u8,
     pub Padding: [u8; 3usize],
@@ -12776,6 +12788,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AvfsFuseOverride_t {
     pub AvfsTemp: [u16; 2usize],
     pub VftFMin: u16,
@@ -12803,6 +12816,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SkuTable_t {
     pub Version: u32,
     pub FeaturesToRun: [u32; 2usize],
@@ -13006,6 +13020,7 @@ pub struct SkuTable_t {
     pub TemperatureLimit_Micron: u16,
     pub TemperatureFwCtfLimit_Hynix: u16,
     pub TemperatureFwCtfLimit_Micron: u16,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub Spare: [u32; 41usize],
     pub MmHubPadding: [u32; 8usize],
 }
@@ -13418,6 +13433,7 @@ const _: () = {
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BoardTable_t {
     pub Version: u32,
     pub I2cControllers: [I2cControllerConfig_t; 8usize],
@@ -13467,6 +13483,7 @@ pub struct BoardTable_t {
     pub BacoEntryDelay: u32,
     pub FuseWritePowerMuxPresent: u8,
     pub FuseWritePadding: [u8; 3usize],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub BoardSpare: [u32; 63usize],
     pub MmHubPadding: [u32; 8usize],
 }
@@ -13577,6 +13594,7 @@ const _: () = {
 };
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PPTable_t {
     pub SkuTable: SkuTable_t,
     pub BoardTable: BoardTable_t,
@@ -14227,13 +14245,16 @@ pub const SMU_13_0_0_PWRMODE_SETTING_SMU_13_0_0_PMSETTING_ACOUSTIC_LIMIT_RPM_RAG
 pub type SMU_13_0_0_PWRMODE_SETTING = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_13_0_0_overdrive_table {
     pub revision: u8,
     pub reserve: [u8; 3usize],
     pub feature_count: u32,
     pub setting_count: u32,
     pub cap: [u8; 32usize],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub max: [u32; 64usize],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub min: [u32; 64usize],
     pub pm_setting: [i16; 32usize],
 }
@@ -14277,6 +14298,7 @@ pub const SMU_13_0_0_PPCLOCK_ID_SMU_13_0_0_PPCLOCK_COUNT: SMU_13_0_0_PPCLOCK_ID
 pub type SMU_13_0_0_PPCLOCK_ID = ::core::ffi::c_uint;
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct smu_13_0_0_powerplay_table {
     pub header: atom_common_table_header,
     pub table_revision: u8,
@@ -14291,6 +14313,7 @@ pub struct smu_13_0_0_powerplay_table {
     pub small_power_limit2: u16,
     pub boost_power_limit: u16,
     pub software_shutdown_temp: u16,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]
     pub reserve: [u32; 45usize],
     pub overdrive_table: smu_13_0_0_overdrive_table,
     pub padding1: u8,
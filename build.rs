@@ -29,15 +29,14 @@ fn build() {
             .clang_args(config.iter())
             .use_core()
             .ctypes_prefix("::core::ffi")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             // .parse_callbacks(Box::new(bindgen::CargoCallbacks))
             // Finish the builder and generate the bindings.
             .generate()
             // Unwrap the Result and panic on failure.
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        bindings
-            .write_to_file(out_path.join("drm.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&bindings, out_path.join("drm.rs"));
     }
 
     {
@@ -60,9 +59,7 @@ fn build() {
             // Unwrap the Result and panic on failure.
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        bindings
-            .write_to_file(out_path.join("dyn_drm.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&bindings, out_path.join("dyn_drm.rs"));
     }
     {
         let bindings = bindgen::Builder::default()
@@ -91,15 +88,14 @@ fn build() {
             .blocklist_function("amdgpu_va_get_start_addr")
             .blocklist_function("amdgpu_query_gpuvm_fault_info")
             .blocklist_function("amdgpu_get_marketing_name")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             // .parse_callbacks(Box::new(bindgen::CargoCallbacks))
             // Finish the builder and generate the bindings.
             .generate()
             // Unwrap the Result and panic on failure.
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        bindings
-            .write_to_file(out_path.join("dyn_drm_amdgpu.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&bindings, out_path.join("dyn_drm_amdgpu.rs"));
     }
 
     {
@@ -109,12 +105,11 @@ fn build() {
             .header("wrapper/smu_v11_0_pptable.h")
             .use_core()
             .ctypes_prefix("::core::ffi")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             .generate()
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        smu_v11_0_0_ppt
-            .write_to_file(out_path.join("ppt").join("smu_v11_0_0_ppt.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&smu_v11_0_0_ppt, out_path.join("ppt").join("smu_v11_0_0_ppt.rs"));
     }
 
     {
@@ -124,12 +119,11 @@ fn build() {
             .header("wrapper/smu_v11_0_7_pptable.h")
             .use_core()
             .ctypes_prefix("::core::ffi")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             .generate()
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        smu_v11_0_7_ppt
-            .write_to_file(out_path.join("ppt").join("smu_v11_0_7_ppt.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&smu_v11_0_7_ppt, out_path.join("ppt").join("smu_v11_0_7_ppt.rs"));
     }
 
     {
@@ -139,12 +133,11 @@ fn build() {
             .header("wrapper/smu_v13_0_0_pptable.h")
             .use_core()
             .ctypes_prefix("::core::ffi")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             .generate()
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        smu_v13_0_0_ppt
-            .write_to_file(out_path.join("ppt").join("smu_v13_0_0_ppt.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&smu_v13_0_0_ppt, out_path.join("ppt").join("smu_v13_0_0_ppt.rs"));
     }
 
     {
@@ -154,17 +147,111 @@ fn build() {
             .header("wrapper/smu_v13_0_7_pptable.h")
             .use_core()
             .ctypes_prefix("::core::ffi")
+            .parse_callbacks(Box::new(SerdeCallbacks))
             .generate()
             .expect("Unable to generate bindings: {wrapper_name}");
 
-        smu_v13_0_7_ppt
-            .write_to_file(out_path.join("ppt").join("smu_v13_0_7_ppt.rs"))
-            .expect("Couldn't write bindings!");
+        write_bindings(&smu_v13_0_7_ppt, out_path.join("ppt").join("smu_v13_0_7_ppt.rs"));
     }
 
     convert_amdgpu_ids();
 }
 
+/// Types which derive `Serialize` with the `serde` feature
+#[cfg(feature = "buildtime_bindgen")]
+const SERDE_TYPES: &[&str] = &[
+    "metrics_table_header",
+    "drm_amdgpu_info_device",
+    "drm_amdgpu_info_hw_ip",
+    "drm_amdgpu_info_video_codec_info",
+    "atom_common_table_header",
+    "smu_11_0_powerplay_table",
+    "smu_11_0_overdrive_table",
+    "smu_11_0_power_saving_clock_table",
+    "smu_11_0_7_powerplay_table",
+    "smu_11_0_7_overdrive_table",
+    "smu_11_0_7_power_saving_clock_table",
+    "smu_13_0_0_powerplay_table",
+    "smu_13_0_0_overdrive_table",
+    "smu_13_0_7_powerplay_table",
+    "smu_13_0_7_overdrive_table",
+    "PPTable_t",
+    "BoardTable_t",
+    "SkuTable_t",
+    "SviTelemetryScale_t",
+    "I2cControllerConfig_t",
+    "QuadraticInt_t",
+    "PiecewiseLinearDroopInt_t",
+    "LinearInt_t",
+    "DroopInt_t",
+    "UclkDpmChangeRange_t",
+    "OverDriveLimits_t",
+    "MsgLimits_t",
+    "DriverReportedClocks_t",
+    "BootValues_t",
+    "AvfsDcBtcParams_t",
+    "AvfsFuseOverride_t",
+    "DpmDescriptor_t",
+];
+
+#[cfg(feature = "buildtime_bindgen")]
+const SERDE_DERIVE: &str = r#"#[cfg_attr(feature = "serde", derive(serde::Serialize))]"#;
+
+/* serde implements `Serialize` only for arrays up to 32 elements */
+#[cfg(feature = "buildtime_bindgen")]
+const SERDE_ARRAY: &str =
+    r#"#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_bindings::serialize_array"))]"#;
+
+/// Add `#[cfg_attr(feature = "serde", derive(serde::Serialize))]` to [SERDE_TYPES] and `gpu_metrics_v*`,
+/// the bindings are the same with or without the `serde` feature.
+#[cfg(feature = "buildtime_bindgen")]
+#[derive(Debug)]
+struct SerdeCallbacks;
+
+#[cfg(feature = "buildtime_bindgen")]
+impl bindgen::callbacks::ParseCallbacks for SerdeCallbacks {
+    fn add_attributes(&self, info: &bindgen::callbacks::AttributeInfo<'_>) -> Vec<String> {
+        let serde = matches!(info.kind, bindgen::callbacks::TypeKind::Struct)
+            && (info.name.starts_with("gpu_metrics_v") || SERDE_TYPES.contains(&info.name));
+
+        if serde { vec![SERDE_DERIVE.to_string()] } else { Vec::new() }
+    }
+}
+
+/// Write the bindings, with [SERDE_ARRAY] for the array fields larger than 32 elements
+/// of the types that derive `Serialize`.
+/// bindgen does not have a callback for the field attributes.
+#[cfg(feature = "buildtime_bindgen")]
+fn write_bindings(bindings: &bindgen::Bindings, path: std::path::PathBuf) {
+    let mut s = String::new();
+    let mut serde = false;
+
+    for line in bindings.to_string().lines() {
+        if line == SERDE_DERIVE {
+            serde = true;
+        } else if line == "}" {
+            serde = false;
+        } else if serde {
+            let len = line
+                .trim_end_matches("usize],")
+                .rsplit_once("; ")
+                .filter(|(field, _)| field.trim_start().starts_with("pub ") && field.contains(": ["))
+                .and_then(|(_, len)| len.parse::<usize>().ok());
+
+            if len.is_some_and(|len| len > 32) {
+                s.push_str("    ");
+                s.push_str(SERDE_ARRAY);
+                s.push('\n');
+            }
+        }
+
+        s.push_str(line);
+        s.push('\n');
+    }
+
+    std::fs::write(path, s).expect("Couldn't write bindings!");
+}
+
 #[cfg(feature = "buildtime_bindgen")]
 fn convert_amdgpu_ids() {
     use std::fmt::Write;
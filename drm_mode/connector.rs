@@ -1,4 +1,4 @@
-use crate::{bindings, query_error, LibDrm};
+use crate::{bindings, query_error, Error, LibDrm};
 use crate::drmModeObjectProperties;
 use core::ptr::addr_of;
 pub use bindings::{drmModeConnectorPtr, drmModeModeInfo};
@@ -48,7 +48,7 @@ impl LibDrm {
         connector_id: u32,
         property_id: u32,
         value: u64,
-    ) -> Result<(), i32> {
+    ) -> Result<(), Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::drmModeConnectorSetProperty;
         #[cfg(feature = "dynamic_loading")]
@@ -56,7 +56,7 @@ impl LibDrm {
 
         let r = unsafe { func(fd, connector_id, property_id, value) };
 
-        query_error!(r);
+        query_error!(r, "drmModeConnectorSetProperty");
 
         Ok(())
     }
@@ -97,12 +97,12 @@ impl drmModeConnector {
         connector_id: u32,
         property_id: u32,
         value: u64,
-    ) -> Result<(), i32> {
+    ) -> Result<(), Error> {
         let r = unsafe {
             bindings::drmModeConnectorSetProperty(fd, connector_id, property_id, value)
         };
 
-        query_error!(r);
+        query_error!(r, "drmModeConnectorSetProperty");
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use crate::{bindings, query_error, LibDrm};
+use crate::{bindings, query_error, Error, LibDrm};
 use core::ptr::addr_of;
 
 pub use bindings::drmModeObjectPropertiesPtr;
@@ -41,7 +41,7 @@ impl LibDrm {
         object_type: u32,
         property_id: u32,
         value: u64,
-    ) -> Result<(), i32> {
+    ) -> Result<(), Error> {
         #[cfg(feature = "link_drm")]
         let func = bindings::drmModeObjectSetProperty;
         #[cfg(feature = "dynamic_loading")]
@@ -49,7 +49,7 @@ impl LibDrm {
 
         let r = unsafe { func(fd, object_id, object_type, property_id, value) };
 
-        query_error!(r);
+        query_error!(r, "drmModeObjectSetProperty");
 
         Ok(())
     }
@@ -79,7 +79,7 @@ impl drmModeObjectProperties {
         object_type: u32,
         property_id: u32,
         value: u64,
-    ) -> Result<(), i32> {
+    ) -> Result<(), Error> {
         let r = unsafe { bindings::drmModeObjectSetProperty(
             fd,
             object_id,
@@ -88,7 +88,7 @@ impl drmModeObjectProperties {
             value,
         ) };
 
-        query_error!(r);
+        query_error!(r, "drmModeObjectSetProperty");
 
         Ok(())
     }
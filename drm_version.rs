@@ -1,6 +1,7 @@
 #[cfg(feature = "link_drm")]
 use crate::bindings;
 use crate::AMDGPU::DeviceHandle;
+use crate::Error;
 use core::ffi::CStr;
 
 #[derive(Debug, Clone)]
@@ -14,7 +15,7 @@ pub struct drmVersion {
 }
 
 impl DeviceHandle {
-    pub fn get_drm_version_struct(&self) -> Result<drmVersion, i32> {
+    pub fn get_drm_version_struct(&self) -> Result<drmVersion, Error> {
         #[cfg(feature = "link_drm")]
         let (get_func, free_func) = (bindings::drmGetVersion, bindings::drmFreeVersion);
         #[cfg(feature = "dynamic_loading")]
@@ -23,7 +24,7 @@ impl DeviceHandle {
         let drm_ver_ptr = unsafe { get_func(self.fd) };
 
         if drm_ver_ptr.is_null() {
-            return Err(-libc::EFAULT);
+            return Err(Error::Query { func: "drmGetVersion", query: None, errno: -libc::EFAULT });
         }
 
         let ver = unsafe { core::ptr::read(drm_ver_ptr) };
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Error type used by this crate
+#[derive(Debug)]
+pub enum Error {
+    /// A libdrm function (ioctl) returned an error.
+    /// `query` is the query/info ID passed to `func` (e.g. `AMDGPU_INFO_DEV_INFO`), if any.
+    /// `errno` is the (negative) value returned by libdrm.
+    Query {
+        func: &'static str,
+        query: Option<u32>,
+        errno: i32,
+    },
+    /// Failed to read/write a sysfs (or debugfs) file
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// Failed to parse a sysfs (or debugfs) file
+    Parse {
+        path: PathBuf,
+        text: String,
+    },
+    /// The kernel driver (or the GPU) does not support it, the sysfs file does not exist.
+    /// `source` is the underlying I/O error, if any.
+    Unsupported {
+        path: PathBuf,
+        source: Option<io::Error>,
+    },
+    /// Writing to sysfs requires root (or the file is read-only)
+    PermissionDenied {
+        path: PathBuf,
+        source: Option<io::Error>,
+    },
+    /// The value is out of the range reported by the kernel driver
+    OutOfRange {
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    /// The GPU virtual address range `addr..addr+size` is out of the VA range `start..end`
+    /// (e.g. [crate::AMDGPU::VaRange])
+    VaOutOfRange {
+        addr: u64,
+        size: u64,
+        start: u64,
+        end: u64,
+    },
+    /// The argument was rejected before calling libdrm (e.g. misaligned address)
+    InvalidArgument(&'static str),
+    /// Failed to spawn a background thread (e.g. [crate::AMDGPU::MetricsSampler])
+    SpawnThread {
+        name: String,
+        source: io::Error,
+    },
+    /// Failed to load `libdrm.so` or `libdrm_amdgpu.so`
+    #[cfg(feature = "dynamic_loading")]
+    DynamicLoading(::libloading::Error),
+}
+
+impl Error {
+    pub(crate) fn io<P: Into<PathBuf>>(path: P, source: io::Error) -> Self {
+        let path = path.into();
+
+        match source.kind() {
+            io::ErrorKind::NotFound => Self::Unsupported { path, source: Some(source) },
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied { path, source: Some(source) },
+            _ if source.raw_os_error() == Some(libc::EOPNOTSUPP) =>
+                Self::Unsupported { path, source: Some(source) },
+            _ => Self::Io { path, source },
+        }
+    }
+
+    pub(crate) fn parse<P: Into<PathBuf>, S: Into<String>>(path: P, text: S) -> Self {
+        Self::Parse { path: path.into(), text: text.into() }
+    }
+
+    /// Returns the (negative) `errno` returned from libdrm,
+    /// or the negated OS error code of the underlying I/O error
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            Self::Query { errno, .. } => Some(*errno),
+            Self::Io { source, .. } |
+            Self::SpawnThread { source, .. } => source.raw_os_error().map(|v| -v),
+            Self::Unsupported { source, .. } |
+            Self::PermissionDenied { source, .. } =>
+                source.as_ref()?.raw_os_error().map(|v| -v),
+            _ => None,
+        }
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, Self::Unsupported { .. })
+    }
+
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, Self::PermissionDenied { .. })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Query { func, query, errno } => {
+                write!(f, "{func}")?;
+
+                if let Some(query) = query {
+                    write!(f, " (query: {query:#X})")?;
+                }
+
+                write!(f, " failed: {errno} ({})", io::Error::from_raw_os_error(-errno))
+            },
+            Self::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Parse { path, text } => write!(f, "{}: failed to parse {text:?}", path.display()),
+            Self::Unsupported { path, .. } => write!(f, "{}: not supported", path.display()),
+            Self::PermissionDenied { path, .. } => write!(f, "{}: permission denied", path.display()),
+            Self::OutOfRange { value, min, max } =>
+                write!(f, "{value} is out of range ({min}..={max})"),
+            Self::VaOutOfRange { addr, size, start, end } => write!(
+                f,
+                "VA {addr:#X}..{:#X} is out of range ({start:#X}..{end:#X})",
+                addr.saturating_add(*size),
+            ),
+            Self::InvalidArgument(reason) => write!(f, "invalid argument: {reason}"),
+            Self::SpawnThread { name, source } => write!(f, "failed to spawn thread {name:?}: {source}"),
+            #[cfg(feature = "dynamic_loading")]
+            Self::DynamicLoading(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } |
+            Self::SpawnThread { source, .. } => Some(source),
+            Self::Unsupported { source: Some(source), .. } |
+            Self::PermissionDenied { source: Some(source), .. } => Some(source),
+            #[cfg(feature = "dynamic_loading")]
+            Self::DynamicLoading(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic_loading")]
+impl From<::libloading::Error> for Error {
+    fn from(e: ::libloading::Error) -> Self {
+        Self::DynamicLoading(e)
+    }
+}
+
+pub(crate) fn read_sysfs<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let path = path.as_ref();
+
+    std::fs::read_to_string(path).map_err(|e| Error::io(path, e))
+}
+
+pub(crate) fn write_sysfs<P: AsRef<Path>>(path: P, s: &str) -> Result<(), Error> {
+    let path = path.as_ref();
+
+    std::fs::write(path, s).map_err(|e| Error::io(path, e))
+}
+
+#[test]
+fn test_error_display() {
+    let e = Error::Query { func: "amdgpu_query_info", query: Some(0x16), errno: -libc::EINVAL };
+
+    assert!(e.to_string().starts_with("amdgpu_query_info (query: 0x16) failed: -22 ("));
+    assert_eq!(e.errno(), Some(-libc::EINVAL));
+
+    let e = Error::io("/sys/foo", io::Error::from(io::ErrorKind::NotFound));
+
+    assert!(e.is_unsupported());
+
+    let e = Error::io("/sys/foo", io::Error::from_raw_os_error(libc::EACCES));
+
+    assert!(e.is_permission_denied());
+    assert_eq!(e.errno(), Some(-libc::EACCES));
+    assert!(std::error::Error::source(&e).is_some());
+
+    let e = Error::SpawnThread {
+        name: "metrics_sampler".to_string(),
+        source: io::Error::from_raw_os_error(libc::EAGAIN),
+    };
+
+    assert!(e.to_string().starts_with("failed to spawn thread \"metrics_sampler\": "));
+    assert_eq!(e.errno(), Some(-libc::EAGAIN));
+}
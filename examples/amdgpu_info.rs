@@ -251,7 +251,7 @@ fn info(libdrm_amdgpu: &LibDrmAmdgpu, pci_bus: &PCI::BUS_INFO) {
         }
     }
 
-    match amdgpu_dev.get_min_max_link_info_from_dpm() { Some([min, max]) => {
+    match amdgpu_dev.get_min_max_link_info_from_dpm() { Ok([min, max]) => {
         println!(
             "PCIe Link Speed     (DPM)    : Gen{}x{} - Gen{}x{}",
             min.r#gen,
@@ -260,7 +260,7 @@ fn info(libdrm_amdgpu: &LibDrmAmdgpu, pci_bus: &PCI::BUS_INFO) {
             max.width,
         );
 
-        if let Some(max_gpu_link) = amdgpu_dev.get_max_gpu_link() {
+        if let Ok(max_gpu_link) = amdgpu_dev.get_max_gpu_link() {
             println!(
                 "PCIe Link Speed (GPU, Max)   : Gen{}x{}",
                 max_gpu_link.r#gen,
@@ -268,7 +268,7 @@ fn info(libdrm_amdgpu: &LibDrmAmdgpu, pci_bus: &PCI::BUS_INFO) {
             );
         }
 
-        if let Some(max_system_link) = amdgpu_dev.get_max_system_link() {
+        if let Ok(max_system_link) = amdgpu_dev.get_max_system_link() {
             println!(
                 "PCIe Link Speed (System, Max): Gen{}x{}",
                 max_system_link.r#gen,
@@ -334,7 +334,7 @@ fn info(libdrm_amdgpu: &LibDrmAmdgpu, pci_bus: &PCI::BUS_INFO) {
 
         println!("Supported Power Profiles: {profiles:?}");
 
-        if let Some(profiles) = PowerProfile::get_current_profile_from_sysfs(&sysfs) {
+        if let Ok(profiles) = PowerProfile::get_current_profile_from_sysfs(&sysfs) {
             println!("Current Power Profiles: {profiles}");
         }
 
@@ -353,21 +353,21 @@ fn info(libdrm_amdgpu: &LibDrmAmdgpu, pci_bus: &PCI::BUS_INFO) {
         }
     }
 
-    if let Some(hwmon) = amdgpu_dev.get_hwmon_path() {
+    if let Ok(hwmon) = amdgpu_dev.get_hwmon_path() {
         println!("hwmon: {hwmon:?}");
 
         use AMDGPU::{HwmonTemp, HwmonTempType, PowerCap};
-        if let Some(power_cap) = PowerCap::from_hwmon_path(&hwmon) {
+        if let Ok(power_cap) = PowerCap::from_hwmon_path(&hwmon) {
             let PowerCap { type_, current, default, min, max } = power_cap;
             println!("PowerCap ({type_}): {current} W (Current), {default} W (Default), {min}-{max} W (Range)");
         }
-        if let Some(edge_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Edge) {
+        if let Ok(edge_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Edge) {
             println!("{edge_temp:?}");
         }
-        if let Some(junction_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Junction) {
+        if let Ok(junction_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Junction) {
             println!("{junction_temp:?}");
         }
-        if let Some(mem_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Memory) {
+        if let Ok(mem_temp) = HwmonTemp::from_hwmon_path(&hwmon, HwmonTempType::Memory) {
             println!("{mem_temp:?}");
         }
     }
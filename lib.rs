@@ -23,6 +23,15 @@ mod bindings {
     mod dyn_drm_amdgpu;
     #[cfg(feature = "dynamic_loading")]
     pub use dyn_drm_amdgpu::*;
+    /* defined in both dyn_drm and dyn_drm_amdgpu */
+    #[cfg(feature = "dynamic_loading")]
+    pub use dyn_drm_amdgpu::{
+        DRM_SYNCOBJ_CREATE_SIGNALED,
+        DRM_SYNCOBJ_WAIT_FLAGS_WAIT_ALL,
+        DRM_SYNCOBJ_WAIT_FLAGS_WAIT_FOR_SUBMIT,
+        DRM_SYNCOBJ_WAIT_FLAGS_WAIT_AVAILABLE,
+        DRM_SYNCOBJ_QUERY_FLAGS_LAST_SUBMITTED,
+    };
 
     mod amdgpu_ids;
     pub use amdgpu_ids::AMDGPU_IDS;
@@ -35,6 +44,15 @@ mod bindings {
     }
 }
 
+mod error;
+pub use error::*;
+
+#[cfg(test)]
+mod test_util;
+
+#[cfg(all(feature = "serde", not(feature = "buildtime_bindgen")))]
+mod serde_bindings;
+
 #[cfg(feature = "dynamic_loading")]
 use std::sync::Arc;
 #[cfg(feature = "dynamic_loading")]
@@ -53,14 +71,14 @@ pub struct LibDrm {
 
 #[cfg(feature = "link_drm")]
 impl LibDrm {
-    pub fn new() -> Result<Self, ()> {
+    pub fn new() -> Result<Self, Error> {
         Ok(Self {})
     }
 }
 
 #[cfg(feature = "dynamic_loading")]
 impl LibDrm {
-    pub fn new() -> Result<Self, ::libloading::Error> {
+    pub fn new() -> Result<Self, Error> {
         let libdrm = unsafe { Arc::new(DynLibDrm::new(LIBDRM_NAME)?) };
 
         Ok(Self { libdrm })
@@ -86,25 +104,25 @@ pub struct LibDrmAmdgpu {
 
 #[cfg(feature = "link_drm")]
 impl LibDrmAmdgpu {
-    pub fn new() -> Result<Self, ()> {
+    pub fn new() -> Result<Self, Error> {
         Ok(Self {})
     }
 
-    pub fn new_with_libdrm(_lib: LibDrm) -> Result<Self, ()> {
+    pub fn new_with_libdrm(_lib: LibDrm) -> Result<Self, Error> {
         Ok(Self {})
     }
 }
 
 #[cfg(feature = "dynamic_loading")]
 impl LibDrmAmdgpu {
-    pub fn new() -> Result<Self, ::libloading::Error> {
+    pub fn new() -> Result<Self, Error> {
         let libdrm = unsafe { Arc::new(DynLibDrm::new(LIBDRM_NAME)?) };
         let libdrm_amdgpu = unsafe { Arc::new(DynLibDrmAmdgpu::new(LIBDRM_AMDGPU_NAME)?) };
 
         Ok(Self { libdrm, libdrm_amdgpu })
     }
 
-    pub fn new_with_libdrm(lib: LibDrm) -> Result<Self, ::libloading::Error> {
+    pub fn new_with_libdrm(lib: LibDrm) -> Result<Self, Error> {
         let libdrm_amdgpu = unsafe { Arc::new(DynLibDrmAmdgpu::new(LIBDRM_AMDGPU_NAME)?) };
 
         Ok(Self { libdrm: lib.libdrm.clone(), libdrm_amdgpu })
@@ -130,17 +148,28 @@ mod drm_version;
 #[cfg(not(feature = "buildtime_bindgen"))]
 pub use drm_version::*;
 
+#[cfg(not(feature = "buildtime_bindgen"))]
+mod sysfs_root;
+#[cfg(not(feature = "buildtime_bindgen"))]
+pub use sysfs_root::*;
+
 #[cfg(not(feature = "buildtime_bindgen"))]
 mod drm_mode;
 #[cfg(not(feature = "buildtime_bindgen"))]
 pub use drm_mode::*;
 
-/// Convert `errno` to `Err(i32)`
+/// Convert `errno` to `Err(Error::Query)`.
+/// `$func` is the name of the libdrm function, `$query` is the query/info ID passed to it.
 #[macro_export]
 macro_rules! query_error {
-    ($r: expr_2021) => {
+    ($r: expr_2021, $func: expr_2021) => {
+        if $r != 0 {
+            return Err($crate::Error::Query { func: $func, query: None, errno: $r });
+        }
+    };
+    ($r: expr_2021, $func: expr_2021, $query: expr_2021) => {
         if $r != 0 {
-            return Err($r);
+            return Err($crate::Error::Query { func: $func, query: Some($query as u32), errno: $r });
         }
     };
 }
@@ -155,18 +184,19 @@ pub(crate) fn get_min_max_from_dpm<
 >(
     sysfs_path: P,
     parse: fn(&str) -> Option<T>,
-) -> Option<[T; 2]> {
+) -> Result<[T; 2], Error> {
     let sysfs_path = sysfs_path.into();
-    let s = std::fs::read_to_string(sysfs_path).ok()?;
+    let s = error::read_sysfs(&sysfs_path)?;
     let mut lines = s.lines();
+    let parse = |line: &str| parse(line).ok_or_else(|| Error::parse(&sysfs_path, line));
 
-    let first = parse(lines.next()?)?;
+    let first = parse(lines.next().unwrap_or(""))?;
     let last = match lines.last() {
         Some(last) => parse(last)?,
-        None => return Some([first; 2]),
+        None => return Ok([first; 2]),
     };
 
-    Some([
+    Ok([
         std::cmp::min(first, last),
         std::cmp::max(first, last),
     ])
@@ -1,7 +1,8 @@
-use crate::AMDGPU;
+use crate::{AMDGPU, Error, SysfsRoot};
 
 /// PCI information (Domain, Bus, Device, Function)
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BUS_INFO {
     pub domain: u16,
     pub bus: u8,
@@ -14,27 +15,45 @@ use super::{LINK, STATUS};
 use std::path::PathBuf;
 
 impl BUS_INFO {
-    /// Get device sysfs path
+    /// Get device sysfs path, [SysfsRoot::global] is used as the root
     pub fn get_sysfs_path(&self) -> PathBuf {
-        PathBuf::from("/sys/bus/pci/devices/").join(self.to_string())
+        self.get_sysfs_path_with_root(&SysfsRoot::global())
+    }
+
+    /// Get device sysfs path under `root`
+    pub fn get_sysfs_path_with_root(&self, root: &SysfsRoot) -> PathBuf {
+        root.pci_device_path(self)
     }
 
     /// Get device hwmon path
-    pub fn get_hwmon_path(&self) -> Option<PathBuf> {
-        let base = self.get_sysfs_path().join("hwmon");
-        let entry = std::fs::read_dir(base).ok()?.next()?.ok()?;
+    pub fn get_hwmon_path(&self) -> Result<PathBuf, Error> {
+        self.get_hwmon_path_with_root(&SysfsRoot::global())
+    }
 
-        Some(entry.path())
+    /// Get device hwmon path under `root`
+    pub fn get_hwmon_path_with_root(&self, root: &SysfsRoot) -> Result<PathBuf, Error> {
+        let base = self.get_sysfs_path_with_root(root).join("hwmon");
+        let entry = std::fs::read_dir(&base)
+            .map_err(|e| Error::io(&base, e))?
+            .next()
+            .ok_or(Error::Unsupported { path: base.clone(), source: None })?
+            .map_err(|e| Error::io(&base, e))?;
+
+        Ok(entry.path())
     }
 
-    fn get_drm_path(&self, type_name: &str) -> std::io::Result<PathBuf> {
-        let base = PathBuf::from("/dev/dri");
+    fn get_drm_path(&self, root: &SysfsRoot, type_name: &str) -> Result<PathBuf, Error> {
+        let base = root.dri.clone();
 
         let name = format!("by-path/pci-{}-{type_name}", self);
-        let pci_by_path = std::fs::canonicalize(base.join(name));
+        let by_path = base.join(name);
+        let pci_by_path = std::fs::canonicalize(&by_path);
 
         pci_by_path.or_else(|e| {
-            std::fs::read_dir(self.get_sysfs_path().join("drm"))?
+            let drm_path = self.get_sysfs_path_with_root(root).join("drm");
+
+            std::fs::read_dir(&drm_path)
+                .map_err(|e| Error::io(&drm_path, e))?
                 .find_map(|v| {
                     let file_name = v.ok()?.file_name().into_string().ok()?;
                     if file_name.starts_with(type_name) {
@@ -43,46 +62,63 @@ impl BUS_INFO {
                         None
                     }
                 })
-                .ok_or(e)
+                .ok_or(Error::io(by_path, e))
         })
     }
 
     /// Get DRM render path
-    pub fn get_drm_render_path(&self) -> std::io::Result<PathBuf> {
-        self.get_drm_path("render")
+    pub fn get_drm_render_path(&self) -> Result<PathBuf, Error> {
+        self.get_drm_render_path_with_root(&SysfsRoot::global())
+    }
+
+    /// Get DRM render path under `root`
+    pub fn get_drm_render_path_with_root(&self, root: &SysfsRoot) -> Result<PathBuf, Error> {
+        self.get_drm_path(root, "render")
     }
 
     /// Get DRM card path
-    pub fn get_drm_card_path(&self) -> std::io::Result<PathBuf> {
-        self.get_drm_path("card")
+    pub fn get_drm_card_path(&self) -> Result<PathBuf, Error> {
+        self.get_drm_card_path_with_root(&SysfsRoot::global())
+    }
+
+    /// Get DRM card path under `root`
+    pub fn get_drm_card_path_with_root(&self, root: &SysfsRoot) -> Result<PathBuf, Error> {
+        self.get_drm_path(root, "card")
     }
 
     /// Get device debug path
-    pub fn get_debug_dri_path(&self) -> std::io::Result<PathBuf> {
+    pub fn get_debug_dri_path(&self) -> Result<PathBuf, Error> {
+        self.get_debug_dri_path_with_root(&SysfsRoot::global())
+    }
+
+    /// Get device debug path under `root`
+    pub fn get_debug_dri_path_with_root(&self, root: &SysfsRoot) -> Result<PathBuf, Error> {
         let s = format!("amdgpu dev={}", self);
+        let debug_dri_path = root.debug_dri_path();
 
-        std::fs::read_dir("/sys/kernel/debug/dri/")?
+        std::fs::read_dir(&debug_dri_path)
+            .map_err(|e| Error::io(&debug_dri_path, e))?
             .filter_map(|entry| Some(entry.ok()?.path()))
             .find(|path| {
                 let Ok(name) = std::fs::read_to_string(path.join("name")) else { return false };
 
                 name.starts_with(&s)
             })
-            .ok_or(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .ok_or(Error::Unsupported { path: debug_dri_path, source: None })
     }
 
     /// Get GPU maximum/minimum link speed/width from DPM
-    pub fn get_min_max_link_info_from_dpm(&self) -> Option<[LINK; 2]> {
+    pub fn get_min_max_link_info_from_dpm(&self) -> Result<[LINK; 2], Error> {
         LINK::get_min_max_link_info_from_dpm(self.get_sysfs_path())
     }
 
     /// Get GPU current link speed/width from DPM
-    pub fn get_current_link_info_from_dpm(&self) -> Option<LINK> {
+    pub fn get_current_link_info_from_dpm(&self) -> Result<LINK, Error> {
         LINK::get_current_link_info_from_dpm(self.get_sysfs_path())
     }
 
     /// Get GPU maximum link speed/width from sysfs
-    pub fn get_max_gpu_link(&self) -> Option<LINK> {
+    pub fn get_max_gpu_link(&self) -> Result<LINK, Error> {
         let mut tmp = self.get_system_pcie_port_sysfs_path();
 
         tmp.pop();
@@ -91,7 +127,7 @@ impl BUS_INFO {
     }
 
     /// Get system maximum link speed/width from sysfs
-    pub fn get_max_system_link(&self) -> Option<LINK> {
+    pub fn get_max_system_link(&self) -> Result<LINK, Error> {
         LINK::get_max_link(&self.get_system_pcie_port_sysfs_path())
     }
 
@@ -155,17 +191,17 @@ impl BUS_INFO {
     }
 
     /// Get GPU current link speed/width from sysfs
-    pub fn get_current_link_info(&self) -> Option<LINK> {
+    pub fn get_current_link_info(&self) -> Result<LINK, Error> {
         LINK::get_from_sysfs_with_status(self.get_sysfs_path(), STATUS::Current)
     }
 
     /// Get GPU maximum link speed/width from sysfs
-    pub fn get_max_link_info(&self) -> Option<LINK> {
+    pub fn get_max_link_info(&self) -> Result<LINK, Error> {
         LINK::get_from_sysfs_with_status(self.get_sysfs_path(), STATUS::Max)
     }
 
-    fn parse_id(&self, file_name: &str) -> Option<u32> {
-        let sysfs_path = self.get_sysfs_path();
+    fn parse_id(&self, root: &SysfsRoot, file_name: &str) -> Option<u32> {
+        let sysfs_path = self.get_sysfs_path_with_root(root);
         let id = std::fs::read_to_string(sysfs_path.join(file_name)).ok()?;
 
         u32::from_str_radix(id.trim_start_matches("0x").trim_end(), 16).ok()
@@ -173,19 +209,34 @@ impl BUS_INFO {
 
     /// Get PCI Device ID from sysfs
     pub fn get_device_id(&self) -> Option<u32> {
-        self.parse_id("device")
+        self.get_device_id_with_root(&SysfsRoot::global())
+    }
+
+    /// Get PCI Device ID from sysfs under `root`
+    pub fn get_device_id_with_root(&self, root: &SysfsRoot) -> Option<u32> {
+        self.parse_id(root, "device")
     }
 
     /// Get PCI Revision ID from sysfs
     pub fn get_revision_id(&self) -> Option<u32> {
-        self.parse_id("revision")
+        self.get_revision_id_with_root(&SysfsRoot::global())
+    }
+
+    /// Get PCI Revision ID from sysfs under `root`
+    pub fn get_revision_id_with_root(&self, root: &SysfsRoot) -> Option<u32> {
+        self.parse_id(root, "revision")
     }
 
     /// Find device marketing name from `amdgpu.ids`  
     /// Link: <https://gitlab.freedesktop.org/mesa/drm/-/blob/main/data/amdgpu.ids>
     pub fn find_device_name(&self) -> Option<String> {
-        let device_id = self.get_device_id()?;
-        let revision_id = self.get_revision_id()?;
+        self.find_device_name_with_root(&SysfsRoot::global())
+    }
+
+    /// Find device marketing name from `amdgpu.ids`, with the IDs read from sysfs under `root`
+    pub fn find_device_name_with_root(&self, root: &SysfsRoot) -> Option<String> {
+        let device_id = self.get_device_id_with_root(root)?;
+        let revision_id = self.get_revision_id_with_root(root)?;
 
         AMDGPU::find_device_name(device_id, revision_id)
     }
@@ -195,7 +246,12 @@ impl BUS_INFO {
     }
 
     pub fn check_if_device_is_active(&self) -> bool {
-        let path = self.get_sysfs_path().join("power/runtime_status");
+        self.check_if_device_is_active_with_root(&SysfsRoot::global())
+    }
+
+    /// Reads `power/runtime_status` under `root`, it does not wake up the device
+    pub fn check_if_device_is_active_with_root(&self, root: &SysfsRoot) -> bool {
+        let path = self.get_sysfs_path_with_root(root).join("power/runtime_status");
         let Ok(s) = std::fs::read_to_string(path) else { return false };
 
         s.starts_with("active")
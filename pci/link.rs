@@ -1,9 +1,12 @@
-use std::path::PathBuf;
+use crate::Error;
+use crate::error::read_sysfs;
+use std::path::{Path, PathBuf};
 
 const PCIE_DPM: &str = "pp_dpm_pcie";
 
 /// PCI link speed information
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LINK {
     pub r#gen: u8,
     pub width: u8,
@@ -15,19 +18,20 @@ impl LINK {
     pub fn get_from_sysfs_with_status<P: Into<PathBuf>>(
         sysfs_path: P,
         status: STATUS,
-    ) -> Option<Self> {
-        let base_path = sysfs_path.into();
-        let [s_speed, s_width] = status.to_sysfs_file_name().map(|name| {
-            let mut s = std::fs::read_to_string(base_path.join(name)).ok()?;
-            s.pop(); // trim `\n`
+    ) -> Result<Self, Error> {
+        Self::get_from_sysfs_files(&sysfs_path.into(), status)
+    }
 
-            Some(s)
-        });
+    fn get_from_sysfs_files(sysfs_path: &Path, status: STATUS) -> Result<Self, Error> {
+        let [speed_path, width_path] = status.to_sysfs_file_name().map(|name| sysfs_path.join(name));
+        let speed = read_sysfs(&speed_path)?;
+        let width = read_sysfs(&width_path)?;
+        let (speed, width) = (speed.trim_end(), width.trim_end());
 
-        let r#gen = Self::speed_to_gen(&s_speed?)?;
-        let width = s_width?.parse::<u8>().ok()?;
+        let r#gen = Self::speed_to_gen(speed).ok_or_else(|| Error::parse(&speed_path, speed))?;
+        let width = width.parse::<u8>().map_err(|_| Error::parse(&width_path, width))?;
 
-        Some(Self { r#gen, width })
+        Ok(Self { r#gen, width })
     }
 
     /// Convert PCIe speed str to PCIe gen
@@ -45,7 +49,7 @@ impl LINK {
         Some(r#gen)
     }
 
-    fn parse_dpm_line(s: &str) -> Option<Self> {
+    pub(crate) fn parse_dpm_line(s: &str) -> Option<Self> {
         let mut r#gen: Option<u8> = None;
         let mut width: Option<u8> = None;
 
@@ -81,31 +85,25 @@ impl LINK {
         Some(Self { r#gen: r#gen?, width: width? })
     }
 
-    pub fn get_min_max_link_info_from_dpm<P: Into<PathBuf>>(sysfs_path: P) -> Option<[LINK; 2]> {
+    pub fn get_min_max_link_info_from_dpm<P: Into<PathBuf>>(
+        sysfs_path: P,
+    ) -> Result<[LINK; 2], Error> {
         use crate::get_min_max_from_dpm;
 
         get_min_max_from_dpm(sysfs_path.into().join(PCIE_DPM), Self::parse_dpm_line)
     }
 
-    pub fn get_current_link_info_from_dpm<P: Into<PathBuf>>(sysfs_path: P) -> Option<LINK> {
-        let sysfs_path = sysfs_path.into();
-        let s = std::fs::read_to_string(sysfs_path.join(PCIE_DPM)).ok()?;
-        let cur = s.lines().find(|&line| line.ends_with(" *"))?;
+    pub fn get_current_link_info_from_dpm<P: Into<PathBuf>>(sysfs_path: P) -> Result<LINK, Error> {
+        let path = sysfs_path.into().join(PCIE_DPM);
+        let s = read_sysfs(&path)?;
 
-        Self::parse_dpm_line(cur)
+        s.lines()
+            .find(|&line| line.ends_with(" *"))
+            .and_then(Self::parse_dpm_line)
+            .ok_or_else(|| Error::parse(&path, s.trim_end()))
     }
 
-    pub(crate) fn get_max_link(sysfs_path: &PathBuf) -> Option<Self> {
-        let [s_speed, s_width] = STATUS::Max.to_sysfs_file_name().map(|name| {
-            let mut s = std::fs::read_to_string(sysfs_path.join(name)).ok()?;
-            s.pop(); // trim `\n`
-
-            Some(s)
-        });
-
-        let r#gen = Self::speed_to_gen(&s_speed?)?;
-        let width = s_width?.parse::<u8>().ok()?;
-
-        Some(Self { r#gen, width })
+    pub(crate) fn get_max_link(sysfs_path: &Path) -> Result<Self, Error> {
+        Self::get_from_sysfs_files(sysfs_path, STATUS::Max)
     }
 }
@@ -0,0 +1,69 @@
+/*
+    `Serialize` for the structs generated by bindgen is derived by `#[cfg_attr(feature = "serde", ...)]`,
+    added to the bindings by `build.rs`.
+*/
+
+use serde::ser::{Serialize, Serializer, SerializeTuple};
+
+/// serde implements `Serialize` only for arrays up to 32 elements,
+/// used for the larger array fields by `#[serde(serialize_with)]`
+pub(crate) fn serialize_array<S: Serializer, T: Serialize, const N: usize>(
+    array: &[T; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(N)?;
+
+    for v in array {
+        tuple.serialize_element(v)?;
+    }
+
+    tuple.end()
+}
+
+#[test]
+fn test_serialize_bindings() {
+    use crate::bindings::{gpu_metrics_v1_3, metrics_table_header};
+    use crate::AMDGPU::{GpuMetrics, PowerCap, PowerCapType};
+    use crate::PCI;
+
+    let mut m: gpu_metrics_v1_3 = unsafe { core::mem::zeroed() };
+
+    m.common_header = metrics_table_header {
+        structure_size: size_of::<gpu_metrics_v1_3>() as u16,
+        format_revision: 1,
+        content_revision: 3,
+    };
+    m.temperature_edge = 45;
+    m.temperature_hbm = [1, 2, 3, 4];
+
+    let raw = unsafe {
+        std::slice::from_raw_parts(
+            &m as *const gpu_metrics_v1_3 as *const u8,
+            size_of::<gpu_metrics_v1_3>(),
+        )
+    };
+    let json = serde_json::to_value(GpuMetrics::from_raw(raw)).unwrap();
+
+    assert_eq!(json["V1_3"]["common_header"]["content_revision"], 3);
+    assert_eq!(json["V1_3"]["temperature_edge"], 45);
+    assert_eq!(json["V1_3"]["temperature_hbm"], serde_json::json!([1, 2, 3, 4]));
+
+    /* packed, with arrays larger than 32 elements */
+    let ppt: crate::bindings::ppt::smu_v13_0_0_ppt::smu_13_0_0_powerplay_table =
+        unsafe { core::mem::zeroed() };
+    let json = serde_json::to_value(ppt).unwrap();
+
+    assert_eq!(json["overdrive_table"]["max"].as_array().unwrap().len(), 64);
+
+    let pci: PCI::BUS_INFO = "0000:0b:00.0".parse().unwrap();
+    let json = serde_json::to_string(&pci).unwrap();
+
+    assert_eq!(json, r#"{"domain":0,"bus":11,"dev":0,"func":0}"#);
+    assert_eq!(serde_json::from_str::<PCI::BUS_INFO>(&json).unwrap(), pci);
+
+    let cap: PowerCap = serde_json::from_str(
+        r#"{"type_":"PPT","current":200,"default":220,"min":0,"max":250}"#,
+    ).unwrap();
+
+    assert_eq!(cap.type_, PowerCapType::PPT);
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::PCI;
+
+static GLOBAL_ROOT: RwLock<Option<SysfsRoot>> = RwLock::new(None);
+
+/// Root directories used to find sysfs, debugfs and DRM device nodes.
+/// The default points to the running system (`/sys`, `/sys/kernel/debug`, `/dev/dri`).
+/// A captured directory tree can be used instead by [SysfsRoot::set_global],
+/// then every sysfs reader (e.g. [PCI::BUS_INFO::get_sysfs_path]) will read from that tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SysfsRoot {
+    pub sysfs: PathBuf,
+    pub debugfs: PathBuf,
+    pub dri: PathBuf,
+}
+
+impl Default for SysfsRoot {
+    fn default() -> Self {
+        Self::new("/")
+    }
+}
+
+impl SysfsRoot {
+    /// `root` is used as a replacement for `/`.
+    /// e.g. `SysfsRoot::new("./snapshot")` uses `./snapshot/sys`, `./snapshot/sys/kernel/debug`
+    /// and `./snapshot/dev/dri`.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref();
+
+        Self {
+            sysfs: root.join("sys"),
+            debugfs: root.join("sys/kernel/debug"),
+            dri: root.join("dev/dri"),
+        }
+    }
+
+    /// Returns the root used by sysfs readers that do not take a path.
+    pub fn global() -> Self {
+        match GLOBAL_ROOT.read() {
+            Ok(root) => root.clone().unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn set_global(root: Self) {
+        if let Ok(mut global) = GLOBAL_ROOT.write() {
+            *global = Some(root);
+        }
+    }
+
+    /// Revert to the running system (`/sys`, `/sys/kernel/debug`, `/dev/dri`)
+    pub fn reset_global() {
+        if let Ok(mut global) = GLOBAL_ROOT.write() {
+            *global = None;
+        }
+    }
+
+    /// `/sys/bus/pci/devices/`
+    pub fn pci_devices_path(&self) -> PathBuf {
+        self.sysfs.join("bus/pci/devices")
+    }
+
+    /// `/sys/bus/pci/drivers/amdgpu/`
+    pub fn amdgpu_driver_path(&self) -> PathBuf {
+        self.sysfs.join("bus/pci/drivers/amdgpu")
+    }
+
+    /// `/sys/kernel/debug/dri/`
+    pub fn debug_dri_path(&self) -> PathBuf {
+        self.debugfs.join("dri")
+    }
+
+    /// Get device sysfs path
+    pub fn pci_device_path(&self, pci_bus: &PCI::BUS_INFO) -> PathBuf {
+        self.pci_devices_path().join(pci_bus.to_string())
+    }
+}
+
+#[test]
+fn test_sysfs_root() {
+    let root = crate::test_util::TempDir::new("sysfs_root");
+    let sysfs_root = SysfsRoot::new(root.path());
+    let pci_bus: PCI::BUS_INFO = "0000:03:00.0".parse().unwrap();
+    let dev_path = sysfs_root.pci_device_path(&pci_bus);
+
+    std::fs::create_dir_all(sysfs_root.amdgpu_driver_path().join(pci_bus.to_string())).unwrap();
+    std::fs::create_dir_all(dev_path.join("hwmon/hwmon3")).unwrap();
+    std::fs::write(
+        dev_path.join("power_dpm_force_performance_level"),
+        "profile_peak\n",
+    ).unwrap();
+
+    assert_eq!(crate::AMDGPU::get_all_amdgpu_pci_bus_with_root(&sysfs_root), vec![pci_bus]);
+    assert_eq!(
+        pci_bus.get_hwmon_path_with_root(&sysfs_root).ok(),
+        Some(dev_path.join("hwmon/hwmon3")),
+    );
+    assert_eq!(
+        crate::AMDGPU::DpmForcedLevel::get_from_sysfs(&dev_path).ok(),
+        Some(crate::AMDGPU::DpmForcedLevel::ProfilePeak),
+    );
+
+    std::fs::write(dev_path.join("device"), "0x73bf\n").unwrap();
+    std::fs::write(dev_path.join("revision"), "0xc1\n").unwrap();
+    assert_eq!(
+        pci_bus.find_device_name_with_root(&sysfs_root).as_deref(),
+        Some("AMD Radeon RX 6800 XT"),
+    );
+
+    let debug_path = sysfs_root.debug_dri_path().join("1");
+    std::fs::create_dir_all(sysfs_root.debug_dri_path().join("0")).unwrap();
+    std::fs::create_dir_all(&debug_path).unwrap();
+    std::fs::write(
+        debug_path.join("name"),
+        "amdgpu dev=0000:03:00.0 unique=0000:03:00.0\n",
+    ).unwrap();
+    assert_eq!(pci_bus.get_debug_dri_path_with_root(&sysfs_root).ok(), Some(debug_path));
+}